@@ -0,0 +1,19 @@
+"""Checks adjacency() against a small graph's neighbour sets."""
+
+import networkx as nx
+import pymocd
+
+
+def test_adjacency_matches_neighbor_sets():
+    graph = nx.Graph([("a", "b"), ("b", "c"), ("c", "a"), ("c", "d")])
+    for cls in (pymocd.MOCD, pymocd.HpMocd):
+        adjacency = cls(graph).adjacency()
+        assert set(adjacency) == set(graph.nodes)
+        for node, neighbors in adjacency.items():
+            assert sorted(neighbors) == sorted(graph.neighbors(node))
+
+
+def test_adjacency_is_a_copy():
+    model = pymocd.MOCD([(0, 1), (1, 2)])
+    model.adjacency()[0].append(2)
+    assert model.adjacency() == {0: [1], 1: [0, 2], 2: [1]}
@@ -0,0 +1,18 @@
+"""Smoke test for annotate(), which writes communities back as node attributes."""
+
+import networkx as nx
+import pymocd
+
+
+def test_annotate_sets_community_attribute():
+    graph = nx.relabel_nodes(nx.ring_of_cliques(3, 4), lambda node: f"n{node}")
+    params = dict(num_gens=20, pop_size=30, seed=3)
+
+    for model in (pymocd.MOCD(graph, **params), pymocd.HpMocd(graph, **params)):
+        annotated = graph.copy()
+        annotated.add_node("unseen")
+        model.annotate(annotated)
+
+        communities = nx.get_node_attributes(annotated, "community")
+        assert communities == model.best()[0]
+        assert "unseen" not in communities
@@ -0,0 +1,28 @@
+"""Checks the PESA-II archive size and archive-based early stopping."""
+
+import networkx as nx
+import pymocd
+
+
+def test_front_never_exceeds_archive_size():
+    G = nx.ring_of_cliques(6, 6)
+    for archive_size in (1, 3, 5):
+        model = pymocd.MOCD(G, pop_size=40, num_gens=30, seed=7, archive_size=archive_size)
+        assert len(model.fit().front()) <= archive_size
+
+
+def test_zero_archive_size_is_rejected():
+    try:
+        pymocd.MOCD(nx.karate_club_graph(), archive_size=0)
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("archive_size=0 should raise ValueError")
+
+
+def test_archive_patience_stops_early():
+    G = nx.ring_of_cliques(4, 5)
+    kwargs = dict(pop_size=40, num_gens=200, seed=7)
+    model = pymocd.MOCD(G, archive_patience=3, **kwargs)
+    model.fit()
+    assert model.last_generations() < 200
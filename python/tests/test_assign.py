@@ -0,0 +1,29 @@
+"""Checks assigning an unseen node to a community of the fitted partition."""
+
+import networkx as nx
+import pymocd
+
+
+def test_bridging_node_joins_the_denser_side():
+    G = nx.ring_of_cliques(2, 6)
+    for cls in (pymocd.HpMocd, pymocd.MOCD):
+        model = cls(G, num_gens=40, pop_size=40, seed=5)
+        partition = model.run() if cls is pymocd.HpMocd else model.max_q()
+        left, right = partition[0], partition[11]
+        assert left != right
+
+        assert model.assign_node(100, [0, 1, 11]) == left
+        assert model.assign_node(100, [0, 10, 11]) == right
+        assert model.assign_node(100, []) == len(set(partition.values()))
+        assert 100 not in (model.run() if cls is pymocd.HpMocd else model.max_q())
+
+
+def test_rejects_known_node_and_unknown_neighbor():
+    model = pymocd.HpMocd(nx.karate_club_graph(), num_gens=5, pop_size=20, seed=1)
+    for node, neighbors in ((0, [1]), (100, [99])):
+        try:
+            model.assign_node(node, neighbors)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"assign_node({node}, {neighbors}) should raise")
@@ -0,0 +1,44 @@
+"""Checks that batch_max_q matches one max_q call per graph."""
+
+import networkx as nx
+import pymocd
+
+
+def test_batch_matches_individual_runs():
+    graphs = [nx.planted_partition_graph(3, 10, 0.6, 0.05, seed=s) for s in range(4)]
+    params = dict(num_gens=30, pop_size=40, seed=11)
+
+    batch = pymocd.batch_max_q(graphs, num_threads=2, **params)
+    single = [pymocd.MOCD(graph, **params).max_q() for graph in graphs]
+
+    assert batch == single
+
+
+def test_keyword_arguments_reach_mocd():
+    graphs = [nx.karate_club_graph(), nx.les_miserables_graph()]
+    params = dict(num_gens=20, pop_size=30, seed=4, refine=True, round_objectives=6)
+
+    batch = pymocd.batch_max_q(graphs, **params)
+    assert batch == [pymocd.MOCD(graph, **params).max_q() for graph in graphs]
+
+    try:
+        pymocd.batch_max_q(graphs, pop_sizes=30)
+    except TypeError:
+        pass
+    else:
+        raise AssertionError("unknown keywords should raise TypeError")
+
+
+def test_per_graph_keywords_are_rejected():
+    graphs = [nx.karate_club_graph()]
+    for key, value in [("initial_partition", {}), ("must_link", [(0, 1)])]:
+        try:
+            pymocd.batch_max_q(graphs, **{key: value})
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"{key} should raise ValueError")
+
+
+def test_empty_batch():
+    assert pymocd.batch_max_q([]) == []
@@ -0,0 +1,63 @@
+"""Checks that best() agrees with max_q/run and with fitness."""
+
+import math
+
+import networkx as nx
+import pymocd
+
+
+def _graph():
+    return nx.planted_partition_graph(3, 12, 0.7, 0.03, seed=6)
+
+
+def test_best_matches_max_q_and_fitness():
+    graph = _graph()
+    params = dict(num_gens=30, pop_size=40, seed=8)
+
+    partition, q = pymocd.MOCD(graph, **params).best()
+    assert partition == pymocd.MOCD(graph, **params).max_q()
+    assert math.isclose(q, pymocd.fitness(graph, partition), abs_tol=1e-9)
+
+    partition, q = pymocd.HpMocd(graph, **params).best()
+    assert partition == pymocd.HpMocd(graph, **params).run()
+    assert math.isclose(q, pymocd.fitness(graph, partition), abs_tol=1e-9)
+
+
+def test_cocomi_best_matches_run():
+    graph = _graph()
+    partition, q = pymocd.CoCoMi(graph, seed=3).best()
+    communities = pymocd.CoCoMi(graph, seed=3).run()
+
+    for community, nodes in enumerate(communities):
+        assert all(partition[node] == community for node in nodes)
+    assert math.isclose(q, pymocd.fitness(graph, partition), abs_tol=1e-9)
+
+
+def test_cocomi_best_keeps_node_labels():
+    graph = nx.relabel_nodes(_graph(), lambda node: f"n{node}")
+    partition, q = pymocd.CoCoMi(graph, seed=3).best()
+    communities = pymocd.CoCoMi(graph, seed=3).run()
+
+    assert set(partition) == set(graph)
+    for community, nodes in enumerate(communities):
+        assert all(partition[node] == community for node in nodes)
+    assert math.isclose(q, pymocd.fitness(graph, partition), abs_tol=1e-9)
+
+
+def test_cocomi_best_scores_weighted_graphs():
+    graph = _graph()
+    for u, v in graph.edges():
+        graph[u][v]["weight"] = 1.0 + (u + v) % 3
+    partition, q = pymocd.CoCoMi(graph, seed=3).best()
+
+    assert math.isclose(q, pymocd.fitness(graph, partition), abs_tol=1e-9)
+    assert math.isclose(
+        q, nx.community.modularity(graph, _communities(partition)), abs_tol=1e-9
+    )
+
+
+def _communities(partition):
+    communities = {}
+    for node, community in partition.items():
+        communities.setdefault(community, set()).add(node)
+    return list(communities.values())
@@ -0,0 +1,34 @@
+"""Checks Barber's bipartite modularity in fitness and the optimizers."""
+
+import networkx as nx
+import pymocd
+
+# Users 0-3 and items 4-7: two bicliques joined by the edge 1-6
+EDGES = [(0, 4), (0, 5), (1, 4), (1, 5), (2, 6), (2, 7), (3, 6), (3, 7), (1, 6)]
+TYPES = {node: int(node >= 4) for node in range(8)}
+PARTITION = {0: 0, 1: 0, 4: 0, 5: 0, 2: 1, 3: 1, 6: 1, 7: 1}
+
+
+def test_known_bimodularity():
+    # m = 9, 8 edges inside, side degrees (5, 4) and (4, 5): Q = 8/9 - 40/81
+    q = pymocd.fitness(EDGES, PARTITION, bipartite=True, node_types=TYPES)
+    assert abs(q - 32 / 81) < 1e-12
+    assert abs(pymocd.fitness(EDGES, PARTITION) - q) > 1e-3
+
+
+def test_optimizers_find_the_bicliques():
+    graph = nx.Graph(EDGES)
+    for cls in (pymocd.MOCD, pymocd.HpMocd):
+        model = cls(graph, num_gens=30, pop_size=30, seed=1, bipartite=True, node_types=TYPES)
+        partition = model.max_q() if cls is pymocd.MOCD else model.run()
+        q = pymocd.fitness(EDGES, partition, bipartite=True, node_types=TYPES)
+        assert q >= 32 / 81 - 1e-9
+
+
+def test_types_must_cover_every_node():
+    try:
+        pymocd.fitness(EDGES, PARTITION, bipartite=True, node_types={0: 0})
+    except ValueError as err:
+        assert "node_types" in str(err)
+    else:
+        raise AssertionError("expected ValueError")
@@ -0,0 +1,36 @@
+"""Checks that repeated calls reuse the cached front until reset()."""
+
+import networkx as nx
+import pymocd
+
+
+def _graph():
+    return nx.planted_partition_graph(3, 12, 0.7, 0.03, seed=6)
+
+
+def test_repeated_calls_reuse_the_front():
+    graph = _graph()
+    params = dict(num_gens=30, pop_size=40)
+
+    model = pymocd.MOCD(graph, **params)
+    first = model.max_q()
+    assert model.max_q() == first
+    assert model.best()[0] == first
+    assert model.history()
+
+    model = pymocd.HpMocd(graph, **params)
+    first = model.run()
+    assert model.run() == first
+    assert model.best()[0] == first
+
+
+def test_reset_drops_the_cache():
+    graph = _graph()
+    params = dict(num_gens=30, pop_size=40)
+    for model in (pymocd.MOCD(graph, **params), pymocd.HpMocd(graph, **params)):
+        model.generate_pareto_front()
+        assert model.history()
+        model.reset()
+        assert model.history() == []
+        model.generate_pareto_front()
+        assert model.history()
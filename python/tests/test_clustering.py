@@ -0,0 +1,24 @@
+"""Checks triangle_count() and average_clustering() against NetworkX."""
+
+import math
+
+import networkx as nx
+import pymocd
+
+
+def test_matches_networkx():
+    graph = nx.karate_club_graph()
+    for cls in (pymocd.MOCD, pymocd.HpMocd):
+        model = cls(graph)
+        assert model.triangle_count() == sum(nx.triangles(graph).values()) // 3
+        assert math.isclose(model.average_clustering(), nx.average_clustering(graph))
+
+
+def test_triangle_and_star():
+    triangle = pymocd.MOCD([(0, 1), (1, 2), (2, 0)])
+    assert triangle.triangle_count() == 1
+    assert triangle.average_clustering() == 1.0
+
+    star = pymocd.MOCD([(0, leaf) for leaf in range(1, 6)])
+    assert star.triangle_count() == 0
+    assert star.average_clustering() == 0.0
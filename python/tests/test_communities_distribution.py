@@ -0,0 +1,15 @@
+"""Checks num_communities_distribution against the front it summarizes."""
+
+import networkx as nx
+import pymocd
+
+
+def test_counts_align_with_front():
+    graph = nx.karate_club_graph()
+    for cls in (pymocd.MOCD, pymocd.HpMocd):
+        model = cls(graph, num_gens=10, pop_size=20, seed=2)
+        front = model.generate_pareto_front()
+        counts = model.num_communities_distribution()
+        assert len(counts) == len(front)
+        for (partition, _), count in zip(front, counts):
+            assert count == len(set(partition.values()))
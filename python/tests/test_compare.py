@@ -0,0 +1,31 @@
+"""Checks that `compare` agrees with the standalone partition metrics."""
+
+import random
+
+import pymocd
+
+
+def test_compare_matches_standalone_functions():
+    rng = random.Random(8)
+    truth = {node: node // 10 for node in range(60)}
+    for _ in range(5):
+        pred = {node: rng.randrange(7) for node in range(60)}
+        scores = pymocd.compare(pred, truth)
+        assert set(scores) == {"nmi", "ari", "vi", "jaccard"}
+        assert scores["nmi"] == pymocd.nmi(pred, truth)
+        assert scores["ari"] == pymocd.adjusted_rand_index(pred, truth)
+        assert scores["vi"] == pymocd.variation_of_information(pred, truth)
+        assert scores["jaccard"] == pymocd.partition_jaccard(pred, truth)
+
+
+def test_identical_partitions():
+    a = {"x": 0, "y": 0, "z": 1}
+    b = {"x": 5, "y": 5, "z": 2}
+    assert pymocd.compare(a, b) == {"nmi": 1.0, "ari": 1.0, "vi": 0.0, "jaccard": 1.0}
+
+
+def test_partition_jaccard_split_cluster():
+    a = {0: 0, 1: 0, 2: 0, 3: 0, 4: 1, 5: 1}
+    b = {0: 0, 1: 0, 2: 2, 3: 2, 4: 1, 5: 1}
+    assert abs(pymocd.partition_jaccard(a, b) - 3 / 7) < 1e-12
+    assert pymocd.partition_jaccard(a, a) == 1.0
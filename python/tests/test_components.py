@@ -0,0 +1,31 @@
+"""Checks component counting and the disconnected-graph warning."""
+
+import warnings
+
+import networkx as nx
+import pymocd
+
+
+def _two_components():
+    return nx.disjoint_union(nx.complete_graph(4), nx.complete_graph(5))
+
+
+def test_num_components():
+    for cls in (pymocd.HpMocd, pymocd.MOCD):
+        assert cls(_two_components()).num_components == 2
+        assert cls(nx.complete_graph(4)).num_components == 1
+
+
+def test_warns_only_when_debugging():
+    for cls in (pymocd.HpMocd, pymocd.MOCD):
+        with warnings.catch_warnings(record=True) as caught:
+            warnings.simplefilter("always")
+            cls(_two_components())
+            cls(nx.complete_graph(4), debug_level=1)
+        assert not caught
+
+        with warnings.catch_warnings(record=True) as caught:
+            warnings.simplefilter("always")
+            cls(_two_components(), debug_level=1)
+        assert len(caught) == 1
+        assert "2 connected components" in str(caught[0].message)
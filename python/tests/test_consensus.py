@@ -0,0 +1,25 @@
+"""Checks the co-association consensus of MOCD runs."""
+
+import networkx as nx
+import pymocd
+
+
+def test_single_run_is_max_q():
+    graph = nx.karate_club_graph()
+    model = pymocd.MOCD(graph, num_gens=15, pop_size=20, seed=6)
+    assert model.consensus(1) == model.max_q()
+
+
+def test_consensus_covers_every_node():
+    graph = nx.planted_partition_graph(3, 10, 0.9, 0.02, seed=1)
+    partition = pymocd.MOCD(graph, num_gens=20, pop_size=30, seed=2).consensus(4)
+    assert set(partition) == set(graph.nodes)
+
+
+def test_zero_runs_is_rejected():
+    try:
+        pymocd.MOCD(nx.karate_club_graph()).consensus(0)
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("expected ValueError")
@@ -0,0 +1,19 @@
+"""Checks core_numbers against networkx and its use to extract a k-core."""
+
+import networkx as nx
+import pymocd
+
+
+def test_matches_networkx():
+    graph = nx.karate_club_graph()
+    for model in (pymocd.MOCD(graph), pymocd.HpMocd(graph)):
+        assert model.core_numbers() == nx.core_number(graph)
+
+
+def test_triangle_with_tail_keeps_labels():
+    graph = nx.Graph([("a", "b"), ("b", "c"), ("a", "c"), ("c", "tail")])
+    cores = pymocd.HpMocd(graph).core_numbers()
+    assert cores == {"a": 2, "b": 2, "c": 2, "tail": 1}
+
+    two_core = graph.subgraph(node for node, k in cores.items() if k >= 2)
+    assert pymocd.HpMocd(two_core).num_nodes == 3
@@ -0,0 +1,35 @@
+"""Checks that both optimizers accept every crossover_type and reject unknown ones."""
+
+import networkx as nx
+import pymocd
+
+
+def test_every_type_fits():
+    graph = nx.karate_club_graph()
+    for kind in ("ensemble", "uniform", "one_point", "two_point"):
+        params = dict(num_gens=15, pop_size=20, seed=8, crossover_type=kind)
+        for model in (pymocd.MOCD(graph, **params), pymocd.HpMocd(graph, **params)):
+            partition, _ = model.best()
+            assert set(partition) == set(graph.nodes)
+
+
+def test_default_matches_the_native_operator():
+    graph = nx.karate_club_graph()
+    params = dict(num_gens=15, pop_size=20, seed=8)
+    assert (
+        pymocd.MOCD(graph, **params).max_q()
+        == pymocd.MOCD(graph, crossover_type="two_point", **params).max_q()
+    )
+    assert (
+        pymocd.HpMocd(graph, **params).run()
+        == pymocd.HpMocd(graph, crossover_type="ensemble", **params).run()
+    )
+
+
+def test_unknown_type_is_rejected():
+    try:
+        pymocd.MOCD(nx.complete_graph(5), crossover_type="three_point")
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("an unknown crossover_type should raise ValueError")
@@ -0,0 +1,45 @@
+"""Checks the degree-corrected block model variant of modularity."""
+
+import math
+
+import networkx as nx
+import pymocd
+
+
+def _hub_graph():
+    # Hub 0 linked to the triangles 1-2-3 and 4-5-6
+    graph = nx.star_graph(6)
+    graph.add_edges_from([(1, 2), (2, 3), (1, 3), (4, 5), (5, 6), (4, 6)])
+    return graph
+
+
+def test_variants_differ_on_a_hub():
+    graph = _hub_graph()
+    partition = {0: 0, 1: 0, 2: 0, 3: 0, 4: 1, 5: 1, 6: 1}
+
+    newman = pymocd.fitness(graph, partition)
+    dcsbm = pymocd.fitness(graph, partition, modularity_variant="dcsbm")
+    assert math.isclose(newman, 7 / 32)
+    assert dcsbm > newman
+
+    intra, inter = pymocd.fitness_components(graph, partition, modularity_variant="dcsbm")
+    assert math.isclose(1 - intra - inter, dcsbm)
+
+
+def test_optimizers_accept_the_variant():
+    graph = _hub_graph()
+    params = dict(num_gens=20, pop_size=30, seed=1, modularity_variant="dcsbm")
+    for model in (pymocd.MOCD(graph, **params), pymocd.HpMocd(graph, **params)):
+        partition, q = model.best()
+        assert math.isclose(
+            q, pymocd.fitness(graph, partition, modularity_variant="dcsbm"), abs_tol=1e-9
+        )
+
+
+def test_unknown_variant_is_rejected():
+    try:
+        pymocd.fitness(_hub_graph(), {node: 0 for node in range(7)}, modularity_variant="sbm")
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("expected ValueError")
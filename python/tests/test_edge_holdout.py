@@ -0,0 +1,21 @@
+"""Checks the proportions and coverage of edge_holdout splits."""
+
+import networkx as nx
+import pymocd
+
+
+def test_split_proportions():
+    graph = nx.karate_club_graph()
+    train, test = pymocd.edge_holdout(graph, 0.2, seed=1)
+
+    assert len(train) + len(test) == graph.number_of_edges()
+    assert len(test) == round(0.2 * graph.number_of_edges())
+    assert {u for u, v, _ in train} | {v for u, v, _ in train} == set(graph.nodes)
+    assert (train, test) == pymocd.edge_holdout(graph, 0.2, seed=1)
+
+
+def test_train_edges_fit_a_model():
+    graph = nx.karate_club_graph()
+    train, _ = pymocd.edge_holdout(graph, 0.1, seed=3)
+    partition = pymocd.HpMocd(train, num_gens=10, pop_size=20, seed=3).run()
+    assert set(partition) == set(graph.nodes)
@@ -0,0 +1,84 @@
+"""Checks that add_edge/remove_edge update the graph and drop the cached front."""
+
+import networkx as nx
+import pymocd
+
+
+def _estimators(graph):
+    params = dict(num_gens=20, pop_size=30, seed=4)
+    return pymocd.MOCD(graph, **params), pymocd.HpMocd(graph, **params)
+
+
+def test_num_edges_follows_mutations():
+    for model in _estimators(nx.path_graph(4)):
+        model.add_edge(0, 3)
+        model.add_edge(3, 0)
+        assert model.num_edges == 4
+        model.remove_edge(1, 2)
+        assert model.num_edges == 3
+        try:
+            model.remove_edge(1, 2)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError("removing a missing edge should raise ValueError")
+
+
+def test_isolated_and_new_nodes():
+    graph = nx.relabel_nodes(nx.path_graph(3), {0: "a", 1: "b", 2: "c"})
+    for model in _estimators(graph):
+        model.remove_edge("a", "b")
+        assert model.num_nodes == 3
+        assert model.degree_sequence() == [0, 1, 1]
+
+        model.add_edge("c", "d")
+        assert model.num_nodes == 4
+        assert set(model.best()[0]) == {"a", "b", "c", "d"}
+
+
+def test_mutation_drops_cached_front():
+    for model in _estimators(nx.ring_of_cliques(3, 4)):
+        model.fit()
+        model.add_edge(0, 6)
+        try:
+            model.front()
+        except RuntimeError:
+            pass
+        else:
+            raise AssertionError("front() should need a new fit after add_edge")
+        partition = model.best()[0]
+        assert len(partition) == 12
+
+
+def test_added_edges_follow_constructor_rules():
+    base = list(nx.ring_of_cliques(3, 4).edges)
+    extra = [(0, 0), (0, 1), (5, 5), (1, 0)]
+    for rules in (
+        dict(drop_self_loops=True),
+        dict(drop_self_loops=False),
+        dict(drop_self_loops=True, parallel_as_weight=True),
+    ):
+        params = dict(num_gens=20, pop_size=30, seed=4, **rules)
+        for cls in (pymocd.MOCD, pymocd.HpMocd):
+            grown = cls(base, **params)
+            for u, v in extra:
+                grown.add_edge(u, v)
+            built = cls(base + extra, **params)
+            assert grown.num_edges == built.num_edges, rules
+            assert grown.degree_sequence() == built.degree_sequence(), rules
+            assert grown.best() == built.best(), rules
+
+
+def test_bipartite_edges_need_typed_nodes():
+    types = {node: node % 2 for node in range(4)}
+    for cls in (pymocd.MOCD, pymocd.HpMocd):
+        model = cls([(0, 1), (2, 3)], bipartite=True, node_types=types)
+        model.add_edge(0, 3)
+        assert model.num_edges == 3
+        try:
+            model.add_edge(0, 4)
+        except ValueError as err:
+            assert "node_types" in str(err)
+        else:
+            raise AssertionError("an untyped node should raise ValueError")
+        assert model.num_nodes == 4
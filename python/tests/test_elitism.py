@@ -0,0 +1,22 @@
+"""Checks that elitism keeps the best Q from falling between generations."""
+
+import networkx as nx
+import pymocd
+
+
+def test_history_is_non_decreasing():
+    graph = nx.karate_club_graph()
+    params = dict(num_gens=30, pop_size=20, seed=5, patience=None)
+    for model in (
+        pymocd.MOCD(graph, archive_size=2, **params),
+        pymocd.HpMocd(graph, **params),
+    ):
+        model.fit()
+        history = model.history()
+        assert len(history) == 30
+        assert all(b >= a for a, b in zip(history, history[1:]))
+
+
+def test_elitism_can_be_disabled():
+    partition = pymocd.HpMocd(nx.karate_club_graph(), num_gens=10, elitism=False, seed=1).run()
+    assert len(partition) == 34
@@ -0,0 +1,36 @@
+"""Checks that graphs without edges give trivial results instead of failing."""
+
+import networkx as nx
+import pymocd
+
+# A dropped self-loop still adds its node, which gives edgeless graphs with nodes
+GRAPHS = {
+    "no edges": ([], {}),
+    "one node": ([(0, 0)], {0: 0}),
+    "two disconnected nodes": ([(0, 0), (1, 1)], {0: 0, 1: 1}),
+}
+
+
+def test_max_q_returns_singletons():
+    for name, (edges, expected) in GRAPHS.items():
+        assert pymocd.MOCD(edges).max_q() == expected, name
+        assert pymocd.HpMocd(edges).run() == expected, name
+    assert pymocd.HpMocd(nx.Graph()).run() == {}
+
+
+def test_networkx_isolated_nodes_are_kept():
+    for n in range(4):
+        expected = {node: node for node in range(n)}
+        assert pymocd.MOCD(nx.empty_graph(n)).max_q() == expected, n
+        assert pymocd.HpMocd(nx.empty_graph(n)).run() == expected, n
+
+    graph = nx.Graph([("a", "b")])
+    graph.add_node("c")
+    for partition in (pymocd.MOCD(graph).max_q(), pymocd.HpMocd(graph).run()):
+        assert set(partition) == {"a", "b", "c"}
+        assert partition["a"] == partition["b"]
+
+
+def test_fitness_is_zero():
+    assert pymocd.fitness([], {}) == 0.0
+    assert pymocd.fitness(nx.Graph(), {}, alpha=0.5) == 0.0
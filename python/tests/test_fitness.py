@@ -0,0 +1,70 @@
+"""Checks that fitness_components recombines into the scalar fitness, that edge
+lists score like the NetworkX graphs they come from, and that self-loops follow
+NetworkX's modularity convention.
+"""
+
+import math
+
+import networkx as nx
+import pymocd
+
+
+def test_components_recombine_into_fitness():
+    graph = nx.planted_partition_graph(3, 20, 0.5, 0.05, seed=4)
+    partition = {node: node // 20 for node in graph.nodes}
+
+    for directed in (False, True):
+        intra, inter = pymocd.fitness_components(graph, partition, directed=directed)
+        q = pymocd.fitness(graph, partition, directed=directed)
+        assert math.isclose(1 - intra - inter, q, abs_tol=1e-12)
+
+
+def test_default_weights_match_unweighted_fitness():
+    graph = nx.planted_partition_graph(3, 20, 0.5, 0.05, seed=4)
+    partition = {node: node // 20 for node in graph.nodes}
+    intra, inter = pymocd.fitness_components(graph, partition)
+
+    assert pymocd.fitness(graph, partition, alpha=1.0, beta=1.0) == pymocd.fitness(
+        graph, partition
+    )
+    assert math.isclose(
+        pymocd.fitness(graph, partition, alpha=0.5, beta=2.0),
+        1 - 0.5 * intra - 2.0 * inter,
+        abs_tol=1e-12,
+    )
+
+
+def test_higher_resolution_splits_borderline_community():
+    # Two triangles tied by three edges, hanging off a 5-clique by a single edge
+    graph = nx.Graph([(0, 1), (1, 2), (0, 2), (3, 4), (4, 5), (3, 5),
+                      (2, 3), (0, 4), (1, 5), (2, 6)])
+    graph.add_edges_from(nx.complete_graph(range(6, 11)).edges)
+    merged = {node: int(node >= 6) for node in graph.nodes}
+    split = {node: min(node // 3, 2) for node in graph.nodes}
+
+    assert pymocd.fitness(graph, merged) > pymocd.fitness(graph, split)
+    assert pymocd.fitness(graph, merged, resolution=2.0) < pymocd.fitness(
+        graph, split, resolution=2.0
+    )
+
+
+def test_edge_list_matches_networkx_graph():
+    graph = nx.planted_partition_graph(3, 20, 0.5, 0.05, seed=4)
+    partition = {node: node // 20 for node in graph.nodes}
+    edges = list(graph.edges)
+
+    assert pymocd.fitness(edges, partition) == pymocd.fitness(graph, partition)
+    assert pymocd.fitness_components(edges, partition) == pymocd.fitness_components(
+        graph, partition
+    )
+
+
+def test_weighted_self_loops_match_networkx():
+    graph = nx.Graph([(0, 1), (1, 2), (0, 2), (2, 3), (3, 4)])
+    graph.add_edge(3, 3, weight=2.0)
+    graph.add_edge(0, 0, weight=0.5)
+    partition = {0: 0, 1: 0, 2: 0, 3: 1, 4: 1}
+    communities = [{0, 1, 2}, {3, 4}]
+
+    expected = nx.community.modularity(graph, communities)
+    assert math.isclose(pymocd.fitness(graph, partition), expected, abs_tol=1e-12)
@@ -0,0 +1,30 @@
+"""Checks fitness_delta, the change in Q of a fixed partition between two graphs."""
+
+import math
+
+import networkx as nx
+import pymocd
+
+
+def test_inter_community_edge_lowers_q():
+    graph_a = nx.ring_of_cliques(2, 4)
+    graph_b = graph_a.copy()
+    graph_b.add_edge(2, 6)
+    partition = {node: node // 4 for node in graph_a}
+
+    delta = pymocd.fitness_delta(graph_a, graph_b, partition)
+    expected = pymocd.fitness(graph_a, partition) - pymocd.fitness(graph_b, partition)
+    assert delta > 0
+    assert math.isclose(delta, expected)
+    assert pymocd.fitness_delta(graph_a, graph_a, partition) == 0.0
+
+
+def test_partition_must_cover_both_graphs():
+    graph_a = nx.path_graph(4)
+    graph_b = nx.path_graph(5)
+    try:
+        pymocd.fitness_delta(graph_a, graph_b, {node: 0 for node in range(4)})
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("a node missing from the partition should raise ValueError")
@@ -0,0 +1,40 @@
+"""Checks that from_file builds the same graph as the edge list it holds."""
+
+import os
+import tempfile
+
+import networkx as nx
+import pymocd
+
+
+def _write(contents):
+    handle, path = tempfile.mkstemp(suffix=".csv")
+    with os.fdopen(handle, "w") as f:
+        f.write(contents)
+    return path
+
+
+def test_from_file_matches_edge_list():
+    graph = nx.planted_partition_graph(2, 10, 0.8, 0.05, seed=3)
+    lines = ["# source,target"] + [f"{u},{v}" for u, v in graph.edges]
+    path = _write("\n".join(lines) + "\n")
+    try:
+        for cls in (pymocd.MOCD, pymocd.HpMocd):
+            model = cls.from_file(path, delimiter=",", num_gens=10, seed=1)
+            reference = cls(list(graph.edges), num_gens=10, seed=1)
+            assert model.num_nodes == reference.num_nodes
+            assert model.num_edges == reference.num_edges
+    finally:
+        os.remove(path)
+
+
+def test_malformed_line_reports_its_number():
+    path = _write("0 1\n# comment\n1 x\n")
+    try:
+        pymocd.HpMocd.from_file(path)
+    except ValueError as err:
+        assert "line 3" in str(err)
+    else:
+        raise AssertionError("expected ValueError")
+    finally:
+        os.remove(path)
@@ -0,0 +1,29 @@
+"""Checks front_to_csv() against front() on a fitted estimator."""
+
+import csv
+import io
+import math
+
+import networkx as nx
+import pymocd
+
+
+def test_one_row_per_front_solution():
+    graph = nx.karate_club_graph()
+    for cls in (pymocd.MOCD, pymocd.HpMocd):
+        model = cls(graph, seed=1, num_gens=20).fit()
+        rows = list(csv.DictReader(io.StringIO(model.front_to_csv())))
+        front = model.front()
+        assert len(rows) == len(front)
+        for row, (partition, (intra, inter)) in zip(rows, front):
+            assert math.isclose(float(row["intra"]), intra)
+            assert math.isclose(float(row["inter"]), inter)
+            assert math.isclose(float(row["modularity"]), 1 - intra - inter)
+            assert int(row["num_communities"]) == len(set(partition.values()))
+
+
+def test_unfitted_estimator_fits_first():
+    graph = nx.karate_club_graph()
+    for cls in (pymocd.MOCD, pymocd.HpMocd):
+        unfitted = cls(graph, seed=1, num_gens=20).front_to_csv()
+        assert unfitted == cls(graph, seed=1, num_gens=20).fit().front_to_csv()
@@ -0,0 +1,27 @@
+"""Checks front_modularities against the front and the max-Q solution."""
+
+import math
+
+import networkx as nx
+import pymocd
+
+
+def test_max_matches_the_max_q_solution():
+    graph = nx.karate_club_graph()
+    params = dict(num_gens=30, pop_size=40, seed=6)
+    for model, select in (
+        (pymocd.MOCD(graph, **params), "max_q"),
+        (pymocd.HpMocd(graph, **params), "run"),
+    ):
+        scores = model.front_modularities()
+        assert len(scores) == len(model.generate_pareto_front())
+
+        q = pymocd.fitness(graph, getattr(model, select)())
+        assert math.isclose(max(scores), q, abs_tol=1e-9)
+
+
+def test_scores_follow_the_front_order():
+    graph = nx.karate_club_graph()
+    model = pymocd.HpMocd(graph, num_gens=20, pop_size=30, seed=3)
+    for (partition, _), score in zip(model.generate_pareto_front(), model.front_modularities()):
+        assert math.isclose(score, pymocd.fitness(graph, partition), abs_tol=1e-9)
@@ -0,0 +1,21 @@
+"""Checks the random graph generators."""
+
+import pymocd
+
+
+def test_erdos_renyi_edge_count():
+    n, p = 200, 0.1
+    expected = p * n * (n - 1) / 2
+    edges = pymocd.gen_erdos_renyi(n, p, seed=1)
+    assert abs(len(edges) - expected) < 0.1 * expected
+    assert edges == pymocd.gen_erdos_renyi(n, p, seed=1)
+
+
+def test_barabasi_albert_edge_count():
+    n, m = 100, 3
+    edges = pymocd.gen_barabasi_albert(n, m, seed=2)
+    assert len(edges) == m * (n - m)
+
+    # The edge lists feed straight into the estimators
+    partition = pymocd.HpMocd(edges, num_gens=10, pop_size=20, seed=2).run()
+    assert len(partition) == n
@@ -0,0 +1,42 @@
+"""Checks that from_graphml reads the same graph NetworkX writes."""
+
+import os
+import tempfile
+
+import networkx as nx
+import pymocd
+
+
+def _write(contents):
+    handle, path = tempfile.mkstemp(suffix=".graphml")
+    with os.fdopen(handle, "w") as f:
+        f.write(contents)
+    return path
+
+
+def test_from_graphml_matches_networkx():
+    graph = nx.relabel_nodes(nx.karate_club_graph(), lambda node: f"n{node}")
+    handle, path = tempfile.mkstemp(suffix=".graphml")
+    os.close(handle)
+    nx.write_graphml(graph, path)
+    try:
+        for cls in (pymocd.MOCD, pymocd.HpMocd):
+            model = cls.from_graphml(path, num_gens=10, seed=1)
+            assert model.num_nodes == graph.number_of_nodes()
+            assert model.num_edges == graph.number_of_edges()
+            for partition, _ in model.fit().front():
+                assert set(partition) == set(graph.nodes)
+    finally:
+        os.remove(path)
+
+
+def test_malformed_edge_is_named():
+    path = _write('<graphml><graph><node id="a"/><edge source="a"/></graph></graphml>')
+    try:
+        pymocd.MOCD.from_graphml(path)
+    except ValueError as err:
+        assert "edge 1" in str(err)
+    else:
+        raise AssertionError("expected ValueError")
+    finally:
+        os.remove(path)
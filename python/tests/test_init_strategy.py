@@ -0,0 +1,24 @@
+"""Checks the label propagation init_strategy against random initialization."""
+
+import networkx as nx
+import pymocd
+
+
+def test_label_propagation_leads_early():
+    graph = nx.ring_of_cliques(8, 5)
+    params = dict(num_gens=10, pop_size=40, seed=3, patience=None)
+    for cls in (pymocd.MOCD, pymocd.HpMocd):
+        random = cls(graph, **params)
+        random.fit()
+        propagated = cls(graph, init_strategy="label_propagation", **params)
+        propagated.fit()
+        assert propagated.history()[-1] >= random.history()[-1]
+
+
+def test_unknown_strategy_is_rejected():
+    try:
+        pymocd.HpMocd(nx.karate_club_graph(), init_strategy="spectral")
+    except ValueError as err:
+        assert "init_strategy" in str(err)
+    else:
+        raise AssertionError("expected ValueError")
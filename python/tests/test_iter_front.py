@@ -0,0 +1,22 @@
+"""Checks that iter_front lazily yields the same solutions as generate_pareto_front."""
+
+import networkx as nx
+import pymocd
+
+
+def test_iterator_matches_pareto_front():
+    graph = nx.planted_partition_graph(3, 10, 0.7, 0.05, seed=8)
+    params = dict(num_gens=20, pop_size=30, seed=1)
+
+    for model in (pymocd.MOCD(graph, **params), pymocd.HpMocd(graph, **params)):
+        front = model.generate_pareto_front()
+        iterator = model.iter_front()
+        assert iter(iterator) is iterator
+        assert len(iterator) == len(front)
+
+        count = 0
+        for (partition, objectives), expected in zip(iterator, front):
+            assert (partition, objectives) == expected
+            count += 1
+        assert count == len(front)
+        assert list(iterator) == []
@@ -0,0 +1,16 @@
+"""Checks that knee_point() picks a solution from the front."""
+
+import networkx as nx
+import pymocd
+
+
+def test_knee_point_is_on_the_front():
+    graph = nx.planted_partition_graph(3, 12, 0.7, 0.03, seed=6)
+    params = dict(num_gens=30, pop_size=40, seed=8)
+
+    for model in (pymocd.MOCD(graph, **params), pymocd.HpMocd(graph, **params)):
+        partition, objectives = model.knee_point()
+        front = model.front()
+        assert (partition, objectives) in front
+        if len(front) < 3:
+            assert partition == model.best()[0]
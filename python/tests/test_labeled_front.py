@@ -0,0 +1,23 @@
+"""Checks the labelled objectives returned by labeled_pareto_front."""
+
+import math
+
+import networkx as nx
+import pymocd
+
+
+def test_labeled_front_matches_fitness():
+    graph = nx.planted_partition_graph(3, 10, 0.7, 0.05, seed=4)
+    params = dict(num_gens=20, pop_size=30, seed=6)
+
+    for model in (pymocd.MOCD(graph, **params), pymocd.HpMocd(graph, **params)):
+        labeled = model.labeled_pareto_front()
+        plain = model.generate_pareto_front()
+        assert len(labeled) == len(plain)
+        for (partition, scores), (same_partition, objectives) in zip(labeled, plain):
+            assert set(scores) == {"intra", "inter", "modularity"}
+            assert partition == same_partition
+            assert [scores["intra"], scores["inter"]] == objectives
+            assert math.isclose(
+                scores["modularity"], pymocd.fitness(graph, partition), abs_tol=1e-9
+            )
@@ -0,0 +1,54 @@
+"""Checks that non-integer node labels round-trip through the estimators."""
+
+import os
+import tempfile
+
+import networkx as nx
+import pymocd
+
+
+def _labeled_cliques():
+    graph = nx.Graph()
+    for clique in ("abcde", "vwxyz"):
+        graph.add_edges_from((u, v) for u in clique for v in clique if u < v)
+    graph.add_edge("e", "v")
+    return graph
+
+
+def test_string_labels_come_back():
+    graph = _labeled_cliques()
+    for cls in (pymocd.HpMocd, pymocd.MOCD):
+        model = cls(graph, num_gens=30, pop_size=40, seed=3).fit()
+        partition = model.predict()
+
+        assert set(partition) == set(graph.nodes)
+        assert partition["a"] == partition["b"] != partition["z"]
+        assert sorted(model.node_order()) == sorted(graph.nodes)
+        assert len(model.score_partition(partition)) == 2
+        for front_partition, _ in model.front():
+            assert set(front_partition) == set(graph.nodes)
+
+
+def test_labels_survive_save_and_load():
+    model = pymocd.HpMocd(_labeled_cliques(), num_gens=10, seed=1).fit()
+    with tempfile.TemporaryDirectory() as tmp:
+        path = os.path.join(tmp, "model.bin")
+        model.save(path)
+        assert pymocd.HpMocd.load(path).predict() == model.predict()
+
+
+def test_module_functions_accept_labels():
+    graph = _labeled_cliques()
+    partition = {node: int(node > "m") for node in graph.nodes}
+
+    assert pymocd.fitness(graph, partition) > 0.4
+    assert pymocd.coverage(graph, partition) > 0.9
+    assert pymocd.nmi(partition, dict(partition)) == 1.0
+
+
+def test_integer_labels_are_unchanged():
+    graph = nx.relabel_nodes(_labeled_cliques(), {c: ord(c) for c in "abcdevwxyz"})
+    model = pymocd.HpMocd(graph, num_gens=10, seed=1).fit()
+
+    assert model.node_order() == sorted(graph.nodes)
+    assert set(model.predict()) == set(graph.nodes)
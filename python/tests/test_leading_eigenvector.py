@@ -0,0 +1,14 @@
+"""Checks that the modularity matrix's leading eigenvector splits two cliques."""
+
+import networkx as nx
+import pymocd
+
+
+def test_signs_split_the_barbell():
+    graph = nx.barbell_graph(6, 0)
+    for cls in (pymocd.MOCD, pymocd.HpMocd):
+        vector = cls(graph).leading_eigenvector()
+        assert set(vector) == set(graph.nodes)
+        assert abs(sum(v * v for v in vector.values()) - 1.0) < 1e-9
+        left = {node for node, value in vector.items() if value > 0}
+        assert left in (set(range(6)), set(range(6, 12)))
@@ -0,0 +1,38 @@
+"""Checks that must_link/cannot_link pairs hold in every returned partition."""
+
+import networkx as nx
+import pymocd
+
+
+def two_cliques():
+    graph = nx.disjoint_union(nx.complete_graph(6), nx.complete_graph(6))
+    graph.add_edge(5, 6)
+    return graph
+
+
+def test_must_link_pair_shares_a_community():
+    graph = two_cliques()
+    for cls in (pymocd.MOCD, pymocd.HpMocd):
+        model = cls(graph, seed=3, num_gens=20, must_link=[(0, 11)], cannot_link=[(1, 2)])
+        for partition, _ in model.fit().front():
+            assert partition[0] == partition[11]
+            assert partition[1] != partition[2]
+    best = pymocd.MOCD(graph, seed=3, num_gens=20, refine=True, must_link=[(0, 11)]).max_q()
+    assert best[0] == best[11]
+
+
+def test_unsatisfiable_links_are_rejected():
+    graph = two_cliques()
+    for cannot_link in ([(0, 2)], [(4, 4)]):
+        try:
+            pymocd.MOCD(graph, must_link=[(0, 1), (1, 2)], cannot_link=cannot_link)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError("expected ValueError")
+    try:
+        pymocd.HpMocd(graph, must_link=[(0, 99)])
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("expected ValueError")
@@ -0,0 +1,30 @@
+"""Checks that local_community grows the seed's clique on two joined cliques."""
+
+import networkx as nx
+import pymocd
+
+
+def _two_cliques():
+    graph = nx.disjoint_union(nx.complete_graph(5), nx.complete_graph(5))
+    graph.add_edge(4, 5)
+    return graph
+
+
+def test_ego_community_is_the_seed_clique():
+    graph = _two_cliques()
+    for model in (pymocd.MOCD(graph), pymocd.HpMocd(graph)):
+        community = model.local_community(7, 10)
+        assert community[0] == 7
+        assert sorted(community) == [5, 6, 7, 8, 9]
+        assert len(model.local_community(0, 2)) == 2
+
+
+def test_unknown_seed_is_rejected():
+    model = pymocd.HpMocd(_two_cliques())
+    for seed, max_size in ((42, 3), (0, 0)):
+        try:
+            model.local_community(seed, max_size)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError("expected ValueError")
@@ -0,0 +1,23 @@
+"""Checks that estimator output goes through `init_logging`."""
+
+import pymocd
+
+EDGES = [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)]
+
+
+def test_init_logging_levels():
+    for level in ["off", "warn", "INFO", "debug"]:
+        pymocd.init_logging(level)
+    try:
+        pymocd.init_logging("loud")
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("an unknown level should raise ValueError")
+    pymocd.init_logging("off")
+
+
+def test_debug_level_still_runs():
+    partition = pymocd.HpMocd(EDGES, debug_level=1, num_gens=5, pop_size=10, seed=1).run()
+    assert len(partition) == 6
+    pymocd.init_logging("off")
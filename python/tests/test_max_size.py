@@ -0,0 +1,24 @@
+"""Checks that max_community_size caps every community the optimizers return."""
+
+from collections import Counter
+
+import networkx as nx
+import pymocd
+
+
+def test_no_community_exceeds_the_cap():
+    graph = nx.complete_graph(15)
+    params = dict(num_gens=20, pop_size=30, seed=2, max_community_size=4)
+
+    for model in (pymocd.MOCD(graph, **params), pymocd.HpMocd(graph, **params)):
+        for partition, _ in model.fit().front():
+            assert max(Counter(partition.values()).values()) <= 4
+
+
+def test_zero_cap_is_rejected():
+    try:
+        pymocd.HpMocd(nx.complete_graph(5), max_community_size=0)
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("max_community_size=0 should raise ValueError")
@@ -0,0 +1,39 @@
+"""Checks merge_gains() against the change in NetworkX modularity."""
+
+import math
+
+import networkx as nx
+import pymocd
+from networkx.algorithms.community import modularity
+
+
+def two_cliques():
+    graph = nx.disjoint_union(nx.complete_graph(5), nx.complete_graph(5))
+    graph.add_edge(4, 5)
+    return graph
+
+
+def communities(partition):
+    groups = {}
+    for node, community in partition.items():
+        groups.setdefault(community, set()).add(node)
+    return list(groups.values())
+
+
+def test_merging_the_cliques_loses_modularity():
+    graph = two_cliques()
+    cliques = {node: node // 5 for node in graph}
+    for cls in (pymocd.MOCD, pymocd.HpMocd):
+        [((a, b), gain)] = cls(graph).merge_gains(cliques)
+        assert (a, b) == (0, 1)
+        assert gain < 0
+
+
+def test_gains_match_networkx():
+    graph = two_cliques()
+    partition = {node: node * 2 // 5 for node in graph}
+    before = modularity(graph, communities(partition))
+    for (a, b), gain in pymocd.MOCD(graph).merge_gains(partition):
+        merged = {node: a if c == b else c for node, c in partition.items()}
+        after = modularity(graph, communities(merged))
+        assert math.isclose(after - before, gain, abs_tol=1e-12)
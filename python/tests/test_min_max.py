@@ -0,0 +1,42 @@
+"""Checks that seeded min_max runs are reproducible, weighted graphs included, and
+that progress reporting leaves the pick unchanged.
+"""
+
+import random
+
+import networkx as nx
+import pymocd
+
+
+def test_seeded_min_max_is_deterministic():
+    graph = nx.planted_partition_graph(3, 10, 0.7, 0.05, seed=1)
+    params = dict(num_gens=20, pop_size=30, rand_networks=3, seed=9)
+
+    for null_model in ("erdos_renyi", "configuration"):
+        first = pymocd.MOCD(graph, **params).min_max(null_model)
+        second = pymocd.MOCD(graph, **params).min_max(null_model)
+        assert first == second
+
+
+def test_weighted_min_max_is_deterministic():
+    graph = nx.ring_of_cliques(3, 5)
+    rng = random.Random(3)
+    for u, v in graph.edges:
+        graph[u][v]["weight"] = rng.uniform(0.5, 2.0)
+    params = dict(num_gens=20, pop_size=30, rand_networks=3, seed=2)
+
+    assert pymocd.MOCD(graph, **params).min_max() == pymocd.MOCD(graph, **params).min_max()
+
+
+def test_progress_reporting_does_not_change_the_pick():
+    graph = nx.planted_partition_graph(3, 10, 0.7, 0.05, seed=1)
+    params = dict(num_gens=20, pop_size=30, rand_networks=3, seed=9)
+    calls = []
+
+    quiet = pymocd.MOCD(graph, **params).min_max()
+    verbose = pymocd.MOCD(
+        graph, debug_level=1, on_generation=lambda gen, q: calls.append(gen), **params
+    ).min_max()
+    pymocd.init_logging("off")
+    assert quiet == verbose
+    assert len(calls) > 20
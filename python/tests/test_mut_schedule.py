@@ -0,0 +1,27 @@
+"""Checks the mut_rate_schedule options and their validation."""
+
+import networkx as nx
+import pymocd
+
+
+def test_decaying_schedules_fit():
+    graph = nx.karate_club_graph()
+
+    for schedule in ("constant", "linear", "exponential"):
+        params = dict(num_gens=20, pop_size=30, seed=4, mut_rate_schedule=schedule)
+        for model in (pymocd.MOCD(graph, **params), pymocd.HpMocd(graph, **params)):
+            partition, _ = model.best()
+            assert set(partition) == set(graph.nodes)
+
+
+def test_invalid_schedule_is_rejected():
+    for params in (
+        dict(mut_rate_schedule="cosine"),
+        dict(mut_rate_schedule="linear", mut_rate=0.1, mut_rate_min=0.5),
+    ):
+        try:
+            pymocd.HpMocd(nx.complete_graph(5), **params)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"{params} should raise ValueError")
@@ -0,0 +1,41 @@
+"""Checks node-weighted modularity in fitness and the optimizers."""
+
+import math
+
+import networkx as nx
+import pymocd
+
+
+def test_doubling_every_weight_leaves_q_unchanged():
+    graph = nx.karate_club_graph()
+    partition = {node: int(graph.nodes[node]["club"] == "Officer") for node in graph}
+    weights = {node: 1.0 + node % 3 for node in graph}
+    doubled = {node: 2.0 * w for node, w in weights.items()}
+
+    q = pymocd.fitness(graph, partition, node_weights=weights)
+    assert math.isclose(q, pymocd.fitness(graph, partition, node_weights=doubled))
+    assert not math.isclose(q, pymocd.fitness(graph, partition))
+    assert math.isclose(
+        pymocd.fitness(graph, partition),
+        pymocd.fitness(graph, partition, node_weights={node: 3.0 for node in graph}),
+    )
+
+
+def test_optimizers_score_with_node_weights():
+    graph = nx.karate_club_graph()
+    weights = {node: 1.0 + node % 3 for node in graph}
+    params = dict(num_gens=15, pop_size=20, seed=2, node_weights=weights)
+    for model in (pymocd.MOCD(graph, **params), pymocd.HpMocd(graph, **params)):
+        partition, q = model.best()
+        assert math.isclose(q, pymocd.fitness(graph, partition, node_weights=weights))
+
+
+def test_bad_weights_are_rejected():
+    graph = nx.path_graph(3)
+    for weights in ({0: 0.0}, {0: -1.0}, {9: 1.0}):
+        try:
+            pymocd.fitness(graph, {0: 0, 1: 0, 2: 1}, node_weights=weights)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"{weights} should raise ValueError")
@@ -0,0 +1,40 @@
+"""Checks the obj_min/obj_max hypergrid bounds of MOCD."""
+
+import networkx as nx
+import pymocd
+
+
+def _boxes(front, divisions):
+    # Hypergrid cell of each solution when the bounds are fixed to [0, 1] per objective
+    return [
+        tuple(min(int(objective * divisions), divisions - 1) for objective in objectives)
+        for _, objectives in front
+    ]
+
+
+def test_fixed_bounds_repeat_box_assignments_under_a_seed():
+    graph = nx.karate_club_graph()
+    params = dict(
+        num_gens=15,
+        pop_size=20,
+        seed=4,
+        grid_divisions=8,
+        obj_min=(0.0, 0.0),
+        obj_max=(1.0, 1.0),
+    )
+    first = pymocd.MOCD(graph, **params)
+    second = pymocd.MOCD(graph, **params)
+    assert first.max_q() == second.max_q()
+    assert len(first.max_q()) == 34
+    assert _boxes(first.front(), 8) == _boxes(second.front(), 8)
+
+
+def test_bounds_must_come_together_and_be_ordered():
+    graph = nx.karate_club_graph()
+    for bounds in (dict(obj_min=(0.0, 0.0)), dict(obj_min=(1.0, 0.0), obj_max=(0.5, 1.0))):
+        try:
+            pymocd.MOCD(graph, **bounds)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"expected ValueError for {bounds}")
@@ -0,0 +1,64 @@
+"""Checks that `HpMocd.set_objectives` swaps in Python objectives."""
+
+import warnings
+from collections import Counter
+
+import networkx as nx
+import pymocd
+
+
+def _num_communities(partition):
+    return len(set(partition.values()))
+
+
+def test_custom_objectives_score_the_front():
+    model = pymocd.HpMocd(nx.ring_of_cliques(4, 5), num_gens=30, pop_size=40, seed=3)
+    calls = []
+
+    def size_gap(partition):
+        calls.append(partition)
+        return abs(_num_communities(partition) - 2)
+
+    def largest(partition):
+        return max(Counter(partition.values()).values()) / 20
+
+    model.set_objectives([size_gap, largest])
+    front = model.generate_pareto_front()
+    assert calls
+    for partition, objectives in front:
+        assert objectives == [size_gap(partition), largest(partition)]
+
+    # An empty list restores the built-in objectives and drops the cached front
+    model.set_objectives([])
+    assert model.history() == []
+    assert len(model.run()) == 20
+
+
+def test_raising_objective_propagates():
+    def broken(partition):
+        raise KeyError("boom")
+
+    model = pymocd.HpMocd(nx.karate_club_graph(), num_gens=5, pop_size=20, seed=1)
+    model.set_objectives([broken, lambda p: 0.0])
+    try:
+        model.run()
+    except KeyError:
+        pass
+    else:
+        raise AssertionError("the objective's exception should reach the caller")
+
+
+def test_validation_and_warning():
+    model = pymocd.HpMocd(nx.karate_club_graph(), pop_size=500)
+    for bad in ([len], [len, len, len], [len, 3]):
+        try:
+            model.set_objectives(bad)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"{bad!r} should raise ValueError")
+
+    with warnings.catch_warnings(record=True) as caught:
+        warnings.simplefilter("always")
+        model.set_objectives([len, len])
+    assert len(caught) == 1
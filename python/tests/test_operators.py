@@ -0,0 +1,32 @@
+"""Checks the standalone crossover and mutate operators."""
+
+import networkx as nx
+import pymocd
+
+
+def _graph_and_parents():
+    graph = nx.planted_partition_graph(2, 8, 0.8, 0.1, seed=2)
+    # Already normalized, so the identity is exact
+    parent_a = {node: node // 8 for node in graph.nodes}
+    parent_b = {node: node % 2 for node in graph.nodes}
+    return graph, parent_a, parent_b
+
+
+def test_zero_rates_are_identity():
+    graph, parent_a, parent_b = _graph_and_parents()
+    for seed in range(10):
+        assert pymocd.crossover(parent_a, parent_b, graph, 0.0, seed=seed) == (
+            parent_a,
+            parent_b,
+        )
+        assert pymocd.mutate(parent_b, graph, 0.0, seed=seed) == parent_b
+
+
+def test_seeded_operators_are_reproducible():
+    graph, parent_a, parent_b = _graph_and_parents()
+    first = pymocd.crossover(parent_a, parent_b, graph, 1.0, seed=5)
+    assert first == pymocd.crossover(parent_a, parent_b, graph, 1.0, seed=5)
+    assert all(set(child) == set(graph.nodes) for child in first)
+    assert pymocd.mutate(parent_b, graph, 1.0, seed=5) == pymocd.mutate(
+        parent_b, graph, 1.0, seed=5
+    )
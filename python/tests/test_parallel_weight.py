@@ -0,0 +1,25 @@
+"""Checks that parallel_as_weight folds multigraph edges into weights."""
+
+import networkx as nx
+import pymocd
+
+
+def _multigraph():
+    graph = nx.MultiGraph()
+    graph.add_edges_from([(0, 1), (0, 1), (1, 0), (1, 2)])
+    return graph
+
+
+def test_copies_become_one_weighted_edge():
+    for estimator in (pymocd.MOCD, pymocd.HpMocd):
+        model = estimator(_multigraph(), parallel_as_weight=True)
+        assert model.num_edges == 2
+
+        edges = model.community_subgraph({0: 0, 1: 0, 2: 0}, 0)
+        weights = {frozenset((u, v)): w for u, v, w in edges}
+        assert weights == {frozenset((0, 1)): 3.0, frozenset((1, 2)): 1.0}
+
+
+def test_copies_are_dropped_by_default():
+    edges = pymocd.HpMocd(_multigraph()).community_subgraph({0: 0, 1: 0, 2: 0}, 0)
+    assert sorted(w for _, _, w in edges) == [1.0, 1.0]
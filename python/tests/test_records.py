@@ -0,0 +1,21 @@
+"""Checks the (node, community) record output of partition_records."""
+
+import networkx as nx
+import pymocd
+
+
+def test_records_cover_nodes_in_order():
+    graph = nx.planted_partition_graph(3, 10, 0.7, 0.05, seed=2)
+    params = dict(num_gens=20, pop_size=30, seed=5)
+
+    for model in (pymocd.MOCD(graph, **params), pymocd.HpMocd(graph, **params)):
+        records = model.partition_records()
+        assert len(records) == graph.number_of_nodes()
+        nodes = [node for node, _ in records]
+        assert nodes == sorted(nodes)
+        assert dict(records) == model.best()[0]
+
+
+def test_standalone_converter():
+    records = pymocd.partition_to_records({3: 9, 0: 4, 1: 9})
+    assert records == [(0, 0), (1, 1), (3, 1)]
@@ -0,0 +1,40 @@
+"""Checks that refine polishes the max-Q partition without lowering its Q."""
+
+from collections import Counter
+
+import networkx as nx
+import pymocd
+
+
+def test_refine_never_lowers_q():
+    graph = nx.karate_club_graph()
+    params = dict(num_gens=15, pop_size=20, seed=3)
+    _, q = pymocd.MOCD(graph, **params).best()
+    partition, refined_q = pymocd.MOCD(graph, refine=True, **params).best()
+    assert len(partition) == 34
+    assert refined_q >= q - 1e-9
+
+
+def test_accessors_use_the_refined_partition():
+    graph = nx.karate_club_graph()
+    model = pymocd.MOCD(graph, num_gens=15, pop_size=20, seed=3, refine=True)
+    partition = model.max_q()
+    assert model.predict() == partition
+
+    def groups(pairs):
+        communities = {}
+        for node, community in pairs:
+            communities.setdefault(community, set()).add(node)
+        return sorted(map(sorted, communities.values()))
+
+    assert groups(model.partition_records()) == groups(partition.items())
+    assert sorted(map(sorted, model.community_sets())) == groups(partition.items())
+
+
+def test_refine_keeps_size_and_count_limits():
+    graph = nx.karate_club_graph()
+    capped = pymocd.MOCD(graph, seed=1, refine=True, max_community_size=6).max_q()
+    assert max(Counter(capped.values()).values()) <= 6
+
+    counted = pymocd.MOCD(graph, seed=1, refine=True, num_communities=6).max_q()
+    assert len(set(counted.values())) == 6
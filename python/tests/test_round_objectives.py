@@ -0,0 +1,22 @@
+"""Checks that round_objectives rounds every front objective and validates its range."""
+
+import networkx as nx
+import pymocd
+
+
+def test_front_objectives_are_rounded():
+    graph = nx.karate_club_graph()
+    for cls in (pymocd.MOCD, pymocd.HpMocd):
+        model = cls(graph, seed=2, num_gens=10, round_objectives=4).fit()
+        for _, objectives in model.front():
+            assert all(round(value, 4) == value for value in objectives)
+
+
+def test_too_many_decimals_are_rejected():
+    for cls in (pymocd.MOCD, pymocd.HpMocd):
+        try:
+            cls(nx.karate_club_graph(), round_objectives=16)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError("round_objectives=16 should raise ValueError")
@@ -0,0 +1,21 @@
+"""Checks the runtime and generation count recorded for the last run."""
+
+import networkx as nx
+import pymocd
+
+
+def test_generations_and_runtime():
+    G = nx.karate_club_graph()
+    for cls in (pymocd.HpMocd, pymocd.MOCD):
+        model = cls(G, num_gens=25, pop_size=30, patience=5, seed=2)
+        assert model.last_generations() == 0
+        assert model.last_runtime_secs() == 0.0
+
+        model.fit()
+        assert 1 <= model.last_generations() <= 25
+        assert model.last_generations() == len(model.history())
+        assert model.last_runtime_secs() > 0.0
+
+        model.reset()
+        assert model.last_generations() == 0
+        assert model.last_runtime_secs() == 0.0
@@ -0,0 +1,34 @@
+"""Checks building the estimators from a sample of the edges."""
+
+import networkx as nx
+import pymocd
+
+
+def test_edge_sample_rate():
+    G = nx.planted_partition_graph(4, 25, 0.5, 0.02, seed=3)
+    for cls in (pymocd.HpMocd, pymocd.MOCD):
+        full = cls(G)
+        whole = cls(G, edge_sample_rate=1.0, seed=1)
+        assert whole.num_edges == full.num_edges
+        assert whole.degree_sequence() == full.degree_sequence()
+
+        sampled = cls(G, edge_sample_rate=0.3, seed=1, num_gens=10, pop_size=20)
+        assert sampled.num_nodes == full.num_nodes
+        assert sampled.num_edges < full.num_edges
+        assert sampled.num_edges == cls(G, edge_sample_rate=0.3, seed=1).num_edges
+
+
+def test_partition_covers_every_node():
+    G = nx.planted_partition_graph(4, 25, 0.5, 0.02, seed=3)
+    model = pymocd.HpMocd(G, edge_sample_rate=0.5, seed=4, num_gens=10, pop_size=20)
+    assert len(model.run()) == model.num_nodes
+
+
+def test_invalid_rate():
+    for rate in (0.0, 1.5, -0.1):
+        try:
+            pymocd.HpMocd(nx.karate_club_graph(), edge_sample_rate=rate)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"edge_sample_rate={rate} should raise ValueError")
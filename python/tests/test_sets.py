@@ -0,0 +1,19 @@
+"""Checks the list-of-communities output format."""
+
+import networkx as nx
+import pymocd
+
+
+def test_sets_partition_the_nodes():
+    graph = nx.planted_partition_graph(3, 10, 0.8, 0.05, seed=5)
+    model = pymocd.HpMocd(graph, num_gens=20, pop_size=30, seed=5)
+    sets = model.community_sets()
+
+    assert sorted(node for nodes in sets for node in nodes) == sorted(graph.nodes)
+    assert sum(len(nodes) for nodes in sets) == graph.number_of_nodes()
+    assert sets == pymocd.partition_to_sets(model.run())
+
+
+def test_partition_to_sets_orders_by_normalized_id():
+    assert pymocd.partition_to_sets({3: 9, 0: 4, 1: 9, 2: 4}) == [[0, 2], [1, 3]]
+    assert pymocd.partition_to_sets({"b": 1, "a": 0, "c": 1}) == [["b", "c"], ["a"]]
@@ -0,0 +1,23 @@
+"""Checks the graph statistics against networkx."""
+
+import math
+
+import networkx as nx
+import pymocd
+
+
+def test_matches_networkx():
+    G = nx.karate_club_graph()
+    for cls in (pymocd.HpMocd, pymocd.MOCD):
+        model = cls(G)
+        assert math.isclose(model.density(), nx.density(G))
+        assert math.isclose(
+            model.degree_assortativity(), nx.degree_assortativity_coefficient(G)
+        )
+
+
+def test_star_and_regular():
+    star = pymocd.HpMocd(nx.star_graph(5))
+    assert star.degree_assortativity() < 0
+    cycle = pymocd.HpMocd(nx.cycle_graph(6))
+    assert math.isnan(cycle.degree_assortativity())
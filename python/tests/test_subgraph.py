@@ -0,0 +1,21 @@
+"""Checks community_subgraph against NetworkX's induced subgraph."""
+
+import networkx as nx
+import pymocd
+
+
+def test_clique_community_gives_the_clique():
+    graph = nx.complete_graph(5)
+    graph.add_edges_from([(4, "a"), ("a", "b")])
+    partition = {node: 0 if node in range(5) else 1 for node in graph.nodes}
+
+    for model in (pymocd.MOCD(graph), pymocd.HpMocd(graph)):
+        edges = model.community_subgraph(partition, 0)
+        assert {frozenset((u, v)) for u, v, _ in edges} == {
+            frozenset(edge) for edge in nx.complete_graph(5).edges
+        }
+        assert all(weight == 1.0 for _, _, weight in edges)
+
+        # The edge list feeds straight back into an estimator
+        inner = pymocd.HpMocd(edges, num_gens=10, pop_size=20, seed=1).run()
+        assert set(inner) == set(range(5))
@@ -0,0 +1,45 @@
+"""Checks that fitting releases the GIL, so Python threads keep running."""
+
+import threading
+import time
+
+import networkx as nx
+import pymocd
+
+
+def _graph(seed):
+    return nx.planted_partition_graph(4, 50, 0.3, 0.01, seed=seed)
+
+
+def test_two_estimators_fit_concurrently():
+    models = [
+        pymocd.HpMocd(_graph(0), num_gens=200, pop_size=100, seed=1, patience=None),
+        pymocd.MOCD(_graph(1), num_gens=100, pop_size=100, seed=1, patience=None),
+    ]
+    running = threading.Event()
+    ticks = 0
+
+    def ticker():
+        # Pure Python work: only makes progress while no thread holds the GIL
+        nonlocal ticks
+        while running.is_set():
+            ticks += 1
+            time.sleep(0.001)
+
+    workers = [threading.Thread(target=model.fit) for model in models]
+    running.set()
+    counter = threading.Thread(target=ticker)
+    counter.start()
+    start = time.perf_counter()
+    for worker in workers:
+        worker.start()
+    for worker in workers:
+        worker.join()
+    elapsed = time.perf_counter() - start
+    running.clear()
+    counter.join()
+
+    for model in models:
+        assert model.predict()
+    # Holding the GIL would freeze the ticker for the whole run
+    assert ticks > elapsed * 100
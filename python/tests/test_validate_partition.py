@@ -0,0 +1,21 @@
+"""Checks that validate_partition reports mismatched nodes without raising."""
+
+import networkx as nx
+import pymocd
+
+
+def test_reports_a_missing_node():
+    graph = nx.path_graph(4)
+    report = pymocd.validate_partition(graph, {0: 0, 1: 0, 2: 1})
+    assert report == {"missing_nodes": [3], "extra_nodes": [], "num_communities": 2}
+
+
+def test_reports_extra_and_off_by_one_labels():
+    graph = nx.path_graph(3)
+    report = pymocd.validate_partition(graph, {1: 0, 2: 0, 3: 1, "x": 1})
+    assert report["missing_nodes"] == [0]
+    assert report["extra_nodes"] == [3, "x"]
+
+    labelled = nx.relabel_nodes(graph, {0: "a", 1: "b", 2: "c"})
+    report = pymocd.validate_partition(labelled, {"a": 0, "b": 0, "c": 1, "d": 2})
+    assert report == {"missing_nodes": [], "extra_nodes": ["d"], "num_communities": 3}
@@ -0,0 +1,20 @@
+"""Checks that warm_restart() never returns a lower Q than before the restart."""
+
+import networkx as nx
+import pymocd
+
+
+def test_restart_keeps_the_better_partition():
+    graph = nx.karate_club_graph()
+    for seed in range(3):
+        model = pymocd.MOCD(graph, seed=seed, num_gens=10)
+        _, before = model.best()
+        restarted = model.warm_restart(5)
+        after_partition, after = model.best()
+        assert after >= before
+        assert restarted == after_partition
+
+
+def test_zero_kicks_is_a_plain_restart():
+    model = pymocd.MOCD(nx.karate_club_graph(), seed=1, num_gens=10)
+    assert set(model.warm_restart(0)) == set(model.max_q())
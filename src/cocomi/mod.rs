@@ -1,11 +1,14 @@
 use rand::prelude::*;
 use rand::seq::SliceRandom;
-use std::collections::{HashMap, HashSet};
+use rand_chacha::ChaCha8Rng;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
 use std::cmp::Ordering;
 use pyo3::prelude::*;
-use pyo3::types::PyAny;
+use pyo3::types::{PyAny, PyDict};
 use crate::{
-    graph::{Graph, NodeId}, 
+    graph::{CommunityId, Graph, NodeId, Partition}, 
+    operators::get_modularity_from_partition,
+    persist::{self, Decode, Encode},
     utils::*
 };
 /// Represents a network as an adjacency list
@@ -89,7 +92,7 @@ impl Network {
     /// Extract a subnetwork based on a set of nodes
     fn extract_subnetwork(&self, nodes: &HashSet<usize>) -> Network {
         let mut edges = Vec::new();
-        let mut node_mapping: HashMap<usize, usize> = HashMap::new();
+        let mut node_mapping: HashMap<usize, usize> = HashMap::default();
         
         // Create a mapping from original node IDs to sequential IDs for the subnetwork
         for (new_id, &orig_id) in nodes.iter().enumerate() {
@@ -116,13 +119,13 @@ struct Individual {
 
 impl Individual {
     /// Create a new random individual
-    fn new_random(network: &Network, rng: &mut ThreadRng) -> Self {
+    fn new_random(network: &Network, rng: &mut ChaCha8Rng) -> Self {
         let n = network.nodes;
         let mut genes = vec![0; n];
         
         // Assign each node to a random community
-        for i in 0..n {
-            genes[i] = rng.random_range(0..n);
+        for gene in genes.iter_mut() {
+            *gene = rng.random_range(0..n);
         }
         
         // Initialize some nodes to have same community as their neighbors
@@ -148,7 +151,7 @@ impl Individual {
     /// Extract genes for a specific subnetwork
     fn extract_subgenes(&self, nodes: &HashSet<usize>) -> Vec<usize> {
         let mut subgenes = Vec::new();
-        let mut seen_communities = HashMap::new();
+        let mut seen_communities = HashMap::default();
         let mut next_comm_id = 0;
         
         for &node in nodes {
@@ -165,8 +168,8 @@ impl Individual {
     }
     
     /// Update genes from a subnetwork solution
-    fn update_from_subnetwork(&mut self, subnetwork_nodes: &Vec<usize>, best_subgenes: &Vec<usize>) {
-        let mut comm_mapping = HashMap::new();
+    fn update_from_subnetwork(&mut self, subnetwork_nodes: &[usize], best_subgenes: &[usize]) {
+        let mut comm_mapping = HashMap::default();
         let mut next_comm_id = 0;
         
         // Find the highest community ID currently in use
@@ -175,16 +178,14 @@ impl Individual {
         }
         
         // Map subnetwork community IDs to new unique IDs in the full network
-        for i in 0..subnetwork_nodes.len() {
-            let original_node = subnetwork_nodes[i];
-            let subnetwork_comm = best_subgenes[i];
-            
-            if !comm_mapping.contains_key(&subnetwork_comm) {
-                comm_mapping.insert(subnetwork_comm, next_comm_id);
+        for (&original_node, &subnetwork_comm) in subnetwork_nodes.iter().zip(best_subgenes) {
+            let new_comm_id = *comm_mapping.entry(subnetwork_comm).or_insert_with(|| {
+                let id = next_comm_id;
                 next_comm_id += 1;
-            }
+                id
+            });
             
-            self.genes[original_node] = comm_mapping[&subnetwork_comm];
+            self.genes[original_node] = new_comm_id;
         }
     }
 }
@@ -199,10 +200,10 @@ fn compute_modularity(individual: &Individual, network: &Network) -> f64 {
     }
     
     // Create a mapping of communities and nodes they contain
-    let mut communities: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut communities: HashMap<usize, Vec<usize>> = HashMap::default();
     for i in 0..n {
         communities.entry(individual.genes[i])
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(i);
     }
     
@@ -234,7 +235,7 @@ fn differential_evolution(
     network: &Network,
     f: f64,
     cr: f64,
-    rng: &mut ThreadRng
+    rng: &mut ChaCha8Rng
 ) {
     let pop_size = population.len();
     let dimension = network.nodes;
@@ -290,7 +291,7 @@ fn differential_evolution(
 }
 
 /// Local Moving scheme to optimize community structure
-fn local_moving_scheme(individual: &mut Individual, network: &Network) -> bool {
+fn local_moving_scheme(individual: &mut Individual, network: &Network, rng: &mut ChaCha8Rng) -> bool {
     let n = network.nodes;
     let mut improved = false;
     let mut continue_moving = true;
@@ -300,8 +301,7 @@ fn local_moving_scheme(individual: &mut Individual, network: &Network) -> bool {
         
         // Process nodes in random order
         let mut node_indices: Vec<usize> = (0..n).collect();
-        let mut rng = rand::rng();
-        node_indices.shuffle(&mut rng);
+        node_indices.shuffle(rng);
         
         for &node in &node_indices {
             let original_comm = individual.genes[node];
@@ -309,7 +309,7 @@ fn local_moving_scheme(individual: &mut Individual, network: &Network) -> bool {
             let mut best_delta_q = 0.0;
             
             // Try moving node to each of its neighbors' communities
-            let mut neighbor_comms = HashSet::new();
+            let mut neighbor_comms = HashSet::default();
             for &neighbor in &network.adjacency_list[node] {
                 neighbor_comms.insert(individual.genes[neighbor]);
             }
@@ -347,7 +347,7 @@ fn local_moving_scheme(individual: &mut Individual, network: &Network) -> bool {
 }
 
 /// Adapted Kernighan-Lin moving scheme to optimize network partition
-fn adapted_kl_moving_scheme(individual: &mut Individual, network: &Network) -> bool {
+fn adapted_kl_moving_scheme(individual: &mut Individual, network: &Network, rng: &mut ChaCha8Rng) -> bool {
     let n = network.nodes;
     let mut improved = false;
     let mut continue_outer = true;
@@ -360,18 +360,17 @@ fn adapted_kl_moving_scheme(individual: &mut Individual, network: &Network) -> b
         let mut moves_without_improvement = 0;
         
         let mut node_indices: Vec<usize> = (0..n).collect();
-        let mut rng = rand::rng();
-        node_indices.shuffle(&mut rng);
+        node_indices.shuffle(rng);
         
         for &node in &node_indices {
             let original_comm = individual.genes[node];
             
             // Find the best move for this node (to any community)
             let mut best_move_comm = original_comm;
-            let mut best_move_q = -std::f64::INFINITY;
+            let mut best_move_q = f64::NEG_INFINITY;
             
             // Consider all neighbor communities
-            let mut neighbor_comms = HashSet::new();
+            let mut neighbor_comms = HashSet::default();
             neighbor_comms.insert(original_comm);  // Include current community
             
             for &neighbor in &network.adjacency_list[node] {
@@ -419,7 +418,7 @@ fn adapted_kl_moving_scheme(individual: &mut Individual, network: &Network) -> b
 fn node_grouping_scheme(
     network: &Network,
     num_subnetworks: usize,
-    rng: &mut ThreadRng
+    rng: &mut ChaCha8Rng
 ) -> Vec<Vec<usize>> {
     let n = network.nodes;
     let nodes_per_subnetwork = n / num_subnetworks;
@@ -446,14 +445,14 @@ fn node_grouping_scheme(
         let mut individual = Individual::new_random(&subnetwork, rng);
         
         // Optimize using local moving
-        local_moving_scheme(&mut individual, &subnetwork);
+        local_moving_scheme(&mut individual, &subnetwork, rng);
         
         // Group nodes by communities
-        let mut communities: HashMap<usize, Vec<usize>> = HashMap::new();
-        for i in 0..subnetwork_nodes.len() {
+        let mut communities: HashMap<usize, Vec<usize>> = HashMap::default();
+        for (i, &node) in subnetwork_nodes.iter().enumerate() {
             communities.entry(individual.genes[i])
-                .or_insert_with(Vec::new)
-                .push(subnetwork_nodes[i]);
+                .or_default()
+                .push(node);
         }
         
         // Add each community as a group
@@ -467,7 +466,7 @@ fn node_grouping_scheme(
     // Ensure we have exactly num_subnetworks groups
     while optimized_subnetworks.len() > num_subnetworks {
         // Merge the two smallest groups
-        optimized_subnetworks.sort_by(|a, b| a.len().cmp(&b.len()));
+        optimized_subnetworks.sort_by_key(|a| a.len());
         let g1 = optimized_subnetworks.remove(0);
         let g2 = optimized_subnetworks.remove(0);
         let mut merged = g1;
@@ -477,7 +476,7 @@ fn node_grouping_scheme(
     
     while optimized_subnetworks.len() < num_subnetworks {
         // Split the largest group
-        optimized_subnetworks.sort_by(|a, b| b.len().cmp(&a.len()));
+        optimized_subnetworks.sort_by_key(|a| std::cmp::Reverse(a.len()));
         let largest = optimized_subnetworks.remove(0);
         let split_point = largest.len() / 2;
         
@@ -492,6 +491,7 @@ fn node_grouping_scheme(
 }
 
 /// Main CoCoMi algorithm
+#[allow(clippy::too_many_arguments)]
 fn cocomi(
     network: &Network,
     max_generations: usize,
@@ -501,13 +501,13 @@ fn cocomi(
     whole_network_generations: usize,
     f: f64,
     cr: f64,
+    rng: &mut ChaCha8Rng,
 ) -> Individual {
     let mut generation = 0;
-    let mut rng = rand::rng();
     
     // Initialize population
     let mut population: Vec<Individual> = (0..pop_size)
-        .map(|_| Individual::new_random(network, &mut rng))
+        .map(|_| Individual::new_random(network, rng))
         .collect();
     
     // Sort population by fitness
@@ -515,7 +515,7 @@ fn cocomi(
     
     while generation < max_generations {
         // Split network into subnetworks
-        let subnetworks = node_grouping_scheme(network, num_subnetworks, &mut rng);
+        let subnetworks = node_grouping_scheme(network, num_subnetworks, rng);
         
         // Optimize each subnetwork
         for subnetwork_nodes in &subnetworks {
@@ -537,11 +537,11 @@ fn cocomi(
             
             // Optimize subpopulation with DE and local moving
             for _ in 0..subnetwork_generations {
-                differential_evolution(&mut subpopulation, &subnetwork, f, cr, &mut rng);
+                differential_evolution(&mut subpopulation, &subnetwork, f, cr, rng);
                 
                 // Apply local moving to best individual
                 subpopulation.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap_or(Ordering::Equal));
-                local_moving_scheme(&mut subpopulation[0], &subnetwork);
+                local_moving_scheme(&mut subpopulation[0], &subnetwork, rng);
             }
             
             // Update main population with best subnetwork solution
@@ -564,7 +564,7 @@ fn cocomi(
         
         // Optimize whole network using adapted KL moving scheme
         for _ in 0..whole_network_generations {
-            adapted_kl_moving_scheme(&mut population[0], network);
+            adapted_kl_moving_scheme(&mut population[0], network, rng);
         }
         
         // Update generation count
@@ -582,6 +582,7 @@ fn recursive_partitioning(
     q_min: f64,
     z_score_threshold: f64,
     cocomi_params: (usize, usize, usize, usize, usize, f64, f64),
+    rng: &mut ChaCha8Rng,
 ) -> Vec<Vec<usize>> {
     let (max_generations, pop_size, num_subnetworks, 
          subnetwork_generations, whole_network_generations, f, cr) = cocomi_params;
@@ -596,13 +597,14 @@ fn recursive_partitioning(
         whole_network_generations,
         f,
         cr,
+        rng,
     );
     
     // Extract communities
-    let mut communities: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut communities: HashMap<usize, Vec<usize>> = HashMap::default();
     for i in 0..network.nodes {
         communities.entry(best_individual.genes[i])
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(i);
     }
     
@@ -629,6 +631,7 @@ fn recursive_partitioning(
             whole_network_generations,
             f,
             cr,
+            rng,
         );
         
         let subnetwork_q = subnetwork_solution.fitness;
@@ -641,7 +644,6 @@ fn recursive_partitioning(
         
         // Check statistical significance using Monte Carlo method
         let mut random_q_values = Vec::new();
-        let mut rng = rand::rng();
         const NUM_RANDOM_NETWORKS: usize = 10;  // Use a smaller value for performance
         
         for _ in 0..NUM_RANDOM_NETWORKS {
@@ -655,7 +657,7 @@ fn recursive_partitioning(
                 }
             }
             
-            node_stubs.shuffle(&mut rng);
+            node_stubs.shuffle(rng);
             
             for i in (0..node_stubs.len()).step_by(2) {
                 if i + 1 < node_stubs.len() {
@@ -679,6 +681,7 @@ fn recursive_partitioning(
                 whole_network_generations / 2,
                 f,
                 cr,
+                rng,
             );
             
             random_q_values.push(random_solution.fitness);
@@ -704,6 +707,7 @@ fn recursive_partitioning(
                 q_min,
                 z_score_threshold,
                 cocomi_params,
+                rng,
             );
             
             // Map subnetwork node IDs back to original network IDs
@@ -723,32 +727,72 @@ fn recursive_partitioning(
 }
 
 
+/// Stored with its adjacency lists as built, so a reloaded network walks
+/// neighbours in the same order
+impl Encode for Network {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.nodes.encode(out);
+        self.edges.encode(out);
+        self.adjacency_list.encode(out);
+    }
+}
+
+impl Decode for Network {
+    fn decode(input: &mut &[u8]) -> std::io::Result<Self> {
+        let nodes: usize = Decode::decode(input)?;
+        let edges: Vec<(usize, usize)> = Decode::decode(input)?;
+        let adjacency_list: Vec<Vec<usize>> = Decode::decode(input)?;
+        let degree = adjacency_list.iter().map(|neighbors| neighbors.len()).collect();
+        Ok(Network { nodes, edges, adjacency_list, degree })
+    }
+}
+
 #[pyclass]
 pub struct CoCoMi {
     network: Network,
+    seed: Option<u64>,
+    graph: Graph,               // The parsed graph with its weights, for scoring
+    node_ids: Vec<NodeId>,      // Node id of each network index (sorted ids)
+    labels: Option<NodeLabels>, // Original node labels when they were not i32 ids
 }
 
-#[pymethods]
 impl CoCoMi {
-    #[new]
-    #[pyo3(signature = (graph))]
-    pub fn new(graph: &Bound<'_, PyAny>) -> PyResult<Self> {
-        /* Convert from networkx to graph */
-        let edges = get_edges(graph)?;
-        let graph = build_graph(edges);
-        // Convert the graph to a network
-        let network = Network::from_graph(graph);
-        Ok(CoCoMi { network })
+    fn from_parts(
+        network: Network,
+        seed: Option<u64>,
+        graph: Graph,
+        labels: Option<NodeLabels>,
+    ) -> Self {
+        let mut node_ids: Vec<NodeId> = graph.nodes.iter().copied().collect();
+        node_ids.sort();
+        CoCoMi {
+            network,
+            seed,
+            graph,
+            node_ids,
+            labels,
+        }
     }
 
-    #[pyo3(signature = ())]
-    pub fn run(&self) -> PyResult<Vec<Vec<usize>>> {
+    /// Final communities of a full run as node ids
+    fn community_ids(&self) -> Vec<Vec<NodeId>> {
+        self.communities()
+            .iter()
+            .map(|nodes| nodes.iter().map(|&node| self.node_ids[node]).collect())
+            .collect()
+    }
+
+    /// Final communities of a full run, as indices into the sorted node list
+    fn communities(&self) -> Vec<Vec<usize>> {
         // Use the network data provided in self.network.
         let n = self.network.nodes;
         
         // Optionally print some information from the network:
         //println!("Using network with {} nodes and {} edges", n, self.network.edges.len());
         
+        let seed = self.seed.unwrap_or_else(|| rand::rng().random());
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
         // Set CoCoMi parameters
         let max_generations = 100;
         let pop_size = 50;
@@ -768,17 +812,17 @@ impl CoCoMi {
             whole_network_generations,
             f,
             cr,
+            &mut rng,
         );
         
-        // Print the best modularity value
-        println!("Best modularity: {}", best_individual.fitness);
+        log::info!("Best modularity: {}", best_individual.fitness);
         
         // Extract communities from best_individual.genes
-        let mut communities: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut communities: HashMap<usize, Vec<usize>> = HashMap::default();
         for i in 0..n {
             communities
                 .entry(best_individual.genes[i])
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(i);
         }
         
@@ -793,14 +837,95 @@ impl CoCoMi {
             cr,
         );
         
-        let final_communities = recursive_partitioning(
+        recursive_partitioning(
             &self.network,
             0.3,  // q_min
             2.0,  // z_score threshold
             cocomi_params,
-        );
-        
-        // Return the final communities as a Vec<Vec<usize>>
-        Ok(final_communities)
+            &mut rng,
+        )
+    }
+}
+
+#[pymethods]
+impl CoCoMi {
+    #[new]
+    #[pyo3(signature = (graph, seed = None, drop_self_loops = true))]
+    pub fn new(
+        graph: &Bound<'_, PyAny>,
+        seed: Option<u64>,
+        drop_self_loops: bool,
+    ) -> PyResult<Self> {
+        /* Convert from networkx to graph */
+        let (edges, labels) = get_edges(graph)?;
+        let (graph, _) = build_graph(edges, drop_self_loops, false, false, false);
+        // Convert the graph to a network
+        let network = Network::from_graph(graph.clone());
+        Ok(CoCoMi::from_parts(network, seed, graph, labels))
+    }
+
+    /// Saves the parsed network and seed to `path`.
+    #[pyo3(signature = (path))]
+    pub fn save(&self, py: Python<'_>, path: &str) -> PyResult<()> {
+        let labels = match &self.labels {
+            Some(labels) => Some(labels.to_bytes(py)?),
+            None => None,
+        };
+        let state = (&self.network, self.seed, &self.graph, labels);
+        Ok(persist::save(path, "CoCoMi", &state)?)
+    }
+
+    /// Loads an estimator written by `save`.
+    #[staticmethod]
+    #[pyo3(signature = (path))]
+    pub fn load(py: Python<'_>, path: &str) -> PyResult<Self> {
+        let (network, seed, graph, labels): (_, _, _, Option<Vec<u8>>) =
+            persist::load(path, "CoCoMi")?;
+        let labels = match labels {
+            Some(bytes) => Some(NodeLabels::from_bytes(py, &bytes)?),
+            None => None,
+        };
+        Ok(CoCoMi::from_parts(network, seed, graph, labels))
+    }
+
+    /// Number of distinct nodes in the parsed graph.
+    #[getter]
+    pub fn num_nodes(&self) -> usize {
+        self.network.nodes
+    }
+
+    /// Number of edges in the parsed graph, after dropping parallel edges.
+    #[getter]
+    pub fn num_edges(&self) -> usize {
+        self.network.edges.len()
+    }
+
+    /// Node degrees ordered by node id; a self-loop counts twice.
+    #[pyo3(signature = ())]
+    pub fn degree_sequence(&self) -> Vec<usize> {
+        self.network.degree.clone()
+    }
+
+    /// The communities found, as lists of the nodes of the input graph
+    #[pyo3(signature = ())]
+    pub fn run<'py>(&self, py: Python<'py>) -> PyResult<Vec<Vec<Bound<'py, PyAny>>>> {
+        self.community_ids()
+            .iter()
+            .map(|nodes| node_labels(py, nodes, self.labels.as_ref()))
+            .collect()
+    }
+
+    /// The communities of `run` as a `{node: community}` dict, together with their
+    /// modularity Q on the weighted graph, as `fitness` scores it
+    #[pyo3(signature = ())]
+    pub fn best<'py>(&self, py: Python<'py>) -> PyResult<(Bound<'py, PyDict>, f64)> {
+        let mut partition = Partition::new();
+        for (community, nodes) in self.community_ids().iter().enumerate() {
+            for &node in nodes {
+                partition.insert(node, community as CommunityId);
+            }
+        }
+        let q = get_modularity_from_partition(&partition, &self.graph, 1.0);
+        Ok((from_partition(py, &partition, self.labels.as_ref())?, q))
     }
 }
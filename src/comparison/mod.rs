@@ -0,0 +1,410 @@
+//! comparison/mod.rs
+//! Measures of agreement between two partitions of the same node set
+//! This Source Code Form is subject to the terms of The GNU General Public License v3.0
+//! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
+//! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
+
+use crate::graph::{CommunityId, Partition};
+use rustc_hash::FxHashMap as HashMap;
+
+/// Contingency table between two partitions over the same nodes
+#[derive(Debug)]
+pub struct Contingency {
+    pub n: usize,
+    pub cells: HashMap<(CommunityId, CommunityId), usize>,
+    pub rows: HashMap<CommunityId, usize>, // community sizes in `a`
+    pub cols: HashMap<CommunityId, usize>, // community sizes in `b`
+}
+
+impl Contingency {
+    /// Builds the table; `a` and `b` are expected to cover the same nodes.
+    pub fn new(a: &Partition, b: &Partition) -> Self {
+        let mut cells = HashMap::default();
+        let mut rows = HashMap::default();
+        let mut cols = HashMap::default();
+
+        for (node, &ca) in a {
+            let cb = b[node];
+            *cells.entry((ca, cb)).or_insert(0) += 1;
+            *rows.entry(ca).or_insert(0) += 1;
+            *cols.entry(cb).or_insert(0) += 1;
+        }
+
+        Contingency {
+            n: a.len(),
+            cells,
+            rows,
+            cols,
+        }
+    }
+
+    /// True when both partitions group the nodes identically (labels aside)
+    pub fn is_identical(&self) -> bool {
+        self.cells.len() == self.rows.len() && self.cells.len() == self.cols.len()
+    }
+
+    pub fn entropy_a(&self) -> f64 {
+        entropy(self.rows.values(), self.n)
+    }
+
+    pub fn entropy_b(&self) -> f64 {
+        entropy(self.cols.values(), self.n)
+    }
+
+    /// Joint entropy H(A, B) of the cells, natural log
+    pub fn joint_entropy(&self) -> f64 {
+        entropy(self.cells.values(), self.n)
+    }
+
+    /// Mutual information I(A; B), natural log
+    pub fn mutual_information(&self) -> f64 {
+        let n = self.n as f64;
+        self.cells
+            .iter()
+            .map(|(&(ca, cb), &count)| {
+                let count = count as f64;
+                let expected = self.rows[&ca] as f64 * self.cols[&cb] as f64;
+                (count / n) * (n * count / expected).ln()
+            })
+            .sum()
+    }
+
+    /// Node pairs grouped together in both partitions, in `a`, and in `b`, followed by
+    /// the total number of pairs: (sum C(n_ij, 2), sum C(a_i, 2), sum C(b_j, 2), C(n, 2))
+    pub fn pair_counts(&self) -> (f64, f64, f64, f64) {
+        (
+            pairs(self.cells.values()),
+            pairs(self.rows.values()),
+            pairs(self.cols.values()),
+            choose_2(self.n),
+        )
+    }
+
+    /// Expected mutual information E[I(A; B)] over random partitions with the same
+    /// community sizes (hypergeometric model of Vinh et al., 2010), natural log
+    pub fn expected_mutual_information(&self) -> f64 {
+        let n = self.n;
+        let nf = n as f64;
+        // ln(k!) for k = 0..=n
+        let mut ln_factorial = vec![0.0; n + 1];
+        for k in 1..=n {
+            ln_factorial[k] = ln_factorial[k - 1] + (k as f64).ln();
+        }
+
+        let mut expected = 0.0;
+        for &a in self.rows.values() {
+            for &b in self.cols.values() {
+                let fixed =
+                    ln_factorial[a] + ln_factorial[b] + ln_factorial[n - a] + ln_factorial[n - b]
+                        - ln_factorial[n];
+                for nij in (a + b).saturating_sub(n).max(1)..=a.min(b) {
+                    let nij_f = nij as f64;
+                    let log_probability = fixed
+                        - ln_factorial[nij]
+                        - ln_factorial[a - nij]
+                        - ln_factorial[b - nij]
+                        - ln_factorial[n + nij - a - b];
+                    expected += (nij_f / nf)
+                        * (nf * nij_f / (a as f64 * b as f64)).ln()
+                        * log_probability.exp();
+                }
+            }
+        }
+        expected
+    }
+}
+
+/// Number of unordered pairs among `k` items
+fn choose_2(k: usize) -> f64 {
+    let k = k as f64;
+    k * (k - 1.0) / 2.0
+}
+
+/// Node pairs inside the clusters of the given sizes
+fn pairs<'a>(sizes: impl Iterator<Item = &'a usize>) -> f64 {
+    sizes.map(|&size| choose_2(size)).sum()
+}
+
+/// Shannon entropy (natural log) of a set of cluster sizes summing to `n`
+fn entropy<'a>(sizes: impl Iterator<Item = &'a usize>, n: usize) -> f64 {
+    let n = n as f64;
+    -sizes
+        .map(|&size| {
+            let p = size as f64 / n;
+            p * p.ln()
+        })
+        .sum::<f64>()
+}
+
+/// Agreement scores computed from the table
+impl Contingency {
+    /// Normalized Mutual Information, 2 * I(A; B) / (H(A) + H(B))
+    pub fn nmi(&self) -> f64 {
+        // Also covers the case where both partitions are a single community
+        if self.is_identical() {
+            return 1.0;
+        }
+
+        let denominator = self.entropy_a() + self.entropy_b();
+        if denominator == 0.0 {
+            return 1.0;
+        }
+        (2.0 * self.mutual_information() / denominator).clamp(0.0, 1.0)
+    }
+
+    /// Adjusted Mutual Information, see `ami`
+    pub fn ami(&self) -> f64 {
+        // Also covers the case where both partitions are a single community
+        if self.is_identical() {
+            return 1.0;
+        }
+
+        let expected = self.expected_mutual_information();
+        let denominator = (self.entropy_a() + self.entropy_b()) / 2.0 - expected;
+        if denominator.abs() < f64::EPSILON {
+            return 0.0;
+        }
+        (self.mutual_information() - expected) / denominator
+    }
+
+    /// Variation of Information, see `variation_of_information`
+    pub fn variation_of_information(&self) -> f64 {
+        if self.is_identical() {
+            return 0.0;
+        }
+        (2.0 * self.joint_entropy() - self.entropy_a() - self.entropy_b()).max(0.0)
+    }
+
+    /// Rand index, see `rand_index`
+    pub fn rand_index(&self) -> f64 {
+        let (both, pairs_a, pairs_b, total) = self.pair_counts();
+        if total == 0.0 {
+            return 1.0;
+        }
+        (total + 2.0 * both - pairs_a - pairs_b) / total
+    }
+
+    /// Adjusted Rand index, see `adjusted_rand_index`
+    pub fn adjusted_rand_index(&self) -> f64 {
+        // Also covers the case where both partitions are a single community
+        if self.is_identical() {
+            return 1.0;
+        }
+
+        let (both, pairs_a, pairs_b, total) = self.pair_counts();
+        let expected = pairs_a * pairs_b / total;
+        let denominator = (pairs_a + pairs_b) / 2.0 - expected;
+        if denominator.abs() < f64::EPSILON {
+            return 0.0;
+        }
+        (both - expected) / denominator
+    }
+
+    /// Jaccard index, see `jaccard`
+    pub fn jaccard(&self) -> f64 {
+        let (both, pairs_a, pairs_b, _) = self.pair_counts();
+        let either = pairs_a + pairs_b - both;
+        if either == 0.0 {
+            return 1.0;
+        }
+        both / either
+    }
+}
+
+/// Normalized Mutual Information, 2 * I(A; B) / (H(A) + H(B))
+pub fn nmi(a: &Partition, b: &Partition) -> f64 {
+    Contingency::new(a, b).nmi()
+}
+
+/// Adjusted Mutual Information, (I - E[I]) / (mean(H(A), H(B)) - E[I]). 1 for identical
+/// groupings, around 0 for chance agreement, and negative below chance.
+pub fn ami(a: &Partition, b: &Partition) -> f64 {
+    Contingency::new(a, b).ami()
+}
+
+/// Variation of Information, H(A|B) + H(B|A) = 2 H(A, B) - H(A) - H(B), natural log.
+/// A metric on partitions: 0 for identical groupings, symmetric, and obeying the
+/// triangle inequality.
+pub fn variation_of_information(a: &Partition, b: &Partition) -> f64 {
+    Contingency::new(a, b).variation_of_information()
+}
+
+/// Rand index: the fraction of node pairs on which both partitions agree, either
+/// grouped together in both or apart in both. 1 for identical groupings.
+pub fn rand_index(a: &Partition, b: &Partition) -> f64 {
+    Contingency::new(a, b).rand_index()
+}
+
+/// Adjusted Rand index (Hubert and Arabie, 1985), the Rand index corrected for chance
+/// under the hypergeometric model: (S - E[S]) / (mean(S_a, S_b) - E[S]), with S the
+/// pairs grouped together in both partitions. 1 for identical groupings, around 0 for
+/// chance agreement, and negative below chance.
+pub fn adjusted_rand_index(a: &Partition, b: &Partition) -> f64 {
+    Contingency::new(a, b).adjusted_rand_index()
+}
+
+/// Jaccard index over node pairs: pairs grouped together in both partitions divided by
+/// pairs grouped together in either. 1 for identical groupings, 0 when no pair is
+/// shared.
+pub fn jaccard(a: &Partition, b: &Partition) -> f64 {
+    Contingency::new(a, b).jaccard()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::Rng;
+
+    fn partition(labels: &[CommunityId]) -> Partition {
+        labels
+            .iter()
+            .enumerate()
+            .map(|(node, &comm)| (node as i32, comm))
+            .collect()
+    }
+
+    #[test]
+    fn test_nmi_identical_partitions() {
+        let a = partition(&[0, 0, 0, 1, 1, 1]);
+        let relabeled = partition(&[7, 7, 7, 3, 3, 3]);
+        let singletons = partition(&[0, 1, 2, 3, 4, 5]);
+
+        assert_eq!(nmi(&a, &a), 1.0);
+        assert_eq!(nmi(&a, &relabeled), 1.0);
+        assert_eq!(nmi(&singletons, &singletons), 1.0);
+    }
+
+    #[test]
+    fn test_nmi_known_value() {
+        let a = partition(&[0, 0, 0, 1, 1, 1]);
+        let b = partition(&[0, 0, 1, 1, 2, 2]);
+
+        assert!((nmi(&a, &b) - 0.515_803_742_979_388_8).abs() < 1e-12);
+        assert!((nmi(&a, &b) - nmi(&b, &a)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ami_identical_partitions() {
+        let a = partition(&[0, 0, 0, 1, 1, 1]);
+        let relabeled = partition(&[7, 7, 7, 3, 3, 3]);
+        let trivial = partition(&[0, 0, 0, 0]);
+
+        assert_eq!(ami(&a, &relabeled), 1.0);
+        assert_eq!(ami(&trivial, &partition(&[5, 5, 5, 5])), 1.0);
+    }
+
+    #[test]
+    fn test_ami_hand_computed() {
+        // I = 0. Of the 6 equally likely ways to split b's pairs, 2 match a (I = ln 2)
+        // and 4 cut across it (I = 0), so E[I] = ln 2 / 3. H(a) = H(b) = ln 2, hence
+        // AMI = (0 - ln 2 / 3) / (ln 2 - ln 2 / 3) = -1 / 2.
+        let a = partition(&[0, 0, 1, 1]);
+        let b = partition(&[0, 1, 0, 1]);
+        let table = Contingency::new(&a, &b);
+
+        assert!((table.expected_mutual_information() - 2f64.ln() / 3.0).abs() < 1e-12);
+        assert!((ami(&a, &b) + 0.5).abs() < 1e-12);
+        assert!((ami(&b, &a) + 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ami_known_value() {
+        let a = partition(&[0, 0, 0, 1, 1, 1]);
+        let b = partition(&[0, 0, 1, 1, 2, 2]);
+
+        // Checked by averaging I over all 720 relabelings of b
+        assert!((ami(&a, &b) - 0.298_792_458_170_889_3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rand_indices_hand_computed() {
+        // Of the 15 pairs, 2 are together in both, 6 together in a and 3 in b, so
+        // RI = (15 + 2 * 2 - 6 - 3) / 15 = 2/3. E[S] = 6 * 3 / 15 = 6/5, hence
+        // ARI = (2 - 6/5) / (9/2 - 6/5) = 8/33.
+        let a = partition(&[0, 0, 0, 1, 1, 1]);
+        let b = partition(&[0, 0, 1, 1, 2, 2]);
+
+        assert!((rand_index(&a, &b) - 2.0 / 3.0).abs() < 1e-12);
+        assert!((adjusted_rand_index(&a, &b) - 8.0 / 33.0).abs() < 1e-12);
+        assert!((adjusted_rand_index(&b, &a) - 8.0 / 33.0).abs() < 1e-12);
+
+        // The same crossing split as in the AMI example: RI = 1/3 and ARI = -1/2
+        let crossed = (partition(&[0, 0, 1, 1]), partition(&[0, 1, 0, 1]));
+        assert!((rand_index(&crossed.0, &crossed.1) - 1.0 / 3.0).abs() < 1e-12);
+        assert!((adjusted_rand_index(&crossed.0, &crossed.1) + 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_adjusted_rand_index_identical_and_random() {
+        let a = partition(&[0, 0, 0, 1, 1, 1]);
+        assert_eq!(
+            adjusted_rand_index(&a, &partition(&[7, 7, 7, 3, 3, 3])),
+            1.0
+        );
+        assert_eq!(rand_index(&a, &a), 1.0);
+
+        let mut rng = crate::operators::seeded_rng(11, 0, 0);
+        let truth: Vec<CommunityId> = (0..2000).map(|node| node % 5).collect();
+        let random: Vec<CommunityId> = (0..2000).map(|_| rng.random_range(0..5)).collect();
+        let ari = adjusted_rand_index(&partition(&truth), &partition(&random));
+        assert!(ari.abs() < 0.01, "ARI of random labels was {}", ari);
+    }
+
+    #[test]
+    fn test_jaccard_hand_computed() {
+        // 2 pairs together in both, 6 in a and 3 in b: J = 2 / (6 + 3 - 2)
+        let a = partition(&[0, 0, 0, 1, 1, 1]);
+        let b = partition(&[0, 0, 1, 1, 2, 2]);
+        assert!((jaccard(&a, &b) - 2.0 / 7.0).abs() < 1e-12);
+        assert_eq!(jaccard(&a, &partition(&[0, 1, 2, 0, 1, 2])), 0.0);
+
+        let singletons = partition(&[0, 1, 2, 3]);
+        assert_eq!(jaccard(&singletons, &singletons), 1.0);
+    }
+
+    #[test]
+    fn test_jaccard_split_one_cluster() {
+        // Splitting the 4-node cluster keeps 2 of its 6 pairs: 3 shared of 7 in a
+        let a = partition(&[0, 0, 0, 0, 1, 1]);
+        let b = partition(&[0, 0, 2, 2, 1, 1]);
+        assert!((jaccard(&a, &b) - 3.0 / 7.0).abs() < 1e-12);
+        assert_eq!(jaccard(&a, &b), jaccard(&b, &a));
+        assert_eq!(jaccard(&a, &a), 1.0);
+    }
+
+    #[test]
+    fn test_variation_of_information_hand_computed() {
+        // H(a) = ln 2, H(b) = ln 3 and the cells (2, 1, 1, 2) give
+        // H(a, b) = 2/3 ln 3 + 1/3 ln 6, so VI = 2 H(a, b) - H(a) - H(b) = ln 3 - 1/3 ln 2
+        let a = partition(&[0, 0, 0, 1, 1, 1]);
+        let b = partition(&[0, 0, 1, 1, 2, 2]);
+        let expected = 3f64.ln() - 2f64.ln() / 3.0;
+
+        assert!((variation_of_information(&a, &b) - expected).abs() < 1e-12);
+        assert!((variation_of_information(&b, &a) - expected).abs() < 1e-12);
+        assert_eq!(
+            variation_of_information(&a, &partition(&[4, 4, 4, 9, 9, 9])),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_variation_of_information_triangle_inequality() {
+        let partitions = [
+            partition(&[0, 0, 0, 1, 1, 1]),
+            partition(&[0, 0, 1, 1, 2, 2]),
+            partition(&[0, 1, 0, 1, 0, 1]),
+            partition(&[0, 0, 0, 0, 0, 0]),
+            partition(&[0, 1, 2, 3, 4, 5]),
+        ];
+        for a in &partitions {
+            for b in &partitions {
+                for c in &partitions {
+                    let direct = variation_of_information(a, c);
+                    let detour = variation_of_information(a, b) + variation_of_information(b, c);
+                    assert!(direct <= detour + 1e-12);
+                }
+            }
+        }
+    }
+}
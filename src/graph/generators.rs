@@ -0,0 +1,87 @@
+//! graph/generators.rs
+//! Random graph models used for benchmarking
+//! This Source Code Form is subject to the terms of The GNU General Public License v3.0
+//! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
+//! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
+
+use crate::graph::{Graph, NodeId};
+
+use rand::Rng;
+
+/// G(n, p): nodes `0..n`, each of the n(n-1)/2 pairs linked with probability `p`
+pub fn erdos_renyi(n: usize, p: f64, rng: &mut impl Rng) -> Graph {
+    let mut graph = Graph::new();
+    for node in 0..n as NodeId {
+        graph.add_node(node);
+    }
+    for i in 0..n as NodeId {
+        for j in (i + 1)..n as NodeId {
+            if rng.random_bool(p) {
+                graph.add_edge(i, j);
+            }
+        }
+    }
+    graph
+}
+
+/// Preferential attachment: nodes `0..n`, each node from `m` on links to `m` distinct
+/// earlier nodes picked with probability proportional to their degree. The first `m`
+/// nodes start unlinked, so the graph has m(n - m) edges. Requires `1 <= m < n`.
+pub fn barabasi_albert(n: usize, m: usize, rng: &mut impl Rng) -> Graph {
+    let mut graph = Graph::new();
+    // Every node appears once per incident edge, so a uniform draw is degree-weighted
+    let mut repeated: Vec<NodeId> = Vec::with_capacity(2 * m * n);
+    let mut targets: Vec<NodeId> = (0..m as NodeId).collect();
+
+    for source in m as NodeId..n as NodeId {
+        for &target in &targets {
+            graph.add_edge(source, target);
+        }
+        repeated.extend(&targets);
+        repeated.extend(std::iter::repeat_n(source, m));
+
+        targets.clear();
+        while targets.len() < m {
+            let candidate = repeated[rng.random_range(0..repeated.len())];
+            if !targets.contains(&candidate) {
+                targets.push(candidate);
+            }
+        }
+    }
+    graph
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::operators::seeded_rng;
+
+    #[test]
+    fn test_erdos_renyi_edge_count() {
+        let (n, p) = (200, 0.1);
+        let expected = p * (n * (n - 1)) as f64 / 2.0;
+        for seed in 0..5 {
+            let graph = erdos_renyi(n, p, &mut seeded_rng(seed, 0, 0));
+            assert_eq!(graph.num_nodes(), n);
+            let edges = graph.num_edges() as f64;
+            assert!((edges - expected).abs() < 0.1 * expected, "{} edges", edges);
+        }
+    }
+
+    #[test]
+    fn test_erdos_renyi_is_reproducible() {
+        let first = erdos_renyi(50, 0.2, &mut seeded_rng(9, 0, 0));
+        let second = erdos_renyi(50, 0.2, &mut seeded_rng(9, 0, 0));
+        assert_eq!(first.edges, second.edges);
+    }
+
+    #[test]
+    fn test_barabasi_albert_edge_count() {
+        let (n, m) = (100, 3);
+        let graph = barabasi_albert(n, m, &mut seeded_rng(4, 0, 0));
+        assert_eq!(graph.num_nodes(), n);
+        assert_eq!(graph.num_edges(), m * (n - m));
+        assert!(graph.edges.iter().all(|&(u, v)| u != v));
+        assert_eq!(graph.connected_components().len(), 1);
+    }
+}
@@ -7,7 +7,11 @@
 use rustc_hash::FxHashMap as HashMap;
 use rustc_hash::FxHashSet as HashSet;
 
-use std::collections::BTreeMap;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use std::collections::{BTreeMap, VecDeque};
+
+pub mod generators;
 
 pub type NodeId = i32;
 pub type CommunityId = i32;
@@ -18,6 +22,20 @@ pub struct Graph {
     pub edges: Vec<(NodeId, NodeId)>,
     pub nodes: HashSet<NodeId>,
     pub adjacency_list: HashMap<NodeId, Vec<NodeId>>,
+    /// Non-unit edge weights keyed by `(min, max)`, or `(from, to)` when directed;
+    /// missing edges weigh 1.0
+    pub weights: HashMap<(NodeId, NodeId), f64>,
+    /// Non-unit node weights scaling each node's degree in the modularity null model;
+    /// missing nodes weigh 1.0
+    pub node_weights: HashMap<NodeId, f64>,
+    /// Side (0 or 1) of each node of a bipartite graph for Barber's modularity; empty
+    /// for other graphs
+    pub node_types: HashMap<NodeId, u8>,
+    /// Directed graphs keep `adjacency_list` symmetric (any-direction neighbours)
+    /// and record the edge directions in `successors` / `predecessors`
+    pub directed: bool,
+    pub successors: HashMap<NodeId, Vec<NodeId>>,
+    pub predecessors: HashMap<NodeId, Vec<NodeId>>,
 }
 
 impl Default for Graph {
@@ -32,17 +50,33 @@ impl Graph {
             edges: Vec::new(),
             nodes: HashSet::default(),
             adjacency_list: HashMap::default(),
+            weights: HashMap::default(),
+            node_weights: HashMap::default(),
+            node_types: HashMap::default(),
+            directed: false,
+            successors: HashMap::default(),
+            predecessors: HashMap::default(),
+        }
+    }
+
+    pub fn new_directed() -> Self {
+        Graph {
+            directed: true,
+            ..Self::new()
         }
     }
 
     pub fn print(&self) {
-        println!(
+        log::info!(
             "[graph/mod.rs]: graph n/e: {}/{}",
             self.num_nodes(),
             self.num_edges(),
         );
     }
 
+    /// Adds an edge and its ends. A self-loop is listed twice in its node's adjacency
+    /// list, so it adds 2 to the degree as in the usual convention; objectives that walk
+    /// the lists count each listing at half weight.
     pub fn add_edge(&mut self, from: NodeId, to: NodeId) {
         self.edges.push((from, to));
         self.nodes.insert(from);
@@ -51,12 +85,123 @@ impl Graph {
         // Update adjacency list
         self.adjacency_list.entry(from).or_default().push(to);
         self.adjacency_list.entry(to).or_default().push(from);
+
+        if self.directed {
+            self.successors.entry(from).or_default().push(to);
+            self.predecessors.entry(to).or_default().push(from);
+        }
+    }
+
+    /// True if an edge joins `from` and `to` (in that direction when directed)
+    pub fn has_edge(&self, from: NodeId, to: NodeId) -> bool {
+        if self.directed {
+            self.successors(&from).contains(&to)
+        } else {
+            self.neighbors(&from).contains(&to)
+        }
+    }
+
+    /// Removes one edge between `from` and `to` (in that direction when directed),
+    /// keeping the order of the others. Both ends stay in `nodes`, isolated if this
+    /// was their last edge, and emptied adjacency entries are dropped. Returns false
+    /// when there is no such edge.
+    pub fn remove_edge(&mut self, from: NodeId, to: NodeId) -> bool {
+        let key = self.key(from, to);
+        let Some(position) = self.edges.iter().position(|&(u, v)| self.key(u, v) == key) else {
+            return false;
+        };
+        let (from, to) = self.edges.remove(position);
+
+        remove_neighbor(&mut self.adjacency_list, from, to);
+        remove_neighbor(&mut self.adjacency_list, to, from);
+        if self.directed {
+            remove_neighbor(&mut self.successors, from, to);
+            remove_neighbor(&mut self.predecessors, to, from);
+        }
+        if !self.has_edge(from, to) {
+            self.weights.remove(&key);
+        }
+        true
+    }
+
+    /// Adds a node without any incident edge
+    pub fn add_node(&mut self, node: NodeId) {
+        self.nodes.insert(node);
+    }
+
+    /// Adds an edge carrying `weight`. Unit weights are not stored, so a graph
+    /// built only from unit weights stays unweighted.
+    pub fn add_weighted_edge(&mut self, from: NodeId, to: NodeId, weight: f64) {
+        self.add_edge(from, to);
+        if weight != 1.0 {
+            self.weights.insert(self.key(from, to), weight);
+        }
+    }
+
+    /// Weight of the edge between `from` and `to` (1.0 when unweighted). On a
+    /// directed graph this is the weight of the `from -> to` edge.
+    pub fn weight(&self, from: NodeId, to: NodeId) -> f64 {
+        self.weights
+            .get(&self.key(from, to))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Sets the null-model weight of `node`. Unit weights are not stored, so a graph
+    /// without other weights keeps the plain degrees.
+    pub fn set_node_weight(&mut self, node: NodeId, weight: f64) {
+        if weight == 1.0 {
+            self.node_weights.remove(&node);
+        } else {
+            self.node_weights.insert(node, weight);
+        }
+    }
+
+    /// Null-model weight of `node`, 1.0 unless set
+    pub fn node_weight(&self, node: NodeId) -> f64 {
+        self.node_weights.get(&node).copied().unwrap_or(1.0)
+    }
+
+    /// Bipartite side (0 or 1) of `node`, None unless set
+    pub fn node_type(&self, node: NodeId) -> Option<u8> {
+        self.node_types.get(&node).copied()
+    }
+
+    /// Key identifying an edge: ordered when directed, canonical otherwise
+    pub fn key(&self, from: NodeId, to: NodeId) -> (NodeId, NodeId) {
+        if self.directed {
+            (from, to)
+        } else {
+            edge_key(from, to)
+        }
+    }
+
+    pub fn is_weighted(&self) -> bool {
+        !self.weights.is_empty()
+    }
+
+    /// Sum of all edge weights (the number of edges when unweighted).
+    pub fn total_weight(&self) -> f64 {
+        if !self.is_weighted() {
+            return self.num_edges() as f64;
+        }
+        self.edges.iter().map(|&(u, v)| self.weight(u, v)).sum()
     }
 
     pub fn neighbors(&self, node: &NodeId) -> &[NodeId] {
         self.adjacency_list.get(node).map_or(&[], |x| x)
     }
 
+    /// Targets of the edges leaving `node` (empty on undirected graphs)
+    pub fn successors(&self, node: &NodeId) -> &[NodeId] {
+        self.successors.get(node).map_or(&[], |x| x)
+    }
+
+    /// Sources of the edges entering `node` (empty on undirected graphs)
+    pub fn predecessors(&self, node: &NodeId) -> &[NodeId] {
+        self.predecessors.get(node).map_or(&[], |x| x)
+    }
+
     pub fn num_nodes(&self) -> usize {
         self.nodes.len()
     }
@@ -69,10 +214,384 @@ impl Graph {
     pub fn precompute_degrees(&self) -> HashMap<NodeId, usize> {
         let mut degrees = HashMap::default();
         for &node in &self.nodes {
-            degrees.insert(node, self.adjacency_list[&node].len());
+            degrees.insert(node, self.adjacency_list.get(&node).map_or(0, |v| v.len()));
         }
         degrees
     }
+
+    /// Degrees ordered by node id
+    pub fn degree_sequence(&self) -> Vec<usize> {
+        let degrees = self.precompute_degrees();
+        self.sorted_nodes()
+            .iter()
+            .map(|node| degrees[node])
+            .collect()
+    }
+
+    /// Fraction of the possible node pairs that are linked: 2E / (N(N-1)), or
+    /// E / (N(N-1)) for directed graphs. 0.0 for fewer than two nodes.
+    pub fn density(&self) -> f64 {
+        let n = self.num_nodes() as f64;
+        if n < 2.0 {
+            return 0.0;
+        }
+        let pairs = n * (n - 1.0);
+        if self.directed {
+            self.num_edges() as f64 / pairs
+        } else {
+            2.0 * self.num_edges() as f64 / pairs
+        }
+    }
+
+    /// Degree assortativity: the Pearson correlation of the endpoint degrees over
+    /// every edge, each edge counted in both orientations (degrees as in
+    /// `precompute_degrees`, so direction is ignored). NaN when the degrees at the
+    /// edge ends do not vary, which covers edgeless and regular graphs.
+    pub fn degree_assortativity(&self) -> f64 {
+        let degrees = self.precompute_degrees();
+        let (mut sum, mut sum_sq, mut sum_prod) = (0.0, 0.0, 0.0);
+        for (from, to) in &self.edges {
+            let (a, b) = (degrees[from] as f64, degrees[to] as f64);
+            sum += a + b;
+            sum_sq += a * a + b * b;
+            sum_prod += 2.0 * a * b;
+        }
+
+        let ends = 2.0 * self.num_edges() as f64;
+        let mean = sum / ends;
+        let variance = sum_sq / ends - mean * mean;
+        if ends == 0.0 || variance <= f64::EPSILON * mean * mean {
+            return f64::NAN;
+        }
+        (sum_prod / ends - mean * mean) / variance
+    }
+
+    /// Number of triangles, each counted once. Direction, self-loops and repeated
+    /// neighbours are ignored.
+    pub fn triangle_count(&self) -> usize {
+        self.node_triangles().values().sum::<usize>() / 3
+    }
+
+    /// Mean local clustering coefficient, 2T / (k(k-1)) for a node with k distinct
+    /// neighbours in T triangles, over every node; nodes with k < 2 count as 0.0, as
+    /// does a graph without nodes. Neighbours as in `triangle_count`.
+    pub fn average_clustering(&self) -> f64 {
+        if self.nodes.is_empty() {
+            return 0.0;
+        }
+        let neighbors = self.neighbor_sets();
+        let total: f64 = self
+            .node_triangles()
+            .iter()
+            .map(|(node, &triangles)| {
+                let k = neighbors[node].len() as f64;
+                if k < 2.0 {
+                    0.0
+                } else {
+                    2.0 * triangles as f64 / (k * (k - 1.0))
+                }
+            })
+            .sum();
+        total / self.nodes.len() as f64
+    }
+
+    /// Distinct neighbours of every node from `adjacency_list`, the node itself excluded
+    fn neighbor_sets(&self) -> HashMap<NodeId, HashSet<NodeId>> {
+        self.nodes
+            .iter()
+            .map(|&node| {
+                let set = self
+                    .neighbors(&node)
+                    .iter()
+                    .copied()
+                    .filter(|&neighbor| neighbor != node)
+                    .collect();
+                (node, set)
+            })
+            .collect()
+    }
+
+    /// Triangles through every node, by intersecting the neighbour sets of each linked
+    /// pair: every triangle at a node is found once from each of its two other corners
+    fn node_triangles(&self) -> HashMap<NodeId, usize> {
+        let neighbors = self.neighbor_sets();
+        neighbors
+            .iter()
+            .map(|(node, own)| {
+                let twice: usize = own
+                    .iter()
+                    .map(|neighbor| own.intersection(&neighbors[neighbor]).count())
+                    .sum();
+                (*node, twice / 2)
+            })
+            .collect()
+    }
+
+    /// Node ids in ascending order
+    pub fn sorted_nodes(&self) -> Vec<NodeId> {
+        let mut nodes: Vec<NodeId> = self.nodes.iter().copied().collect();
+        nodes.sort_unstable();
+        nodes
+    }
+
+    /// Connected components found by BFS over `adjacency_list` (weak components when
+    /// directed). Each component is sorted, and components are ordered by smallest node.
+    pub fn connected_components(&self) -> Vec<Vec<NodeId>> {
+        let mut visited: HashSet<NodeId> = HashSet::default();
+        let mut components = Vec::new();
+
+        for start in self.sorted_nodes() {
+            if !visited.insert(start) {
+                continue;
+            }
+            let mut component = vec![start];
+            let mut queue = VecDeque::from([start]);
+            while let Some(node) = queue.pop_front() {
+                for &neighbor in self.neighbors(&node) {
+                    if visited.insert(neighbor) {
+                        component.push(neighbor);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+
+    /// Coreness of every node: the largest k such that it lies in the k-core, the
+    /// maximal subgraph whose nodes all keep degree >= k. Peels a node of smallest
+    /// remaining degree at a time (Batagelj-Zaversnik) over `adjacency_list`, so
+    /// direction is ignored and degrees are as in `precompute_degrees`.
+    pub fn core_numbers(&self) -> HashMap<NodeId, usize> {
+        let mut degrees = self.precompute_degrees();
+        let max_degree = degrees.values().copied().max().unwrap_or(0);
+        let mut buckets: Vec<Vec<NodeId>> = vec![Vec::new(); max_degree + 1];
+        for node in self.sorted_nodes() {
+            buckets[degrees[&node]].push(node);
+        }
+
+        let mut cores = HashMap::default();
+        let mut level = 0;
+        while level <= max_degree {
+            let Some(node) = buckets[level].pop() else {
+                level += 1;
+                continue;
+            };
+            // Nodes are pushed again whenever their degree drops; skip stale entries
+            if cores.contains_key(&node) || degrees[&node] != level {
+                continue;
+            }
+            cores.insert(node, level);
+            for neighbor in self.neighbors(&node) {
+                if cores.contains_key(neighbor) {
+                    continue;
+                }
+                let degree = degrees.get_mut(neighbor).expect("neighbor is a node");
+                if *degree > level {
+                    *degree -= 1;
+                    buckets[*degree].push(*neighbor);
+                }
+            }
+        }
+        cores
+    }
+
+    /// Leading eigenvector of the modularity matrix B = A - k kᵀ / 2m after `iters`
+    /// rounds of power iteration, applying B implicitly as Ax over `adjacency_list`
+    /// minus k (k·x) / 2m so the dense matrix is never built. Degrees are those of
+    /// `precompute_degrees`. Entries are unit-norm, ordered by node id, and signed so
+    /// the largest in magnitude is positive; the signs split the graph in two.
+    pub fn leading_eigenvector(&self, iters: usize) -> Vec<(NodeId, f64)> {
+        let nodes = self.sorted_nodes();
+        let num_nodes = nodes.len();
+        let degrees = self.precompute_degrees();
+        let index: HashMap<NodeId, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, &node)| (node, i))
+            .collect();
+        let k: Vec<f64> = nodes.iter().map(|node| degrees[node] as f64).collect();
+        let two_m: f64 = k.iter().sum();
+        // Rows of B sum to at most 2 k_i in magnitude, so this shift makes B + cI
+        // positive semidefinite and the iteration finds B's most positive eigenvalue
+        // instead of its largest in magnitude
+        let shift = 2.0 * k.iter().copied().fold(0.0, f64::max);
+
+        // A ramp: the all-ones start is an eigenvector (B maps it to zero) and never moves
+        let mut x: Vec<f64> = (1..=num_nodes).map(|i| i as f64).collect();
+        normalize(&mut x);
+        for _ in 0..iters {
+            let kx = if two_m > 0.0 {
+                k.iter().zip(&x).map(|(a, b)| a * b).sum::<f64>() / two_m
+            } else {
+                0.0
+            };
+            let mut next: Vec<f64> = (0..num_nodes).map(|i| shift * x[i] - k[i] * kx).collect();
+            for (i, node) in nodes.iter().enumerate() {
+                for neighbor in self.neighbors(node) {
+                    next[i] += x[index[neighbor]];
+                }
+            }
+            if !normalize(&mut next) {
+                break;
+            }
+            x = next;
+        }
+
+        let largest = x
+            .iter()
+            .copied()
+            .fold(0.0, |acc: f64, v| if v.abs() > acc.abs() { v } else { acc });
+        if largest < 0.0 {
+            x.iter_mut().for_each(|v| *v = -*v);
+        }
+        nodes.into_iter().zip(x).collect()
+    }
+
+    /// Subgraph induced by `nodes`: the listed nodes of this graph and every edge with
+    /// both endpoints among them, keeping weights, node weights and types and direction
+    pub fn subgraph(&self, nodes: &HashSet<NodeId>) -> Graph {
+        let mut subgraph = Graph {
+            directed: self.directed,
+            ..Graph::new()
+        };
+        for node in self.nodes.intersection(nodes) {
+            subgraph.add_node(*node);
+            subgraph.set_node_weight(*node, self.node_weight(*node));
+            if let Some(node_type) = self.node_type(*node) {
+                subgraph.node_types.insert(*node, node_type);
+            }
+        }
+        for &(from, to) in &self.edges {
+            if nodes.contains(&from) && nodes.contains(&to) {
+                subgraph.add_weighted_edge(from, to, self.weight(from, to));
+            }
+        }
+        subgraph
+    }
+
+    /// Copy keeping every node and each edge independently with probability `rate`,
+    /// with its weight and direction; edges keep their order and nodes their weights and
+    /// types
+    pub fn sample_edges(&self, rate: f64, rng: &mut impl Rng) -> Graph {
+        let mut sample = Graph {
+            directed: self.directed,
+            ..Graph::new()
+        };
+        for &node in &self.nodes {
+            sample.add_node(node);
+        }
+        sample.node_weights = self.node_weights.clone();
+        sample.node_types = self.node_types.clone();
+        for &(from, to) in &self.edges {
+            if rng.random_bool(rate) {
+                sample.add_weighted_edge(from, to, self.weight(from, to));
+            }
+        }
+        sample
+    }
+
+    /// Splits the edges into a train graph keeping every node and a test graph holding
+    /// about `frac` of the edges, drawn at random. An edge is only held out while both
+    /// ends keep another train edge, so no node loses all of its edges in train; the
+    /// test share can fall short of `frac` on sparse graphs. Weights, direction and
+    /// edge order are kept in both.
+    pub fn holdout_edges(&self, frac: f64, rng: &mut impl Rng) -> (Graph, Graph) {
+        let mut incident: HashMap<NodeId, usize> = HashMap::default();
+        for &(from, to) in &self.edges {
+            *incident.entry(from).or_default() += 1;
+            if from != to {
+                *incident.entry(to).or_default() += 1;
+            }
+        }
+
+        let target = (frac * self.num_edges() as f64).round() as usize;
+        let mut order: Vec<usize> = (0..self.num_edges()).collect();
+        order.shuffle(rng);
+        let mut held_out = vec![false; self.num_edges()];
+        let mut remaining = target;
+        for index in order {
+            if remaining == 0 {
+                break;
+            }
+            let (from, to) = self.edges[index];
+            if incident[&from] > 1 && incident[&to] > 1 {
+                *incident.get_mut(&from).unwrap() -= 1;
+                if from != to {
+                    *incident.get_mut(&to).unwrap() -= 1;
+                }
+                held_out[index] = true;
+                remaining -= 1;
+            }
+        }
+
+        let empty = Graph {
+            directed: self.directed,
+            ..Graph::new()
+        };
+        let (mut train, mut test) = (empty.clone(), empty);
+        for &node in &self.nodes {
+            train.add_node(node);
+        }
+        for (&(from, to), &held_out) in self.edges.iter().zip(&held_out) {
+            let split = if held_out { &mut test } else { &mut train };
+            split.add_weighted_edge(from, to, self.weight(from, to));
+        }
+        (train, test)
+    }
+
+    /// Dense adjacency matrix with rows and columns in `sorted_nodes` order. Entries
+    /// count edges (weights are ignored), a self-loop sits once on the diagonal and
+    /// undirected graphs give a symmetric matrix. Allocates N * N entries.
+    pub fn adjacency_matrix(&self) -> Vec<Vec<i32>> {
+        let nodes = self.sorted_nodes();
+        let index: HashMap<NodeId, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, &node)| (node, i))
+            .collect();
+
+        let mut matrix = vec![vec![0; nodes.len()]; nodes.len()];
+        for &(from, to) in &self.edges {
+            let (i, j) = (index[&from], index[&to]);
+            matrix[i][j] += 1;
+            if !self.directed && i != j {
+                matrix[j][i] += 1;
+            }
+        }
+        matrix
+    }
+}
+
+/// Removes one `neighbor` from the list of `node`, and the list itself once empty
+fn remove_neighbor(lists: &mut HashMap<NodeId, Vec<NodeId>>, node: NodeId, neighbor: NodeId) {
+    let Some(list) = lists.get_mut(&node) else {
+        return;
+    };
+    if let Some(position) = list.iter().position(|&n| n == neighbor) {
+        list.remove(position);
+    }
+    if list.is_empty() {
+        lists.remove(&node);
+    }
+}
+
+/// Canonical undirected key for an edge.
+#[inline]
+pub fn edge_key(from: NodeId, to: NodeId) -> (NodeId, NodeId) {
+    if from <= to { (from, to) } else { (to, from) }
+}
+
+/// Scales `values` to unit length; false, leaving them untouched, when they are all zero
+fn normalize(values: &mut [f64]) -> bool {
+    let norm = values.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm == 0.0 {
+        return false;
+    }
+    values.iter_mut().for_each(|v| *v /= norm);
+    true
 }
 
 #[cfg(test)]
@@ -115,6 +634,82 @@ mod test {
         assert_eq!(graph.precompute_degrees(), expected);
     }
 
+    #[test]
+    fn test_precompute_degrees_isolated_node() {
+        let mut graph: Graph = Graph::new();
+        graph.add_edge(0, 1);
+        graph.add_node(2);
+
+        let degrees = graph.precompute_degrees();
+        assert_eq!(graph.num_nodes(), 3);
+        assert_eq!(degrees[&2], 0);
+        assert_eq!(degrees[&0], 1);
+        assert_eq!(graph.degree_sequence(), vec![1, 1, 0]);
+    }
+
+    #[test]
+    fn test_adjacency_matrix() {
+        let mut graph: Graph = Graph::new();
+        graph.add_edge(3, 1);
+        graph.add_edge(1, 7);
+        graph.add_edge(7, 7);
+        graph.add_node(5);
+
+        assert_eq!(graph.sorted_nodes(), vec![1, 3, 5, 7]);
+        assert_eq!(
+            graph.adjacency_matrix(),
+            vec![
+                vec![0, 1, 0, 1],
+                vec![1, 0, 0, 0],
+                vec![0, 0, 0, 0],
+                vec![1, 0, 0, 1],
+            ]
+        );
+
+        let mut directed = Graph::new_directed();
+        directed.add_edge(3, 1);
+        directed.add_edge(1, 7);
+        assert_eq!(
+            directed.adjacency_matrix(),
+            vec![vec![0, 0, 1], vec![1, 0, 0], vec![0, 0, 0]]
+        );
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let mut graph: Graph = Graph::new();
+        graph.add_edge(4, 3);
+        graph.add_edge(3, 5);
+        graph.add_edge(0, 1);
+        graph.add_node(9);
+
+        assert_eq!(
+            graph.connected_components(),
+            vec![vec![0, 1], vec![3, 4, 5], vec![9]]
+        );
+
+        let mut directed = Graph::new_directed();
+        directed.add_edge(0, 1);
+        directed.add_edge(2, 1);
+        directed.add_edge(3, 4);
+        assert_eq!(
+            directed.connected_components(),
+            vec![vec![0, 1, 2], vec![3, 4]]
+        );
+    }
+
+    #[test]
+    fn test_weighted_edges() {
+        let mut graph: Graph = Graph::new();
+        graph.add_weighted_edge(0, 1, 2.0);
+        graph.add_weighted_edge(1, 2, 1.0);
+
+        assert!(graph.is_weighted());
+        assert_eq!(graph.weight(1, 0), 2.0);
+        assert_eq!(graph.weight(2, 1), 1.0);
+        assert_eq!(graph.total_weight(), 3.0);
+    }
+
     #[test]
     fn test_graph_num_edges() {
         let mut graph: Graph = Graph::new();
@@ -124,4 +719,203 @@ mod test {
 
         assert_eq!(graph.num_edges(), 3);
     }
+
+    #[test]
+    fn test_density() {
+        let mut graph = Graph::new();
+        assert_eq!(graph.density(), 0.0);
+        graph.add_node(0);
+        assert_eq!(graph.density(), 0.0);
+
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+        graph.add_node(3);
+        assert!((graph.density() - 2.0 * 2.0 / 12.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_star_is_disassortative() {
+        let mut graph = Graph::new();
+        for leaf in 1..6 {
+            graph.add_edge(0, leaf);
+        }
+        // Every edge joins the hub to a leaf, so the correlation is exactly -1
+        assert!((graph.degree_assortativity() + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_regular_graph_assortativity_is_nan() {
+        let mut graph = Graph::new();
+        for i in 0..6 {
+            graph.add_edge(i, (i + 1) % 6);
+        }
+        assert!((graph.density() - 0.4).abs() < 1e-12);
+        assert!(graph.degree_assortativity().is_nan());
+        assert!(Graph::new().degree_assortativity().is_nan());
+    }
+
+    #[test]
+    fn test_sample_edges() {
+        let mut rng = crate::operators::seeded_rng(5, 0, 0);
+        let graph = generators::erdos_renyi(60, 0.3, &mut rng);
+
+        let full = graph.sample_edges(1.0, &mut rng);
+        assert_eq!(full.nodes, graph.nodes);
+        assert_eq!(full.edges, graph.edges);
+
+        let half = graph.sample_edges(0.5, &mut rng);
+        assert_eq!(half.nodes, graph.nodes);
+        assert!(half.num_edges() < graph.num_edges());
+        assert!(half.edges.iter().all(|edge| graph.edges.contains(edge)));
+    }
+
+    #[test]
+    fn test_subgraph_of_clique_is_the_clique() {
+        let mut graph = Graph::new();
+        for i in 0..5 {
+            for j in (i + 1)..5 {
+                graph.add_weighted_edge(i, j, 1.0 + (i + j) as f64);
+            }
+        }
+        graph.add_edge(4, 5);
+        graph.add_edge(5, 6);
+        graph.add_node(7);
+
+        let clique: HashSet<NodeId> = (0..5).collect();
+        let subgraph = graph.subgraph(&clique);
+        assert_eq!(subgraph.nodes, clique);
+        assert_eq!(subgraph.num_edges(), 10);
+        for &(u, v) in &subgraph.edges {
+            assert_eq!(subgraph.weight(u, v), graph.weight(u, v));
+        }
+        assert_eq!(subgraph.edges, graph.edges[..10]);
+    }
+
+    #[test]
+    fn test_remove_edge_isolates_nodes() {
+        let mut graph = Graph::new();
+        graph.add_weighted_edge(0, 1, 2.0);
+        graph.add_edge(1, 2);
+        graph.add_edge(2, 2);
+
+        assert!(graph.remove_edge(1, 0));
+        assert!(!graph.remove_edge(0, 1));
+        assert_eq!(graph.num_edges(), 2);
+        assert_eq!(graph.num_nodes(), 3);
+        assert!(!graph.adjacency_list.contains_key(&0));
+        assert!(!graph.is_weighted());
+        assert_eq!(graph.degree_sequence(), vec![0, 1, 3]);
+
+        assert!(graph.remove_edge(2, 2));
+        assert!(graph.remove_edge(1, 2));
+        assert_eq!(graph.num_edges(), 0);
+        assert!(graph.adjacency_list.is_empty());
+        assert_eq!(graph.degree_sequence(), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_remove_directed_edge() {
+        let mut graph = Graph::new_directed();
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 0);
+
+        assert!(!graph.has_edge(0, 2));
+        assert!(graph.remove_edge(1, 0));
+        assert!(graph.has_edge(0, 1));
+        assert!(!graph.has_edge(1, 0));
+        assert_eq!(graph.edges, vec![(0, 1)]);
+        assert_eq!(graph.successors(&1), &[] as &[NodeId]);
+        assert_eq!(graph.predecessors(&1), &[0]);
+    }
+
+    #[test]
+    fn test_core_numbers_of_triangle_with_tail() {
+        let mut graph = Graph::new();
+        for (u, v) in [(0, 1), (1, 2), (0, 2), (2, 3), (3, 4)] {
+            graph.add_edge(u, v);
+        }
+        graph.add_node(5);
+
+        let cores = graph.core_numbers();
+        assert_eq!(cores.len(), 6);
+        for node in 0..3 {
+            assert_eq!(cores[&node], 2);
+        }
+        assert_eq!(cores[&3], 1);
+        assert_eq!(cores[&4], 1);
+        assert_eq!(cores[&5], 0);
+    }
+
+    #[test]
+    fn test_leading_eigenvector_signs_split_two_cliques() {
+        let mut graph = Graph::new();
+        for offset in [0, 5] {
+            for i in offset..offset + 5 {
+                for j in (i + 1)..offset + 5 {
+                    graph.add_edge(i, j);
+                }
+            }
+        }
+        graph.add_edge(4, 5);
+
+        let vector = graph.leading_eigenvector(200);
+        assert_eq!(vector.len(), 10);
+        let norm: f64 = vector.iter().map(|(_, v)| v * v).sum();
+        assert!((norm - 1.0).abs() < 1e-9);
+        let first_sign = vector[0].1 > 0.0;
+        for &(node, value) in &vector {
+            assert!(value.abs() > 1e-6);
+            assert_eq!(value > 0.0, (node < 5) == first_sign, "node {}", node);
+        }
+    }
+
+    #[test]
+    fn test_triangle_and_star_clustering() {
+        let mut triangle = Graph::new();
+        for (u, v) in [(0, 1), (1, 2), (2, 0)] {
+            triangle.add_edge(u, v);
+        }
+        assert_eq!(triangle.triangle_count(), 1);
+        assert_eq!(triangle.average_clustering(), 1.0);
+
+        let mut star = Graph::new();
+        for leaf in 1..6 {
+            star.add_edge(0, leaf);
+        }
+        assert_eq!(star.triangle_count(), 0);
+        assert_eq!(star.average_clustering(), 0.0);
+
+        // A tail node of degree 1 and a self-loop on the triangle change nothing but N
+        triangle.add_edge(2, 3);
+        triangle.add_edge(0, 0);
+        assert_eq!(triangle.triangle_count(), 1);
+        let expected = (1.0 + 1.0 + 1.0 / 3.0 + 0.0) / 4.0;
+        assert!((triangle.average_clustering() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_holdout_edges_proportions() {
+        let mut graph = Graph::new();
+        for i in 0..10 {
+            for j in (i + 1)..10 {
+                graph.add_edge(i, j);
+            }
+        }
+        let mut rng = crate::operators::seeded_rng(4, 0, 0);
+
+        let (train, test) = graph.holdout_edges(0.2, &mut rng);
+        assert_eq!(test.num_edges(), 9);
+        assert_eq!(train.num_edges(), 36);
+        assert_eq!(train.num_nodes(), 10);
+        assert!(train.degree_sequence().iter().all(|&degree| degree > 0));
+        assert!(test.edges.iter().all(|&(u, v)| !train.has_edge(u, v)));
+
+        // Every edge of a path has an end of degree one or is the last train edge
+        let mut path = Graph::new();
+        path.add_edge(0, 1);
+        path.add_edge(1, 2);
+        let (train, test) = path.holdout_edges(1.0, &mut rng);
+        assert_eq!(train.edges, path.edges);
+        assert_eq!(test.num_edges(), 0);
+    }
 }
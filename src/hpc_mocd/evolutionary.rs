@@ -0,0 +1,468 @@
+//! hpc_mocd/evolutionary.rs
+//! Implements the NSGA-II generational loop
+//! This Source Code Form is subject to the terms of The GNU General Public License v3.0
+//! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
+//! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
+
+use crate::graph::Graph;
+use crate::hpc_mocd::individual::{Individual, create_offspring};
+use crate::hpc_mocd::utils::{calculate_crowding_distance, fast_non_dominated_sort};
+use crate::operators::{
+    self, EvolutionConfig, EvolutionResult, GenerationCallback, ObjectiveFn, seeded_rng,
+};
+
+use rayon::prelude::*;
+use rustc_hash::FxBuildHasher;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Scores every individual with `objectives`, or with [intra, inter] when it is None,
+/// rounded to `config.round_objectives` decimals
+fn evaluate_population(
+    individuals: &mut [Individual],
+    graph: &Graph,
+    degrees: &HashMap<i32, usize, FxBuildHasher>,
+    config: &EvolutionConfig,
+    objectives: Option<&ObjectiveFn<'_>>,
+) {
+    individuals.par_iter_mut().for_each(|ind| {
+        ind.objectives = match objectives {
+            Some(objectives) => objectives(&ind.partition),
+            None => {
+                let metrics = operators::get_fitness(
+                    graph,
+                    &ind.partition,
+                    degrees,
+                    true,
+                    config.resolution,
+                    config.modularity_variant,
+                );
+                vec![metrics.intra, metrics.inter]
+            }
+        };
+        operators::round_objectives(&mut ind.objectives, config.round_objectives);
+        ind.calculate_fitness();
+    });
+}
+
+/// Ranks and sorts the population, then keeps the first `pop_size`. With `elitism`, the
+/// max-Q individual takes the last place kept if crowding would have dropped it.
+fn update_population_sort_and_truncate(
+    individuals: &mut Vec<Individual>,
+    pop_size: usize,
+    elitism: bool,
+) {
+    fast_non_dominated_sort(individuals);
+    calculate_crowding_distance(individuals);
+    individuals.sort_unstable_by(|a, b| {
+        a.rank.cmp(&b.rank).then_with(|| {
+            b.crowding_distance
+                .partial_cmp(&a.crowding_distance)
+                .unwrap_or(Ordering::Equal)
+        })
+    });
+    if elitism && pop_size > 0 && individuals.len() > pop_size {
+        let best = (0..individuals.len())
+            .max_by(|&a, &b| {
+                individuals[a]
+                    .fitness
+                    .partial_cmp(&individuals[b].fitness)
+                    .unwrap_or(Ordering::Equal)
+            })
+            .unwrap();
+        if best >= pop_size {
+            individuals.swap(best, pop_size - 1);
+        }
+    }
+    individuals.truncate(pop_size);
+}
+
+/// Runs NSGA-II and returns the first (non-dominated) front with the best Q per generation
+pub fn evolutionary_phase(
+    graph: &Graph,
+    debug_level: i8,
+    config: &EvolutionConfig,
+    on_generation: Option<&GenerationCallback<'_>>,
+    objectives: Option<&ObjectiveFn<'_>>,
+) -> EvolutionResult<Individual> {
+    let (individuals, history) = run(graph, debug_level, config, on_generation, objectives);
+
+    // Extract the Pareto front (first front).
+    let front = individuals
+        .into_iter()
+        .filter(|ind| ind.rank == 1)
+        .collect();
+    EvolutionResult { front, history }
+}
+
+/// Runs NSGA-II and returns the whole final population, re-ranked on its own and sorted
+/// by rank (1 is the non-dominated front), then by decreasing crowding distance
+pub fn ranked_population(
+    graph: &Graph,
+    debug_level: i8,
+    config: &EvolutionConfig,
+    on_generation: Option<&GenerationCallback<'_>>,
+    objectives: Option<&ObjectiveFn<'_>>,
+) -> Vec<Individual> {
+    let (mut individuals, _) = run(graph, debug_level, config, on_generation, objectives);
+    let len = individuals.len();
+    update_population_sort_and_truncate(&mut individuals, len, false);
+    individuals
+}
+
+/// The generational loop: final population and best Q per generation
+fn run(
+    graph: &Graph,
+    debug_level: i8,
+    config: &EvolutionConfig,
+    on_generation: Option<&GenerationCallback<'_>>,
+    objectives: Option<&ObjectiveFn<'_>>,
+) -> (Vec<Individual>, Vec<f64>) {
+    let degrees = &graph.precompute_degrees();
+
+    // Nothing to optimize without edges: the front is the singleton partition
+    if graph.edges.is_empty() {
+        let mut partition = operators::singleton_partition(graph);
+        operators::enforce_links(&mut partition, config);
+        let mut individuals = vec![Individual::new(partition)];
+        evaluate_population(&mut individuals, graph, degrees, config, objectives);
+        individuals[0].rank = 1;
+        return (individuals, Vec::new());
+    }
+
+    let seed = config.resolve_seed();
+    let mut rng = seeded_rng(seed, 0, 0);
+    let mut population =
+        operators::generate_population(graph, config.pop_size, config.init_strategy, &mut rng);
+    operators::warm_start(&mut population, config.initial_partition.as_ref());
+    for partition in &mut population {
+        operators::constrain_communities(partition, graph, config, &mut rng);
+    }
+    let mut individuals: Vec<Individual> =
+        population.into_par_iter().map(Individual::new).collect();
+    evaluate_population(&mut individuals, graph, degrees, config, objectives);
+
+    let mut max_local = config.convergence();
+    let mut history: Vec<f64> = Vec::with_capacity(config.num_gens);
+    let start = Instant::now();
+    for generation in 0..config.num_gens {
+        // Out of time: the population was ranked at the end of the last generation
+        if generation > 0 && config.time_exceeded(start) {
+            if debug_level >= 1 {
+                log::info!("[evolutionary_phase]: Time limit reached");
+            }
+            break;
+        }
+
+        let len = individuals.len();
+        update_population_sort_and_truncate(&mut individuals, len, false);
+
+        // Create offspring and evaluate them.
+        let mut offspring = create_offspring(&individuals, graph, config, seed, generation);
+        evaluate_population(&mut offspring, graph, degrees, config, objectives);
+
+        // Combine and prepare for environmental selection.
+        individuals.extend(offspring);
+        update_population_sort_and_truncate(&mut individuals, config.pop_size, config.elitism);
+
+        // Record best fitness.
+        let best_fitness = individuals
+            .par_iter()
+            .map(|ind| ind.fitness)
+            .max_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .unwrap_or(f64::NEG_INFINITY);
+        history.push(best_fitness);
+
+        if let Some(callback) = on_generation
+            && !callback(generation, best_fitness)
+        {
+            break;
+        }
+
+        if max_local.has_converged(best_fitness) {
+            if debug_level >= 1 {
+                log::info!("[evolutionary_phase]: Converged!");
+            }
+            break;
+        }
+
+        if debug_level >= 1 && (generation % 10 == 0 || generation == config.num_gens - 1) {
+            let first_front_size = individuals.iter().filter(|ind| ind.rank == 1).count();
+            log::info!(
+                "NSGA-II: Gen {} | Best fitness: {:.4} | First front size: {} | Pop size: {}",
+                generation,
+                best_fitness,
+                first_front_size,
+                individuals.len()
+            );
+        }
+    }
+
+    (individuals, history)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn two_cliques() -> Graph {
+        let mut graph = Graph::new();
+        for offset in [0, 5] {
+            for i in offset..offset + 5 {
+                for j in (i + 1)..offset + 5 {
+                    graph.add_edge(i, j);
+                }
+            }
+        }
+        graph.add_edge(4, 5);
+        graph
+    }
+
+    fn config(seed: Option<u64>) -> EvolutionConfig {
+        EvolutionConfig {
+            num_gens: 20,
+            pop_size: 40,
+            seed,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_edgeless_graphs_return_singletons() {
+        for num_nodes in 0..3 {
+            let mut graph = Graph::new();
+            for node in 0..num_nodes {
+                graph.add_node(node);
+            }
+
+            let result = evolutionary_phase(&graph, 0, &config(Some(1)), None, None);
+            assert_eq!(result.front.len(), 1);
+            assert_eq!(
+                result.front[0].partition,
+                operators::singleton_partition(&graph)
+            );
+            assert_eq!(result.front[0].fitness, 0.0);
+            assert!(result.history.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_seeded_runs_are_identical() {
+        let graph = two_cliques();
+        let first = evolutionary_phase(&graph, 0, &config(Some(7)), None, None).front;
+        let second = evolutionary_phase(&graph, 0, &config(Some(7)), None, None).front;
+
+        assert_eq!(first.len(), second.len());
+        for (a, b) in first.iter().zip(second.iter()) {
+            assert_eq!(a.partition, b.partition);
+            assert_eq!(a.objectives, b.objectives);
+        }
+    }
+
+    #[test]
+    fn test_thread_count_does_not_change_seeded_runs() {
+        let graph = two_cliques();
+        let run = |num_threads| {
+            operators::install(Some(num_threads), || {
+                evolutionary_phase(&graph, 0, &config(Some(7)), None, None).front
+            })
+            .unwrap()
+        };
+
+        let single = run(1);
+        let multi = run(4);
+        assert_eq!(single.len(), multi.len());
+        for (a, b) in single.iter().zip(multi.iter()) {
+            assert_eq!(a.partition, b.partition);
+            assert_eq!(a.objectives, b.objectives);
+        }
+    }
+
+    #[test]
+    fn test_reloaded_state_gives_identical_front() {
+        use crate::persist::{Decode, Encode};
+
+        let graph = two_cliques();
+        let mut bytes = Vec::new();
+        (&graph, &config(Some(7))).encode(&mut bytes);
+        let (reloaded, reloaded_config): (Graph, EvolutionConfig) =
+            Decode::decode(&mut bytes.as_slice()).unwrap();
+
+        let original = evolutionary_phase(&graph, 0, &config(Some(7)), None, None).front;
+        let restored = evolutionary_phase(&reloaded, 0, &reloaded_config, None, None).front;
+        assert_eq!(original.len(), restored.len());
+        for (a, b) in original.iter().zip(restored.iter()) {
+            assert_eq!(a.partition, b.partition);
+            assert_eq!(a.objectives, b.objectives);
+        }
+    }
+
+    #[test]
+    fn test_callback_can_stop_the_run() {
+        let graph = two_cliques();
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let callback = |generation: usize, _: f64| {
+            calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            generation < 2
+        };
+
+        evolutionary_phase(&graph, 0, &config(Some(7)), Some(&callback), None);
+        assert_eq!(calls.into_inner(), 3);
+    }
+
+    #[test]
+    fn test_num_communities_is_enforced() {
+        let graph = two_cliques();
+        let config = EvolutionConfig {
+            num_communities: Some(3),
+            ..config(Some(5))
+        };
+
+        let front = evolutionary_phase(&graph, 0, &config, None, None).front;
+        assert!(!front.is_empty());
+        for solution in &front {
+            let labels: std::collections::BTreeSet<_> = solution.partition.values().collect();
+            assert_eq!(labels.len(), 3);
+        }
+    }
+
+    #[test]
+    fn test_link_constraints_hold_across_the_front() {
+        let graph = two_cliques();
+        let config = EvolutionConfig {
+            must_link: vec![(0, 9)],
+            cannot_link: vec![(1, 2)],
+            ..config(Some(5))
+        };
+
+        let front = evolutionary_phase(&graph, 0, &config, None, None).front;
+        assert!(!front.is_empty());
+        for solution in &front {
+            assert_eq!(solution.partition[&0], solution.partition[&9]);
+            assert_ne!(solution.partition[&1], solution.partition[&2]);
+        }
+    }
+
+    #[test]
+    fn test_max_community_size_is_enforced() {
+        let mut graph = Graph::new();
+        for i in 0..12 {
+            for j in (i + 1)..12 {
+                graph.add_edge(i, j);
+            }
+        }
+        let config = EvolutionConfig {
+            max_community_size: Some(4),
+            ..config(Some(5))
+        };
+
+        let front = evolutionary_phase(&graph, 0, &config, None, None).front;
+        assert!(!front.is_empty());
+        for solution in &front {
+            let mut sizes = std::collections::BTreeMap::new();
+            for community in solution.partition.values() {
+                *sizes.entry(community).or_insert(0) += 1;
+            }
+            assert!(sizes.values().all(|&size| size <= 4), "{:?}", sizes);
+        }
+    }
+
+    #[test]
+    fn test_time_limit_keeps_first_generation() {
+        let graph = two_cliques();
+        let config = EvolutionConfig {
+            num_gens: 1_000,
+            time_limit_secs: Some(0.0),
+            ..config(Some(3))
+        };
+        let generations = std::sync::atomic::AtomicUsize::new(0);
+        let callback = |_: usize, _: f64| {
+            generations.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            true
+        };
+
+        let front = evolutionary_phase(&graph, 0, &config, Some(&callback), None).front;
+        assert_eq!(generations.into_inner(), 1);
+        assert!(!front.is_empty());
+    }
+
+    #[test]
+    fn test_custom_objectives_replace_intra_inter() {
+        let graph = two_cliques();
+        // Minimized only by partitions with exactly four communities
+        let objectives = |partition: &crate::graph::Partition| {
+            let communities: std::collections::BTreeSet<_> = partition.values().collect();
+            vec![(communities.len() as f64 - 4.0).abs(), 0.0]
+        };
+
+        let front = evolutionary_phase(&graph, 0, &config(Some(7)), None, Some(&objectives)).front;
+        assert!(!front.is_empty());
+        for solution in &front {
+            assert_eq!(solution.objectives, vec![0.0, 0.0]);
+            assert_eq!(solution.fitness, 1.0);
+        }
+    }
+
+    #[test]
+    fn test_ranked_population_keeps_every_front() {
+        let graph = two_cliques();
+        let population = ranked_population(&graph, 0, &config(Some(4)), None, None);
+        assert_eq!(population.len(), config(None).pop_size);
+
+        let front: Vec<_> = population.iter().filter(|ind| ind.rank == 1).collect();
+        assert!(!front.is_empty());
+        for a in &front {
+            assert!(front.iter().all(|b| !b.dominates(a)));
+        }
+        // Every later front is dominated by some member of the one before it
+        for ind in population.iter().filter(|ind| ind.rank > 1) {
+            assert!(
+                population
+                    .iter()
+                    .any(|other| other.rank == ind.rank - 1 && other.dominates(ind))
+            );
+        }
+        assert!(
+            population
+                .windows(2)
+                .all(|pair| pair[0].rank <= pair[1].rank)
+        );
+    }
+
+    #[test]
+    fn test_patience_stops_early() {
+        let graph = two_cliques();
+        let config = EvolutionConfig {
+            num_gens: 500,
+            patience: Some(5),
+            ..config(Some(3))
+        };
+        let generations = std::sync::atomic::AtomicUsize::new(0);
+        let callback = |_: usize, _: f64| {
+            generations.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            true
+        };
+
+        let result = evolutionary_phase(&graph, 0, &config, Some(&callback), None);
+        let generations = generations.into_inner();
+        assert!(generations < 100);
+        assert_eq!(result.history.len(), generations);
+    }
+
+    #[test]
+    fn test_elitism_keeps_best_q_non_decreasing() {
+        let graph = two_cliques();
+        for seed in 0..4 {
+            let config = EvolutionConfig {
+                pop_size: 6,
+                patience: None,
+                ..config(Some(seed))
+            };
+
+            let history = evolutionary_phase(&graph, 0, &config, None, None).history;
+            assert_eq!(history.len(), config.num_gens);
+            assert!(history.windows(2).all(|pair| pair[1] >= pair[0]));
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use crate::graph::{Graph, Partition};
 
-use crate::operators;
+use crate::operators::{self, CrossoverType, EvolutionConfig, seeded_rng};
+use crate::persist::{Decode, Encode};
 
-use rand::{prelude::*, rng};
+use rand::prelude::*;
+use rand::seq::index;
 use rayon::prelude::*;
 use rustc_hash::FxHashSet as HashSet;
-use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 
 const ENSEMBLE_SIZE: usize = 4;
 
@@ -52,15 +53,41 @@ impl Individual {
     }
 }
 
-// Tournament selection with early return
+impl Encode for Individual {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.partition.encode(out);
+        self.objectives.encode(out);
+        self.rank.encode(out);
+        self.crowding_distance.encode(out);
+        self.fitness.encode(out);
+    }
+}
+
+impl Decode for Individual {
+    fn decode(input: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Individual {
+            partition: Decode::decode(input)?,
+            objectives: Decode::decode(input)?,
+            rank: Decode::decode(input)?,
+            crowding_distance: Decode::decode(input)?,
+            fitness: Decode::decode(input)?,
+        })
+    }
+}
+
+// k-way tournament over distinct individuals: lowest rank wins, then largest crowding
+// distance. A tournament as large as the population always returns its best member.
 #[inline]
-pub fn tournament_selection(population: &[Individual], tournament_size: usize) -> &Individual {
-    let mut rng: ThreadRng = rng();
-    let best_idx: usize = rng.random_range(0..population.len());
-    let mut best: &Individual = &population[best_idx];
+pub fn tournament_selection<'a>(
+    population: &'a [Individual],
+    tournament_size: usize,
+    rng: &mut impl Rng,
+) -> &'a Individual {
+    let tournament_size = tournament_size.clamp(1, population.len());
+    let mut candidates = index::sample(rng, population.len(), tournament_size).into_iter();
+    let mut best: &Individual = &population[candidates.next().unwrap()];
 
-    for _ in 1..tournament_size {
-        let candidate_idx: usize = rng.random_range(0..population.len());
+    for candidate_idx in candidates {
         let candidate: &Individual = &population[candidate_idx];
 
         if candidate.rank < best.rank
@@ -77,74 +104,102 @@ pub fn tournament_selection(population: &[Individual], tournament_size: usize) -
 pub fn create_offspring(
     population: &[Individual],
     graph: &Graph,
-    crossover_rate: f64,
-    mutation_rate: f64,
-    tournament_size: usize,
+    config: &EvolutionConfig,
+    seed: u64,
+    generation: usize,
 ) -> Vec<Individual> {
-    let pop_size = population.len();
-    let mut offspring = Vec::with_capacity(pop_size);
-    let num_threads = rayon::current_num_threads();
-    let chunk_size = pop_size.div_ceil(num_threads);
-
-    // Use atomic counter for better load balancing
-    let offspring_counter = AtomicUsize::new(0);
-
-    let thread_offsprings: Vec<Vec<Individual>> = (0..num_threads)
+    (0..population.len())
         .into_par_iter()
-        .map(|_| {
-            let mut local_rng = rng();
-            let mut local_offspring = Vec::with_capacity(chunk_size);
-
-            while offspring_counter.fetch_add(1, AtomicOrdering::Relaxed) < pop_size {
-                // Select unique parents
-                let mut parents = Vec::with_capacity(ENSEMBLE_SIZE);
-                let mut selected_ids =
-                    HashSet::with_capacity_and_hasher(ENSEMBLE_SIZE, Default::default());
-
-                let mut attempts = 0;
-                while parents.len() < ENSEMBLE_SIZE && attempts < 50 {
-                    let parent = tournament_selection(population, tournament_size);
-                    if selected_ids.insert(parent.rank) {
-                        parents.push(parent);
-                    }
-                    attempts += 1;
-                }
-
-                // Fill remaining slots if needed
-                while parents.len() < ENSEMBLE_SIZE {
-                    parents.push(tournament_selection(population, tournament_size));
+        .map(|index| {
+            // Each child draws from its own stream, keeping runs reproducible
+            let mut local_rng = seeded_rng(seed, generation + 1, index);
+
+            // Select unique parents
+            let mut parents = Vec::with_capacity(ENSEMBLE_SIZE);
+            let mut selected_ids =
+                HashSet::with_capacity_and_hasher(ENSEMBLE_SIZE, Default::default());
+
+            let mut attempts = 0;
+            while parents.len() < ENSEMBLE_SIZE && attempts < 50 {
+                let parent =
+                    tournament_selection(population, config.tournament_size, &mut local_rng);
+                if selected_ids.insert(parent.rank) {
+                    parents.push(parent);
                 }
+                attempts += 1;
+            }
 
-                let parent_partitions: Vec<Partition> =
-                    parents.iter().map(|p| p.partition.clone()).collect();
+            // Fill remaining slots if needed
+            while parents.len() < ENSEMBLE_SIZE {
+                parents.push(tournament_selection(
+                    population,
+                    config.tournament_size,
+                    &mut local_rng,
+                ));
+            }
 
-                let parent_slice: &[Partition] = &parent_partitions;
-                let should_crossover = local_rng.random::<f64>() < crossover_rate;
+            let parent_partitions: Vec<Partition> =
+                parents.iter().map(|p| p.partition.clone()).collect();
 
-                let mut child = if should_crossover {
-                    operators::ensemble_crossover(parent_slice, 1.0)
-                } else {
-                    parent_partitions[0].clone()
-                };
+            let parent_slice: &[Partition] = &parent_partitions;
+            let should_crossover = local_rng.random::<f64>() < config.cross_rate;
 
-                operators::mutation(&mut child, graph, mutation_rate);
-                local_offspring.push(Individual::new(child));
-            }
+            let mut child = if should_crossover {
+                match config.crossover_type {
+                    None | Some(CrossoverType::Ensemble) => {
+                        operators::ensemble_crossover(parent_slice, 1.0, &mut local_rng)
+                    }
+                    Some(kind) => operators::crossover(
+                        &parent_partitions[0],
+                        &parent_partitions[1],
+                        kind,
+                        1.0,
+                        &mut local_rng,
+                    ),
+                }
+            } else {
+                parent_partitions[0].clone()
+            };
 
-            local_offspring
+            operators::mutate_child(&mut child, graph, config, generation, &mut local_rng);
+            operators::constrain_communities(&mut child, graph, config, &mut local_rng);
+            Individual::new(child)
         })
-        .collect();
-
-    // Combine results, only taking what we need
-    let mut remaining = pop_size;
-    for mut thread_offspring in thread_offsprings {
-        let to_take = remaining.min(thread_offspring.len());
-        offspring.extend(thread_offspring.drain(..to_take));
-        remaining -= to_take;
-        if remaining == 0 {
-            break;
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ranked_population() -> Vec<Individual> {
+        (0..10)
+            .map(|i| Individual {
+                rank: 1 + i % 4,
+                crowding_distance: i as f64,
+                ..Individual::new(Partition::new())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_full_tournament_picks_the_best() {
+        let population = ranked_population();
+        let mut rng = seeded_rng(3, 0, 0);
+
+        // Rank 1 holds individuals 0, 4 and 8; the widest crowding distance breaks the tie
+        for _ in 0..20 {
+            let winner = tournament_selection(&population, population.len(), &mut rng);
+            assert_eq!((winner.rank, winner.crowding_distance), (1, 8.0));
         }
     }
 
-    offspring
+    #[test]
+    fn test_oversized_tournament_is_clamped() {
+        let population = ranked_population();
+        let mut rng = seeded_rng(3, 0, 0);
+
+        let winner = tournament_selection(&population, 100, &mut rng);
+        assert_eq!((winner.rank, winner.crowding_distance), (1, 8.0));
+    }
 }
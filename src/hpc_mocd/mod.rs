@@ -3,130 +3,169 @@
 //! Copyright 2024 - Guilherme Santos.
 //! If a copy of the GPL was not distributed with this file, you can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
 
+mod evolutionary;
 mod individual;
 mod utils;
 
-use crate::graph::{Graph, Partition};
-use crate::operators;
-use crate::utils::{build_graph, get_edges, normalize_community_ids};
-use individual::{Individual, create_offspring};
-use utils::{calculate_crowding_distance, fast_non_dominated_sort, max_q_selection};
+use crate::graph::{CommunityId, Graph, NodeId, Partition};
+use crate::local;
+use crate::operators::{
+    EvolutionConfig, EvolutionResult, GenerationCallback, ObjectiveFn, assign_node, get_fitness,
+    get_modularity_from_partition, hypervolume, knee_point, merge_gains, soft_memberships,
+};
+use crate::persist;
+use crate::utils::{
+    EdgeFile, EdgeRules, LabeledEdges, LinkPairs, NodeLabels, ParetoIterator, add_edge_with_rules,
+    adjacency_array, adjacency_dict, build_graph, check_overlap_threshold, check_partition_nodes,
+    check_rate, check_resolution, check_round_objectives, check_typed_ends, community_count,
+    community_sets, edge_list, edge_node_ids, enable_debug_logging, from_partition, front_csv,
+    get_edges, link_constraints, new_node_ids, node_id, node_labels, normalize_community_ids,
+    objective_dict, objectives_array, parse_crossover_type, parse_init_strategy,
+    parse_modularity_variant, parse_mutation_schedule, partition_records, python_objectives,
+    records_with_labels, sample_graph, set_bipartite, set_node_weights, to_partition,
+    warn_if_disconnected, with_generation_callback,
+};
+use individual::Individual;
+use utils::max_q_selection;
+pub use utils::pareto_filter;
 
+use pyo3::exceptions::{PyRuntimeError, PyUserWarning, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyAny;
-use rayon::prelude::*;
-use rustc_hash::FxBuildHasher;
-use std::cmp::Ordering;
-use std::collections::HashMap;
+use pyo3::types::{PyAny, PyDict};
+use rustc_hash::FxHashSet as HashSet;
+use std::collections::BTreeMap;
+use std::ffi::CString;
+use std::sync::Mutex;
+use std::time::Instant;
 
-const TOURNAMENT_SIZE: usize = 2;
+/// `(partition, [intra, inter], rank, crowding_distance)` as returned by `generate_fronts`
+type RankedSolution<'py> = (Bound<'py, PyDict>, Vec<f64>, usize, f64);
 
 #[pyclass]
 pub struct HpMocd {
     graph: Graph,
     debug_level: i8,
-    pop_size: usize,
-    num_gens: usize,
-    cross_rate: f64,
-    mut_rate: f64,
+    config: EvolutionConfig,
+    archive: Option<Vec<Individual>>, // Cached by `fit`
+    history: Vec<f64>,                // Best Q per generation of the last `fit`
+    runtime_secs: f64,                // Wall-clock time of the last `fit`
+    labels: Option<NodeLabels>,       // Original node labels when they were not i32 ids
+    edge_rules: EdgeRules,            // Self-loop and parallel-edge handling for `add_edge`
+    on_generation: Option<Py<PyAny>>,
+    objectives: Option<Vec<Py<PyAny>>>, // Python replacements for [intra, inter]
 }
 
+/// Population size above which `set_objectives` warns that every generation makes
+/// that many round trips into Python
+const PYTHON_OBJECTIVES_WARN_POP_SIZE: usize = 200;
+
 /* Private (Not exposed to py user) */
 impl HpMocd {
-    fn evaluate_population(
-        &self,
-        individuals: &mut [Individual],
-        graph: &Graph,
-        degrees: &HashMap<i32, usize, FxBuildHasher>,
-    ) {
-        individuals.par_iter_mut().for_each(|ind| {
-            let metrics = operators::get_fitness(graph, &ind.partition, degrees, true);
-            ind.objectives = vec![metrics.intra, metrics.inter];
-            ind.calculate_fitness();
-        });
-    }
-
-    fn update_population_sort_and_truncate(
-        &self,
-        individuals: &mut Vec<Individual>,
-        pop_size: usize,
-    ) {
-        fast_non_dominated_sort(individuals);
-        calculate_crowding_distance(individuals);
-        individuals.sort_unstable_by(|a, b| {
-            a.rank.cmp(&b.rank).then_with(|| {
-                b.crowding_distance
-                    .partial_cmp(&a.crowding_distance)
-                    .unwrap_or(Ordering::Equal)
-            })
-        });
-        individuals.truncate(pop_size);
-    }
-
-    fn envolve(&self) -> Vec<Individual> {
+    fn envolve(&self) -> PyResult<EvolutionResult<Individual>> {
+        enable_debug_logging(self.debug_level);
         if self.debug_level >= 1 {
             self.graph.print();
         }
 
-        let degrees = &self.graph.precompute_degrees();
-        let mut individuals: Vec<Individual> =
-            operators::generate_population(&self.graph, self.pop_size)
-                .into_par_iter()
-                .map(Individual::new)
-                .collect();
-        self.evaluate_population(&mut individuals, &self.graph, degrees);
+        self.run_phase(|on_generation, objectives| {
+            evolutionary::evolutionary_phase(
+                &self.graph,
+                self.debug_level,
+                &self.config,
+                on_generation,
+                objectives,
+            )
+        })
+    }
 
-        let mut max_local = operators::ConvergenceCriteria::default();
-        for generation in 0..self.num_gens {
-            let len = individuals.len();
-            self.update_population_sort_and_truncate(&mut individuals, len);
+    /// Runs `phase` with the `on_generation` hook and, once `set_objectives` was
+    /// called, the Python objectives. If an objective raises, the run stops at the end
+    /// of that generation and the exception is returned instead of the result.
+    fn run_phase<T: Send>(
+        &self,
+        phase: impl FnOnce(Option<&GenerationCallback<'_>>, Option<&ObjectiveFn<'_>>) -> T + Send,
+    ) -> PyResult<T> {
+        let error: Mutex<Option<PyErr>> = Mutex::new(None);
+        let objectives = self
+            .objectives
+            .as_deref()
+            .map(|callables| python_objectives(callables, self.labels.as_ref(), &error));
 
-            // Create offspring and evaluate them.
-            let mut offspring = create_offspring(
-                &individuals,
-                &self.graph,
-                self.cross_rate,
-                self.mut_rate,
-                TOURNAMENT_SIZE,
-            );
-            self.evaluate_population(&mut offspring, &self.graph, degrees);
-
-            // Combine and prepare for environmental selection.
-            individuals.extend(offspring);
-            self.update_population_sort_and_truncate(&mut individuals, self.pop_size);
-
-            // Record best fitness.
-            let best_fitness = individuals
-                .par_iter()
-                .map(|ind| ind.fitness)
-                .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-                .unwrap_or(f64::NEG_INFINITY);
-
-            if max_local.has_converged(best_fitness) {
-                if self.debug_level >= 1 {
-                    println!("[evolutionary_phase]: Converged!");
+        let result = with_generation_callback(
+            self.on_generation.as_ref(),
+            self.config.num_threads,
+            |on_generation| match &objectives {
+                Some(objectives) => {
+                    let hook = |generation: usize, best_q: f64| {
+                        error.lock().unwrap().is_none()
+                            && on_generation.is_none_or(|hook| hook(generation, best_q))
+                    };
+                    phase(Some(&hook), Some(objectives))
                 }
-                break;
-            }
+                None => phase(on_generation, None),
+            },
+        )?;
+        match error.lock().unwrap().take() {
+            Some(err) => Err(err),
+            None => Ok(result),
+        }
+    }
 
-            if self.debug_level >= 1 && (generation % 10 == 0 || generation == self.num_gens - 1) {
-                let first_front_size = individuals.iter().filter(|ind| ind.rank == 1).count();
-                println!(
-                    "NSGA-II: Gen {} | Best fitness: {:.4} | First front size: {} | Pop size: {}",
-                    generation,
-                    best_fitness,
-                    first_front_size,
-                    individuals.len()
-                );
-            }
+    /// Normalized partition as a dict keyed by the original node labels
+    fn to_dict<'py>(&self, py: Python<'py>, partition: Partition) -> PyResult<Bound<'py, PyDict>> {
+        from_partition(
+            py,
+            &normalize_community_ids(partition),
+            self.labels.as_ref(),
+        )
+    }
+
+    /// Like `to_dict`, but lists every community of a node when `overlap_threshold`
+    /// is set (see `operators::soft_memberships`)
+    fn to_soft_dict<'py>(
+        &self,
+        py: Python<'py>,
+        partition: Partition,
+        overlap_threshold: Option<f64>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let Some(threshold) = overlap_threshold else {
+            return self.to_dict(py, partition);
+        };
+        let partition = normalize_community_ids(partition);
+        let memberships = soft_memberships(&self.graph, &partition, threshold);
+        from_partition(py, &memberships, self.labels.as_ref())
+    }
+
+    fn fitted_archive(&self) -> PyResult<&[Individual]> {
+        self.archive
+            .as_deref()
+            .ok_or_else(|| PyRuntimeError::new_err("HpMocd is not fitted yet, call fit() first"))
+    }
+
+    /// Runs the evolutionary phase unless a front is already cached, by `fit` or an
+    /// earlier call; `reset` drops the cache
+    fn ensure_fitted(&mut self) -> PyResult<()> {
+        if self.archive.is_none() {
+            self.refit()?;
         }
+        Ok(())
+    }
 
-        // Extract the Pareto front (first front).
-        individuals
-            .iter()
-            .filter(|ind| ind.rank == 1)
-            .cloned()
-            .collect()
+    /// The max-Q partition of the fitted front with its Q, shared by `run`, `best` and
+    /// the accessors built on it
+    fn max_q_partition(&self) -> PyResult<(Partition, f64)> {
+        let best = max_q_selection(self.fitted_archive()?);
+        Ok((best.partition.clone(), best.fitness))
+    }
+
+    /// Runs the evolutionary phase and caches its front, history and runtime
+    fn refit(&mut self) -> PyResult<()> {
+        let start = Instant::now();
+        let result = self.envolve()?;
+        self.runtime_secs = start.elapsed().as_secs_f64();
+        self.archive = Some(result.front);
+        self.history = result.history;
+        Ok(())
     }
 }
 
@@ -138,8 +177,37 @@ impl HpMocd {
         pop_size = 100,
         num_gens = 500,
         cross_rate = 0.8,
-        mut_rate = 0.2
+        mut_rate = 0.2,
+        seed = None,
+        on_generation = None,
+        patience = Some(100),
+        tol = 1e-6,
+        drop_self_loops = true,
+        num_threads = None,
+        num_communities = None,
+        directed = false,
+        time_limit_secs = None,
+        tournament_size = 2,
+        initial_partition = None,
+        resolution = 1.0,
+        macro_mutation_rate = 0.0,
+        edge_sample_rate = None,
+        modularity_variant = "newman",
+        max_community_size = None,
+        mut_rate_schedule = "constant",
+        mut_rate_min = 0.01,
+        parallel_as_weight = false,
+        crossover_type = None,
+        node_weights = None,
+        elitism = true,
+        init_strategy = "random",
+        bipartite = false,
+        node_types = None,
+        must_link = None,
+        cannot_link = None,
+        round_objectives = None
     ))]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         graph: &Bound<'_, PyAny>,
         debug_level: i8,
@@ -147,35 +215,806 @@ impl HpMocd {
         num_gens: usize,
         cross_rate: f64,
         mut_rate: f64,
+        seed: Option<u64>,
+        on_generation: Option<Py<PyAny>>,
+        patience: Option<usize>,
+        tol: f64,
+        drop_self_loops: bool,
+        num_threads: Option<usize>,
+        num_communities: Option<usize>,
+        directed: bool,
+        time_limit_secs: Option<f64>,
+        tournament_size: usize,
+        initial_partition: Option<&Bound<'_, PyDict>>,
+        resolution: f64,
+        macro_mutation_rate: f64,
+        edge_sample_rate: Option<f64>,
+        modularity_variant: &str,
+        max_community_size: Option<usize>,
+        mut_rate_schedule: &str,
+        mut_rate_min: f64,
+        parallel_as_weight: bool,
+        crossover_type: Option<&str>,
+        node_weights: Option<&Bound<'_, PyDict>>,
+        elitism: bool,
+        init_strategy: &str,
+        bipartite: bool,
+        node_types: Option<&Bound<'_, PyDict>>,
+        must_link: Option<LinkPairs<'_>>,
+        cannot_link: Option<LinkPairs<'_>>,
+        round_objectives: Option<u32>,
     ) -> PyResult<Self> {
-        let edges = get_edges(graph)?;
-        let graph = build_graph(edges);
+        check_resolution(resolution)?;
+        let modularity_variant = parse_modularity_variant(modularity_variant)?;
+        let crossover_type = parse_crossover_type(crossover_type)?;
+        let init_strategy = parse_init_strategy(init_strategy)?;
+        let mut_rate_schedule = parse_mutation_schedule(mut_rate_schedule, mut_rate, mut_rate_min)?;
+        // Oversized communities are split after every mutation; a very small cap leaves
+        // little room to improve Q and can keep the run from converging
+        if max_community_size == Some(0) {
+            return Err(PyValueError::new_err(
+                "max_community_size must be at least 1",
+            ));
+        }
+        check_rate("macro_mutation_rate", macro_mutation_rate)?;
+        // Rounded objectives tie instead of dominating on differences below the precision
+        check_round_objectives(round_objectives)?;
+        if tournament_size == 0 {
+            return Err(PyValueError::new_err("tournament_size must be at least 1"));
+        }
+        let py = graph.py();
+        let (edges, labels) = get_edges(graph)?;
+        let (graph, self_loops) =
+            build_graph(edges, drop_self_loops, false, parallel_as_weight, directed);
+        let mut graph = sample_graph(graph, edge_sample_rate, seed)?;
+        if let Some(node_weights) = node_weights {
+            set_node_weights(&mut graph, node_weights, labels.as_ref())?;
+        }
+        let modularity_variant = set_bipartite(
+            &mut graph,
+            bipartite,
+            node_types,
+            labels.as_ref(),
+            modularity_variant,
+        )?;
+        enable_debug_logging(debug_level);
+        if debug_level >= 1 && self_loops > 0 {
+            log::info!("[HpMocd::new]: Dropped {} self-loops", self_loops);
+        }
+        if debug_level >= 1 {
+            warn_if_disconnected(py, &graph, "HpMocd")?;
+        }
+        let initial_partition = match initial_partition {
+            Some(partition) => {
+                let partition = to_partition(partition, labels.as_ref())?;
+                check_partition_nodes(&graph, &partition)?;
+                Some(partition)
+            }
+            None => None,
+        };
+        // Repaired into every partition after mutation and crossover: must-linked nodes
+        // are merged into one community, cannot-linked ones split apart
+        let (must_link, cannot_link) =
+            link_constraints(py, &graph, labels.as_ref(), must_link, cannot_link)?;
 
         Ok(HpMocd {
             graph,
             debug_level,
-            pop_size,
-            num_gens,
-            cross_rate,
-            mut_rate,
+            config: EvolutionConfig {
+                num_gens,
+                pop_size,
+                cross_rate,
+                mut_rate,
+                seed,
+                patience,
+                tol,
+                num_threads,
+                num_communities,
+                time_limit_secs,
+                tournament_size,
+                resolution,
+                macro_mutation_rate,
+                elitism,
+                max_community_size,
+                modularity_variant,
+                crossover_type,
+                mut_rate_schedule,
+                mut_rate_min,
+                init_strategy,
+                initial_partition,
+                must_link,
+                cannot_link,
+                round_objectives,
+                ..Default::default()
+            },
+            archive: None,
+            history: Vec::new(),
+            runtime_secs: 0.0,
+            labels,
+            edge_rules: EdgeRules {
+                drop_self_loops,
+                parallel_as_weight,
+            },
+            on_generation,
+            objectives: None,
         })
     }
 
+    /// Saves the parsed graph, settings and fitted front (if any) to `path`.
+    /// The `on_generation` callback and objectives from `set_objectives` are not saved.
+    #[pyo3(signature = (path))]
+    pub fn save(&self, py: Python<'_>, path: &str) -> PyResult<()> {
+        let labels = match &self.labels {
+            Some(labels) => Some(labels.to_bytes(py)?),
+            None => None,
+        };
+        let state = (
+            &self.graph,
+            self.debug_level,
+            &self.config,
+            &self.archive,
+            &self.history,
+            labels,
+            self.edge_rules,
+        );
+        Ok(persist::save(path, "HpMocd", &state)?)
+    }
+
+    /// Loads an estimator written by `save`; it comes back fitted if it was saved fitted.
+    #[staticmethod]
+    #[pyo3(signature = (path))]
+    pub fn load(py: Python<'_>, path: &str) -> PyResult<Self> {
+        let (graph, debug_level, config, archive, history, labels, edge_rules): (
+            _,
+            _,
+            _,
+            _,
+            _,
+            Option<Vec<u8>>,
+            _,
+        ) = persist::load(path, "HpMocd")?;
+        let labels = match labels {
+            Some(bytes) => Some(NodeLabels::from_bytes(py, &bytes)?),
+            None => None,
+        };
+        Ok(HpMocd {
+            graph,
+            debug_level,
+            config,
+            archive,
+            history,
+            runtime_secs: 0.0,
+            labels,
+            edge_rules,
+            on_generation: None,
+            objectives: None,
+        })
+    }
+
+    /// Builds the estimator from an edge list file read directly in Rust, one `u v` or
+    /// `u v weight` line of integer node ids per edge. `delimiter` defaults to any
+    /// whitespace; blank and `#` comment lines are skipped. Other keyword arguments go
+    /// to the constructor. Raises ValueError naming the line of a malformed entry.
+    #[staticmethod]
+    #[pyo3(signature = (path, delimiter = None, **kwargs))]
+    pub fn from_file<'py>(
+        py: Python<'py>,
+        path: &str,
+        delimiter: Option<&str>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, Self>> {
+        let edges = Bound::new(py, EdgeFile::read(path, delimiter)?)?;
+        Ok(py
+            .get_type::<Self>()
+            .call((edges,), kwargs)?
+            .downcast_into()?)
+    }
+
+    /// Builds the estimator from a GraphML file read directly in Rust. Node ids become
+    /// string labels; edges read their `source`/`target` attributes and a numeric
+    /// `weight` data key when declared, other attributes are ignored. Other keyword
+    /// arguments go to the constructor. Raises ValueError naming a malformed element.
+    #[staticmethod]
+    #[pyo3(signature = (path, **kwargs))]
+    pub fn from_graphml<'py>(
+        py: Python<'py>,
+        path: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, Self>> {
+        let edges = Bound::new(py, EdgeFile::read_graphml(path)?)?;
+        Ok(py
+            .get_type::<Self>()
+            .call((edges,), kwargs)?
+            .downcast_into()?)
+    }
+
+    /// Number of distinct nodes in the parsed graph.
+    #[getter]
+    pub fn num_nodes(&self) -> usize {
+        self.graph.num_nodes()
+    }
+
+    /// Number of edges in the parsed graph, after dropping parallel edges.
+    #[getter]
+    pub fn num_edges(&self) -> usize {
+        self.graph.num_edges()
+    }
+
+    /// Number of connected components in the parsed graph (weak components when directed).
+    #[getter]
+    pub fn num_components(&self) -> usize {
+        self.graph.connected_components().len()
+    }
+
+    /// Node degrees ordered by node id; a self-loop counts twice.
+    #[pyo3(signature = ())]
+    pub fn degree_sequence(&self) -> Vec<usize> {
+        self.graph.degree_sequence()
+    }
+
+    /// Fraction of the possible node pairs that are linked, 2E / (N(N-1)), or
+    /// E / (N(N-1)) when directed; 0.0 below two nodes.
+    #[pyo3(signature = ())]
+    pub fn density(&self) -> f64 {
+        self.graph.density()
+    }
+
+    /// Pearson correlation of the degrees at either end of each edge: negative when
+    /// hubs link to low-degree nodes, NaN when every edge joins equal degrees (e.g.
+    /// a regular graph) or the graph has no edges.
+    #[pyo3(signature = ())]
+    pub fn degree_assortativity(&self) -> f64 {
+        self.graph.degree_assortativity()
+    }
+
+    /// Number of triangles in the parsed graph, ignoring direction and self-loops.
+    #[pyo3(signature = ())]
+    pub fn triangle_count(&self) -> usize {
+        self.graph.triangle_count()
+    }
+
+    /// Mean local clustering coefficient over all nodes, nodes with fewer than two
+    /// neighbours counting as 0.0 (as in NetworkX); ignores direction and self-loops.
+    #[pyo3(signature = ())]
+    pub fn average_clustering(&self) -> f64 {
+        self.graph.average_clustering()
+    }
+
+    /// Core number of every node, keyed by the original labels: the largest k such
+    /// that the node stays in the k-core (every node of degree >= k). Keep the nodes
+    /// with core number >= k to get the k-core before building another estimator.
+    #[pyo3(signature = ())]
+    pub fn core_numbers<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let cores: BTreeMap<NodeId, usize> = self.graph.core_numbers().into_iter().collect();
+        from_partition(py, &cores, self.labels.as_ref())
+    }
+
+    /// Leading eigenvector of the modularity matrix B = A - k kᵀ / 2m, keyed by the
+    /// original labels, from `iters` rounds of power iteration that never build B. Its
+    /// signs give the spectral two-way split; entries are unit-norm, the largest positive.
+    #[pyo3(signature = (iters = 1000))]
+    pub fn leading_eigenvector<'py>(
+        &self,
+        py: Python<'py>,
+        iters: usize,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let vector: BTreeMap<NodeId, f64> =
+            self.graph.leading_eigenvector(iters).into_iter().collect();
+        from_partition(py, &vector, self.labels.as_ref())
+    }
+
+    /// Node ids in ascending order (the original labels, in order of first appearance,
+    /// when they were remapped); row and column i of `to_adjacency()` is node i here.
+    #[pyo3(signature = ())]
+    pub fn node_order<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        node_labels(py, &self.graph.sorted_nodes(), self.labels.as_ref())
+    }
+
+    /// Returns the parsed graph, after self-loop and parallel-edge handling, as a
+    /// dense (N, N) int32 NumPy adjacency matrix ordered by `node_order()`. Symmetric
+    /// unless the graph is directed; weights are not included. This materializes all
+    /// N * N entries, so it is meant for small graphs. Requires NumPy.
     #[pyo3(signature = ())]
-    pub fn generate_pareto_front(&self) -> PyResult<Vec<(Partition, Vec<f64>)>> {
-        let first_front = self.envolve();
+    pub fn to_adjacency(&self, py: Python<'_>) -> PyResult<PyObject> {
+        adjacency_array(py, &self.graph)
+    }
+
+    /// Returns a copy of the parsed adjacency list as `dict[node, list[neighbor]]`, after
+    /// self-loop and parallel-edge handling: a kept self-loop lists its node twice, and
+    /// directed graphs list neighbours in either direction. Changing it does not touch
+    /// the estimator.
+    #[pyo3(signature = ())]
+    pub fn adjacency<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        adjacency_dict(py, &self.graph, self.labels.as_ref())
+    }
+
+    /// Runs the evolutionary phase once and caches the resulting Pareto front.
+    /// Calling it again replaces the cached front.
+    #[pyo3(signature = ())]
+    pub fn fit(mut slf: PyRefMut<'_, Self>) -> PyResult<PyRefMut<'_, Self>> {
+        slf.refit()?;
+        Ok(slf)
+    }
+
+    /// Replaces the built-in [intra, inter] objectives with two Python callables, each
+    /// taking a partition dict and returning a float to minimize. Front objectives,
+    /// `history` and max-Q selection then use these values, with Q read as
+    /// 1 - f1 - f2; mutation still follows modularity gains. Each callable runs once
+    /// per individual and generation while holding the GIL, so a UserWarning is
+    /// emitted above 200 individuals. An empty list restores the built-in
+    /// objectives. Drops the cached front, like `reset`.
+    #[pyo3(signature = (callables))]
+    pub fn set_objectives(&mut self, py: Python<'_>, callables: Vec<Py<PyAny>>) -> PyResult<()> {
+        if callables.is_empty() {
+            self.objectives = None;
+            self.reset();
+            return Ok(());
+        }
+        if callables.len() != 2 {
+            return Err(PyValueError::new_err(format!(
+                "expected 2 objective callables, got {}",
+                callables.len()
+            )));
+        }
+        if let Some(callable) = callables
+            .iter()
+            .find(|callable| !callable.bind(py).is_callable())
+        {
+            return Err(PyValueError::new_err(format!(
+                "objectives must be callable, got {}",
+                callable.bind(py).get_type().name()?
+            )));
+        }
+        if self.config.pop_size > PYTHON_OBJECTIVES_WARN_POP_SIZE {
+            let message = CString::new(format!(
+                "[HpMocd::set_objectives]: Python objectives run for each of the {} \
+                 individuals every generation; expect a slow run",
+                self.config.pop_size
+            ))?;
+            PyErr::warn(py, &py.get_type::<PyUserWarning>(), &message, 1)?;
+        }
 
-        Ok(first_front
+        self.objectives = Some(callables);
+        self.reset();
+        Ok(())
+    }
+
+    /// Drops the cached front, history and runtime, so the next call runs the evolutionary
+    /// phase again.
+    #[pyo3(signature = ())]
+    pub fn reset(&mut self) {
+        self.archive = None;
+        self.history.clear();
+        self.runtime_secs = 0.0;
+    }
+
+    /// Wall-clock seconds the cached run took, callbacks included. 0.0 before the
+    /// first run and after `load`, which restores the front but not its timing.
+    #[pyo3(signature = ())]
+    pub fn last_runtime_secs(&self) -> f64 {
+        self.runtime_secs
+    }
+
+    /// Generations the cached run completed, at most `num_gens` and fewer when
+    /// `patience`, `time_limit_secs` or `on_generation` stopped it early. 0 before the
+    /// first run.
+    #[pyo3(signature = ())]
+    pub fn last_generations(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Best Q recorded at each generation of the cached run, one entry per generation
+    /// that ran. Empty until the first run.
+    #[pyo3(signature = ())]
+    pub fn history(&self) -> Vec<f64> {
+        self.history.clone()
+    }
+
+    /// Returns the max-Q partition of the fitted front.
+    #[pyo3(signature = ())]
+    pub fn predict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let (partition, _) = self.max_q_partition()?;
+
+        self.to_dict(py, partition)
+    }
+
+    /// Returns every solution of the fitted front with its objectives.
+    #[pyo3(signature = ())]
+    pub fn front<'py>(&self, py: Python<'py>) -> PyResult<Vec<(Bound<'py, PyDict>, Vec<f64>)>> {
+        self.fitted_archive()?
+            .iter()
+            .map(|ind| {
+                Ok((
+                    self.to_dict(py, ind.partition.clone())?,
+                    ind.objectives.clone(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Returns the fitted front objectives as an (N, 2) NumPy array of [intra, inter],
+    /// row i matching the i-th solution of `front()`. Requires NumPy.
+    #[pyo3(signature = ())]
+    pub fn pareto_objectives(&self, py: Python<'_>) -> PyResult<PyObject> {
+        objectives_array(py, self.fitted_archive()?.iter().map(|ind| &ind.objectives))
+    }
+
+    /// Edges of the subgraph induced by community `community_id` of `partition`, as
+    /// `(u, v, weight)` tuples that any estimator accepts, for re-running detection
+    /// inside one community. Nodes without an edge in the community are left out.
+    /// Raises ValueError unless the partition assigns exactly the graph's nodes and
+    /// holds `community_id`.
+    #[pyo3(signature = (partition, community_id))]
+    pub fn community_subgraph<'py>(
+        &self,
+        py: Python<'py>,
+        partition: &Bound<'py, PyDict>,
+        community_id: CommunityId,
+    ) -> PyResult<LabeledEdges<'py>> {
+        let partition = to_partition(partition, self.labels.as_ref())?;
+        check_partition_nodes(&self.graph, &partition)?;
+
+        let nodes: HashSet<NodeId> = partition
+            .iter()
+            .filter(|&(_, &community)| community == community_id)
+            .map(|(&node, _)| node)
+            .collect();
+        if nodes.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "community {} is not in the partition",
+                community_id
+            )));
+        }
+        edge_list(py, &self.graph.subgraph(&nodes), self.labels.as_ref())
+    }
+
+    /// Community of the max-Q partition (ids as in `run`) that a new `node` with
+    /// edges to `neighbors` would join: the one whose modularity gain is largest, or a
+    /// fresh id one past the last when joining no neighbor's community gains. A cheap
+    /// one-node prediction, not a re-run; the cached front and the graph stay as they
+    /// are. Directed graphs read the edges as pointing from `node`. Raises ValueError
+    /// if `node` is already in the graph or a neighbor is not.
+    #[pyo3(signature = (node, neighbors))]
+    pub fn assign_node(
+        &mut self,
+        node: &Bound<'_, PyAny>,
+        neighbors: Vec<Bound<'_, PyAny>>,
+    ) -> PyResult<CommunityId> {
+        let (node, neighbors) = new_node_ids(&self.graph, self.labels.as_ref(), node, &neighbors)?;
+        self.ensure_fitted()?;
+        let (partition, _) = self.max_q_partition()?;
+
+        Ok(assign_node(
+            &self.graph,
+            &normalize_community_ids(partition),
+            node,
+            &neighbors,
+            self.config.resolution,
+        ))
+    }
+
+    /// Adds the edge `u`-`v` to the graph, nodes included, and drops the cached front
+    /// so the next `run` re-optimizes the updated graph. A new node starts in a
+    /// community of its own in `initial_partition`, if one was given. The edge follows
+    /// the constructor's rules: a self-loop only adds its node with `drop_self_loops`,
+    /// and an edge already in the graph gains a unit of weight with
+    /// `parallel_as_weight` and is left as is otherwise. With `bipartite=True` both
+    /// nodes must be in `node_types`, else ValueError is raised.
+    #[pyo3(signature = (u, v))]
+    pub fn add_edge(&mut self, u: &Bound<'_, PyAny>, v: &Bound<'_, PyAny>) -> PyResult<()> {
+        check_typed_ends(&self.graph, self.labels.as_ref(), u, v)?;
+        let (u, v) = edge_node_ids(self.labels.as_mut(), u, v, true)?;
+        let new_nodes: Vec<NodeId> = [u, v]
             .into_iter()
-            .map(|ind| (normalize_community_ids(ind.partition), ind.objectives))
+            .filter(|node| !self.graph.nodes.contains(node))
+            .collect();
+        if !add_edge_with_rules(&mut self.graph, u, v, 1.0, self.edge_rules) {
+            return Ok(());
+        }
+        for node in new_nodes {
+            self.config.extend_initial_partition(node);
+        }
+        self.reset();
+        Ok(())
+    }
+
+    /// Removes the edge `u`-`v` and drops the cached front, like `add_edge`. Nodes
+    /// left without edges stay in the graph as isolated nodes. Raises ValueError if
+    /// there is no such edge.
+    #[pyo3(signature = (u, v))]
+    pub fn remove_edge(&mut self, u: &Bound<'_, PyAny>, v: &Bound<'_, PyAny>) -> PyResult<()> {
+        let (from, to) = edge_node_ids(self.labels.as_mut(), u, v, false)?;
+        if !self.graph.remove_edge(from, to) {
+            return Err(PyValueError::new_err(format!(
+                "edge ({}, {}) is not in the graph",
+                u.repr()?,
+                v.repr()?
+            )));
+        }
+        self.reset();
+        Ok(())
+    }
+
+    /// Community grown around `seed` alone, without partitioning the graph or running
+    /// the evolutionary phase: neighbouring nodes join one at a time while they raise
+    /// the local modularity R (the share of the boundary's edge weight that stays
+    /// inside), up to `max_size` nodes. Nodes come in the order they joined, `seed`
+    /// first. Raises ValueError if `seed` is not in the graph or `max_size` is 0.
+    #[pyo3(signature = (seed, max_size))]
+    pub fn local_community<'py>(
+        &self,
+        py: Python<'py>,
+        seed: &Bound<'py, PyAny>,
+        max_size: usize,
+    ) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        if max_size == 0 {
+            return Err(PyValueError::new_err("max_size must be at least 1"));
+        }
+        let seed = node_id(&self.graph, self.labels.as_ref(), seed)?;
+        let community = local::local_community(&self.graph, seed, max_size);
+        node_labels(py, &community, self.labels.as_ref())
+    }
+
+    /// Sets a `community` node attribute on the NetworkX `graph` from the max-Q
+    /// partition (ids as in `run`), through `networkx.set_node_attributes`. Nodes the
+    /// estimator was not built with are left without the attribute. Uses the cached
+    /// front, running the evolutionary phase if needed. Requires NetworkX.
+    #[pyo3(signature = (graph))]
+    pub fn annotate(&mut self, graph: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.ensure_fitted()?;
+        let (partition, _) = self.max_q_partition()?;
+        let partition = self.to_dict(graph.py(), partition)?;
+
+        graph
+            .py()
+            .import("networkx")?
+            .call_method1("set_node_attributes", (graph, partition, "community"))?;
+        Ok(())
+    }
+
+    /// The max-Q partition as one list of nodes per community, ordered by community id
+    /// as in `max_q`. Uses the cached front, running the evolutionary phase if needed.
+    #[pyo3(signature = ())]
+    pub fn community_sets<'py>(
+        &mut self,
+        py: Python<'py>,
+    ) -> PyResult<Vec<Vec<Bound<'py, PyAny>>>> {
+        self.ensure_fitted()?;
+        let (partition, _) = self.max_q_partition()?;
+
+        community_sets(partition)
+            .iter()
+            .map(|nodes| node_labels(py, nodes, self.labels.as_ref()))
+            .collect()
+    }
+
+    /// The max-Q partition as `(node, community)` pairs ordered by node id (ids as in
+    /// `run`), ready for `pandas.DataFrame(records, columns=["node", "community"])`.
+    /// Uses the cached front, running the evolutionary phase if needed.
+    #[pyo3(signature = ())]
+    pub fn partition_records<'py>(
+        &mut self,
+        py: Python<'py>,
+    ) -> PyResult<Vec<(Bound<'py, PyAny>, CommunityId)>> {
+        self.ensure_fitted()?;
+        let (partition, _) = self.max_q_partition()?;
+
+        records_with_labels(py, partition_records(partition), self.labels.as_ref())
+    }
+
+    /// Area of objective space dominated by the front's [intra, inter] vectors and
+    /// bounded by `reference`, for comparing runs; points outside the reference box
+    /// add nothing. Uses the cached front, running the evolutionary phase if needed.
+    #[pyo3(signature = (reference))]
+    pub fn hypervolume(&mut self, reference: (f64, f64)) -> PyResult<f64> {
+        self.ensure_fitted()?;
+        let archive = self.fitted_archive()?;
+        Ok(hypervolume(
+            archive.iter().map(|ind| &ind.objectives),
+            reference,
+        ))
+    }
+
+    /// Returns the objective vector [intra, inter] of a user-supplied partition on
+    /// the stored graph, comparable with the objectives of `front()`.
+    /// Raises ValueError unless the partition assigns exactly the graph's nodes.
+    #[pyo3(signature = (partition))]
+    pub fn score_partition(&self, partition: &Bound<'_, PyDict>) -> PyResult<Vec<f64>> {
+        let partition = to_partition(partition, self.labels.as_ref())?;
+        check_partition_nodes(&self.graph, &partition)?;
+
+        let metrics = get_fitness(
+            &self.graph,
+            &partition,
+            &self.graph.precompute_degrees(),
+            true,
+            self.config.resolution,
+            self.config.modularity_variant,
+        );
+        Ok(vec![metrics.intra, metrics.inter])
+    }
+
+    /// Modularity change from merging each pair of communities of `partition` joined by
+    /// an edge, as `((a, b), gain)` with `a < b` in ascending order: the candidate moves
+    /// of greedy agglomeration (CNM). Uses the configured resolution and the Newman null
+    /// model, ignoring node weights. Raises ValueError unless the partition assigns
+    /// exactly the graph's nodes.
+    #[pyo3(signature = (partition))]
+    pub fn merge_gains(
+        &self,
+        partition: &Bound<'_, PyDict>,
+    ) -> PyResult<Vec<((CommunityId, CommunityId), f64)>> {
+        let partition = to_partition(partition, self.labels.as_ref())?;
+        check_partition_nodes(&self.graph, &partition)?;
+        Ok(merge_gains(&self.graph, &partition, self.config.resolution))
+    }
+
+    /// Runs the evolutionary phase and returns every front solution with its objectives.
+    /// With `overlap_threshold`, each node maps to a list of communities instead: its own
+    /// first, then, in ascending order, every other community holding strictly more than
+    /// that fraction of its incident edges.
+    #[pyo3(signature = (overlap_threshold = None))]
+    pub fn generate_pareto_front<'py>(
+        &mut self,
+        py: Python<'py>,
+        overlap_threshold: Option<f64>,
+    ) -> PyResult<Vec<(Bound<'py, PyDict>, Vec<f64>)>> {
+        check_overlap_threshold(overlap_threshold)?;
+        self.ensure_fitted()?;
+
+        self.fitted_archive()?
+            .iter()
+            .map(|ind| {
+                let partition = self.to_soft_dict(py, ind.partition.clone(), overlap_threshold)?;
+                Ok((partition, ind.objectives.clone()))
+            })
+            .collect()
+    }
+
+    /// Like `generate_pareto_front`, but returns a `ParetoIterator` that builds one
+    /// `(partition, objectives)` pair at a time, for fronts too large to hold as dicts.
+    #[pyo3(signature = ())]
+    pub fn iter_front(&mut self, py: Python<'_>) -> PyResult<ParetoIterator> {
+        self.ensure_fitted()?;
+        let front = self
+            .fitted_archive()?
+            .iter()
+            .map(|ind| (ind.partition.clone(), ind.objectives.clone()))
+            .collect();
+        let labels = match &self.labels {
+            Some(labels) => Some(labels.copy(py)?),
+            None => None,
+        };
+
+        Ok(ParetoIterator::new(front, labels))
+    }
+
+    /// Like `generate_pareto_front`, but with each objective vector as a dict
+    /// `{"intra": x, "inter": y, "modularity": 1 - x - y}`. After
+    /// `set_objectives`, `intra` and `inter` hold the two custom values.
+    #[pyo3(signature = (overlap_threshold = None))]
+    pub fn labeled_pareto_front<'py>(
+        &mut self,
+        py: Python<'py>,
+        overlap_threshold: Option<f64>,
+    ) -> PyResult<Vec<(Bound<'py, PyDict>, Bound<'py, PyDict>)>> {
+        check_overlap_threshold(overlap_threshold)?;
+        self.ensure_fitted()?;
+
+        self.fitted_archive()?
+            .iter()
+            .map(|ind| {
+                let partition = self.to_soft_dict(py, ind.partition.clone(), overlap_threshold)?;
+                Ok((partition, objective_dict(py, &ind.objectives)?))
+            })
+            .collect()
+    }
+
+    /// Modularity Q of every front solution at the configured resolution, in the order
+    /// of `generate_pareto_front`, scored on the stored graph instead of rebuilding it
+    /// per `fitness` call. Always Newman's Q, whatever `modularity_variant`. It stays
+    /// Q after `set_objectives`, so it need not match the custom objectives.
+    #[pyo3(signature = ())]
+    pub fn front_modularities(&mut self) -> PyResult<Vec<f64>> {
+        self.ensure_fitted()?;
+        Ok(self
+            .fitted_archive()?
+            .iter()
+            .map(|ind| {
+                get_modularity_from_partition(&ind.partition, &self.graph, self.config.resolution)
+            })
             .collect())
     }
 
+    /// The front as CSV text, an `intra,inter,modularity,num_communities` header and
+    /// one row per solution in the order of `front()`, ready to write to a file. After
+    /// `set_objectives`, `intra` and `inter` hold the two custom values.
+    /// Uses the cached front, running the evolutionary phase if needed.
+    #[pyo3(signature = ())]
+    pub fn front_to_csv(&mut self) -> PyResult<String> {
+        self.ensure_fitted()?;
+        Ok(front_csv(
+            self.fitted_archive()?
+                .iter()
+                .map(|ind| (&ind.partition, ind.objectives.as_slice())),
+        ))
+    }
+
+    /// Number of distinct communities in every front solution, in the order of
+    /// `generate_pareto_front`, showing the range of resolutions the front spans.
+    #[pyo3(signature = ())]
+    pub fn num_communities_distribution(&mut self) -> PyResult<Vec<usize>> {
+        self.ensure_fitted()?;
+        Ok(self
+            .fitted_archive()?
+            .iter()
+            .map(|ind| community_count(&ind.partition))
+            .collect())
+    }
+
+    /// Runs the evolutionary phase and returns the whole final population as
+    /// `(partition, [intra, inter], rank, crowding_distance)`, where rank 0 is the
+    /// non-dominated front. Sorted by rank, then by decreasing crowding distance;
+    /// the extremes of each front have an infinite distance.
+    #[pyo3(signature = ())]
+    pub fn generate_fronts<'py>(&self, py: Python<'py>) -> PyResult<Vec<RankedSolution<'py>>> {
+        let population = self.run_phase(|on_generation, objectives| {
+            evolutionary::ranked_population(
+                &self.graph,
+                self.debug_level,
+                &self.config,
+                on_generation,
+                objectives,
+            )
+        })?;
+
+        population
+            .into_iter()
+            .map(|ind| {
+                let partition = self.to_dict(py, ind.partition)?;
+                Ok((
+                    partition,
+                    ind.objectives,
+                    ind.rank - 1,
+                    ind.crowding_distance,
+                ))
+            })
+            .collect()
+    }
+
+    #[pyo3(signature = ())]
+    pub fn run<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.ensure_fitted()?;
+        let (partition, _) = self.max_q_partition()?;
+
+        self.to_dict(py, partition)
+    }
+
+    /// Like `run`, but also returns the chosen partition's Q = 1 - intra - inter,
+    /// read from the same front.
+    #[pyo3(signature = ())]
+    pub fn best<'py>(&mut self, py: Python<'py>) -> PyResult<(Bound<'py, PyDict>, f64)> {
+        self.ensure_fitted()?;
+        let (partition, q) = self.max_q_partition()?;
+
+        Ok((self.to_dict(py, partition)?, q))
+    }
+
+    /// Picks the knee of the front, the solution farthest from the chord joining its
+    /// two extremes, with its objectives. Fronts of fewer than three solutions fall back
+    /// to the `run` solution.
     #[pyo3(signature = ())]
-    pub fn run(&self) -> PyResult<Partition> {
-        let first_front = self.envolve();
-        let best_solution = max_q_selection(&first_front);
+    pub fn knee_point<'py>(&mut self, py: Python<'py>) -> PyResult<(Bound<'py, PyDict>, Vec<f64>)> {
+        self.ensure_fitted()?;
+        let archive = self.fitted_archive()?;
+        let knee = match knee_point(archive.iter().map(|ind| &ind.objectives)) {
+            Some(index) => &archive[index],
+            None => max_q_selection(archive),
+        };
 
-        Ok(normalize_community_ids(best_solution.partition.clone()))
+        Ok((
+            self.to_dict(py, knee.partition.clone())?,
+            knee.objectives.clone(),
+        ))
     }
 }
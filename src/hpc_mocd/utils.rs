@@ -1,3 +1,4 @@
+use crate::graph::Partition;
 use crate::hpc_mocd::individual::Individual;
 
 use rustc_hash::FxHashMap as HashMap;
@@ -162,3 +163,46 @@ pub fn max_q_selection(population: &[Individual]) -> &Individual {
         .max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap_or(Ordering::Equal))
         .expect("Empty population in max_q_selection")
 }
+
+/// Indices of the points no other point dominates (minimization), in input order.
+/// Duplicates do not dominate each other, so all copies of a front point are kept.
+pub fn pareto_filter(objectives: Vec<Vec<f64>>) -> Vec<usize> {
+    let mut points: Vec<Individual> = objectives
+        .into_iter()
+        .map(|objectives| Individual {
+            objectives,
+            ..Individual::new(Partition::new())
+        })
+        .collect();
+    fast_non_dominated_sort(&mut points);
+
+    points
+        .iter()
+        .enumerate()
+        .filter(|(_, point)| point.rank == 1)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pareto_filter_drops_dominated_interior_point() {
+        let objectives = vec![
+            vec![0.1, 0.9],
+            vec![0.5, 0.5],
+            vec![0.6, 0.6], // dominated by [0.5, 0.5]
+            vec![0.9, 0.1],
+            vec![0.5, 0.5],
+        ];
+        assert_eq!(pareto_filter(objectives), vec![0, 1, 3, 4]);
+    }
+
+    #[test]
+    fn test_pareto_filter_edge_cases() {
+        assert_eq!(pareto_filter(vec![vec![0.3, 0.7]]), vec![0]);
+        assert!(pareto_filter(Vec::new()).is_empty());
+    }
+}
@@ -5,19 +5,26 @@
 //! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
 
 mod cocomi;
+mod comparison;
 mod hpc_mocd;
+mod local;
 mod mocd;
+mod quality;
 
 mod graph;
 mod operators;
+mod persist;
 mod utils;
 
 pub use cocomi::CoCoMi;
 pub use hpc_mocd::HpMocd;
 pub use mocd::MOCD;
 
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::types::{PyDict, PySet};
+use rand::Rng;
+use rustc_hash::FxHashSet;
 
 // ================================================================================================
 // Functions
@@ -25,24 +32,745 @@ use pyo3::types::PyDict;
 
 /// Calculates the Q score for a given graph and community partition
 /// based on (Shi, 2012) multi-objective modularity equation. Q = 1 - intra - inter
+/// Edge `weight` attributes are honoured when present, otherwise every edge weighs 1.0.
+/// A plain edge list scores the same as a NetworkX graph with those edges, without
+/// needing NetworkX; `(u, v, weight)` items carry weights.
+/// Self-loops are kept: each counts twice towards its node's degree (strength when
+/// weighted) and once, at its full weight, as an intra-community edge, as in NetworkX.
+/// With `directed=True` edges are read as `u -> v` and the Leicht-Newman directed
+/// modularity is used, with out-degree times in-degree as the null model.
+/// `alpha` and `beta` weight the objectives, Q = 1 - alpha * intra - beta * inter;
+/// they only affect this score, the optimizers always use unit weights.
+/// `resolution` multiplies the expected-edges term inside inter, as the optimizers'
+/// `resolution` does: above 1.0 favours more, smaller communities.
+/// `modularity_variant="dcsbm"` swaps the configuration null for a planted-partition
+/// degree-corrected block model, which rescales inter by the resolution the partition's
+/// fitted block rates imply (Newman, 2016).
+/// `node_weights` scales each node's degree in the null model (population, say), with
+/// 2m replaced by the total weighted degree; nodes left out weigh 1.0.
+/// `bipartite=True` scores Barber's bipartite modularity instead: only edges between
+/// the two sides given by `node_types` count, against the product of the sides' degrees.
 ///
 /// # Parameters
-/// - `graph` (networkx.Graph): The graph to analyze
+/// - `graph` (networkx.Graph | list[tuple[int, int]]): The graph to analyze
 /// - `partition` (dict[int, int]): Dictionary mapping nodes to community IDs
+/// - `directed` (bool): Treat the edges as directed, defaults to False
+/// - `alpha` (float): Weight of the intra objective, defaults to 1.0
+/// - `beta` (float): Weight of the inter objective, defaults to 1.0
+/// - `resolution` (float): Modularity resolution, defaults to 1.0
+/// - `modularity_variant` (str): "newman" (the default) or "dcsbm"
+/// - `node_weights` (dict[int, float] | None): Positive weight per node, defaults to None
+/// - `bipartite` (bool): Use Barber's bipartite modularity, defaults to False
+/// - `node_types` (dict[int, int] | None): Side (0 or 1) of every node when bipartite
 ///
 /// # Returns
-/// - float
-#[pyfunction(name = "fitness")]
-fn fitness(graph: &Bound<'_, PyAny>, partition: &Bound<'_, PyDict>) -> PyResult<f64> {
-    let edges = utils::get_edges(graph)?;
-    let graph = utils::build_graph(edges);
+/// - float, 0.0 for a graph without edges
+#[pyfunction(name = "fitness", signature = (graph,
+    partition,
+    directed = false,
+    alpha = 1.0,
+    beta = 1.0,
+    resolution = 1.0,
+    modularity_variant = "newman",
+    node_weights = None,
+    bipartite = false,
+    node_types = None
+))]
+#[allow(clippy::too_many_arguments)]
+fn fitness(
+    graph: &Bound<'_, PyAny>,
+    partition: &Bound<'_, PyDict>,
+    directed: bool,
+    alpha: f64,
+    beta: f64,
+    resolution: f64,
+    modularity_variant: &str,
+    node_weights: Option<&Bound<'_, PyDict>>,
+    bipartite: bool,
+    node_types: Option<&Bound<'_, PyDict>>,
+) -> PyResult<f64> {
+    utils::check_resolution(resolution)?;
+    let variant = utils::parse_modularity_variant(modularity_variant)?;
+    let (edges, labels) = utils::get_edges(graph)?;
+    let (mut graph, _) = utils::build_graph(edges, false, false, false, directed);
+    if let Some(node_weights) = node_weights {
+        utils::set_node_weights(&mut graph, node_weights, labels.as_ref())?;
+    }
+    let variant =
+        utils::set_bipartite(&mut graph, bipartite, node_types, labels.as_ref(), variant)?;
+    // Q is 0 for every partition of a graph without edges, whatever alpha and beta are
+    if graph.total_weight() == 0.0 {
+        return Ok(0.0);
+    }
 
-    Ok(operators::get_modularity_from_partition(
-        &utils::to_partition(partition)?,
+    Ok(operators::get_metrics_from_partition(
+        &utils::to_partition(partition, labels.as_ref())?,
         &graph,
+        resolution,
+        variant,
+    )
+    .weighted_modularity(alpha, beta))
+}
+
+/// Calculates the two objectives (intra, inter) that `fitness` combines into
+/// Q = 1 - intra - inter. These are the values the optimizers minimize, so they
+/// place a partition in the same objective space as `generate_pareto_front`.
+///
+/// # Parameters
+/// - `graph` (networkx.Graph | list[tuple[int, int]]): The graph to analyze
+/// - `partition` (dict[int, int]): Dictionary mapping nodes to community IDs
+/// - `directed` (bool): Treat the edges as directed, defaults to False
+/// - `resolution` (float): Modularity resolution scaling inter, defaults to 1.0
+/// - `modularity_variant` (str): Null model of inter, "newman" (the default) or "dcsbm"
+///
+/// # Returns
+/// - tuple[float, float]
+#[pyfunction(name = "fitness_components", signature = (graph,
+    partition,
+    directed = false,
+    resolution = 1.0,
+    modularity_variant = "newman"
+))]
+fn fitness_components(
+    graph: &Bound<'_, PyAny>,
+    partition: &Bound<'_, PyDict>,
+    directed: bool,
+    resolution: f64,
+    modularity_variant: &str,
+) -> PyResult<(f64, f64)> {
+    utils::check_resolution(resolution)?;
+    let variant = utils::parse_modularity_variant(modularity_variant)?;
+    let (edges, labels) = utils::get_edges(graph)?;
+    let (graph, _) = utils::build_graph(edges, false, false, false, directed);
+
+    let metrics = operators::get_metrics_from_partition(
+        &utils::to_partition(partition, labels.as_ref())?,
+        &graph,
+        resolution,
+        variant,
+    );
+    Ok((metrics.intra, metrics.inter))
+}
+
+/// Calculates how much Q a fixed partition gains from `graph_b` to `graph_a`,
+/// fitness(graph_a, partition) - fitness(graph_b, partition), e.g. to measure how a
+/// rewiring affects a community structure. Each graph is built and scored once, with
+/// the same settings as `fitness` (unit objective weights).
+///
+/// # Parameters
+/// - `graph_a` (networkx.Graph | list[tuple[int, int]]): First graph
+/// - `graph_b` (networkx.Graph | list[tuple[int, int]]): Second graph
+/// - `partition` (dict[int, int]): Dictionary mapping nodes to community IDs
+/// - `directed` (bool): Treat the edges as directed, defaults to False
+/// - `resolution` (float): Modularity resolution, defaults to 1.0
+/// - `modularity_variant` (str): "newman" (the default) or "dcsbm"
+///
+/// # Returns
+/// - float, positive when the partition fits `graph_a` better; raises ValueError if
+///   the partition does not assign exactly the nodes of each graph
+#[pyfunction(name = "fitness_delta", signature = (graph_a,
+    graph_b,
+    partition,
+    directed = false,
+    resolution = 1.0,
+    modularity_variant = "newman"
+))]
+fn fitness_delta(
+    graph_a: &Bound<'_, PyAny>,
+    graph_b: &Bound<'_, PyAny>,
+    partition: &Bound<'_, PyDict>,
+    directed: bool,
+    resolution: f64,
+    modularity_variant: &str,
+) -> PyResult<f64> {
+    utils::check_resolution(resolution)?;
+    let variant = utils::parse_modularity_variant(modularity_variant)?;
+    // Labels are interned per graph, so the partition is read against each one
+    let score = |graph: &Bound<'_, PyAny>| -> PyResult<f64> {
+        let (edges, labels) = utils::get_edges(graph)?;
+        let (graph, _) = utils::build_graph(edges, false, false, false, directed);
+        let partition = utils::to_partition(partition, labels.as_ref())?;
+        utils::check_partition_nodes(&graph, &partition)?;
+        Ok(
+            operators::get_metrics_from_partition(&partition, &graph, resolution, variant)
+                .modularity,
+        )
+    };
+
+    Ok(score(graph_a)? - score(graph_b)?)
+}
+
+/// Calculates the Normalized Mutual Information (natural log) between a predicted
+/// partition and a ground-truth labeling, 2 * I(pred; truth) / (H(pred) + H(truth)).
+///
+/// # Parameters
+/// - `pred` (dict[int, int]): Predicted node to community mapping
+/// - `truth` (dict[int, int]): Ground-truth node to community mapping
+///
+/// # Returns
+/// - float in [0, 1]; raises ValueError if the node sets differ
+#[pyfunction(name = "nmi")]
+fn nmi(pred: &Bound<'_, PyDict>, truth: &Bound<'_, PyDict>) -> PyResult<f64> {
+    // Only node identity matters here, so any hashable labels are interned
+    let mut labels = utils::NodeLabels::new(pred.py());
+    let pred = labels.intern_partition(pred)?;
+    let truth = labels.intern_partition(truth)?;
+    utils::check_same_nodes(&pred, &truth)?;
+
+    Ok(comparison::nmi(&pred, &truth))
+}
+
+/// Calculates the Adjusted Mutual Information (natural log) between a predicted
+/// partition and a ground-truth labeling, correcting NMI's bias towards many small
+/// communities: (I - E[I]) / (mean(H(pred), H(truth)) - E[I]), where E[I] is the
+/// expected mutual information of random partitions with the same community sizes.
+///
+/// # Parameters
+/// - `pred` (dict[int, int]): Predicted node to community mapping
+/// - `truth` (dict[int, int]): Ground-truth node to community mapping
+///
+/// # Returns
+/// - float, at most 1 and around 0 for chance agreement; raises ValueError if the
+///   node sets differ
+#[pyfunction(name = "ami")]
+fn ami(pred: &Bound<'_, PyDict>, truth: &Bound<'_, PyDict>) -> PyResult<f64> {
+    let mut labels = utils::NodeLabels::new(pred.py());
+    let pred = labels.intern_partition(pred)?;
+    let truth = labels.intern_partition(truth)?;
+    utils::check_same_nodes(&pred, &truth)?;
+
+    Ok(comparison::ami(&pred, &truth))
+}
+
+/// Calculates the Variation of Information (natural log) between two partitions,
+/// H(a | b) + H(b | a). Unlike NMI it is a true distance: 0 for identical partitions,
+/// symmetric, and it satisfies the triangle inequality.
+///
+/// # Parameters
+/// - `a` (dict[int, int]): First node to community mapping
+/// - `b` (dict[int, int]): Second node to community mapping
+///
+/// # Returns
+/// - float in [0, ln(n)]; raises ValueError if the node sets differ
+#[pyfunction(name = "variation_of_information")]
+fn variation_of_information(a: &Bound<'_, PyDict>, b: &Bound<'_, PyDict>) -> PyResult<f64> {
+    let mut labels = utils::NodeLabels::new(a.py());
+    let a = labels.intern_partition(a)?;
+    let b = labels.intern_partition(b)?;
+    utils::check_same_nodes(&a, &b)?;
+
+    Ok(comparison::variation_of_information(&a, &b))
+}
+
+/// Calculates the Rand index between two partitions: the fraction of node pairs
+/// that both put in the same community or both put in different ones.
+///
+/// # Parameters
+/// - `a` (dict[int, int]): First node to community mapping
+/// - `b` (dict[int, int]): Second node to community mapping
+///
+/// # Returns
+/// - float in [0, 1]; raises ValueError if the node sets differ
+#[pyfunction(name = "rand_index")]
+fn rand_index(a: &Bound<'_, PyDict>, b: &Bound<'_, PyDict>) -> PyResult<f64> {
+    let mut labels = utils::NodeLabels::new(a.py());
+    let a = labels.intern_partition(a)?;
+    let b = labels.intern_partition(b)?;
+    utils::check_same_nodes(&a, &b)?;
+
+    Ok(comparison::rand_index(&a, &b))
+}
+
+/// Calculates the Adjusted Rand Index between two partitions, the Rand index
+/// corrected for chance from the contingency table: (S - E[S]) / (mean(S_a, S_b) -
+/// E[S]), where S counts node pairs grouped together in both partitions and E[S] is
+/// its expectation for random partitions with the same community sizes.
+///
+/// # Parameters
+/// - `a` (dict[int, int]): First node to community mapping
+/// - `b` (dict[int, int]): Second node to community mapping
+///
+/// # Returns
+/// - float, at most 1 and around 0 for chance agreement; raises ValueError if the
+///   node sets differ
+#[pyfunction(name = "adjusted_rand_index")]
+fn adjusted_rand_index(a: &Bound<'_, PyDict>, b: &Bound<'_, PyDict>) -> PyResult<f64> {
+    let mut labels = utils::NodeLabels::new(a.py());
+    let a = labels.intern_partition(a)?;
+    let b = labels.intern_partition(b)?;
+    utils::check_same_nodes(&a, &b)?;
+
+    Ok(comparison::adjusted_rand_index(&a, &b))
+}
+
+/// Calculates the Jaccard index over node pairs: pairs grouped together in both
+/// partitions divided by pairs grouped together in either.
+///
+/// # Parameters
+/// - `a` (dict[int, int]): First node to community mapping
+/// - `b` (dict[int, int]): Second node to community mapping
+///
+/// # Returns
+/// - float in [0, 1], 1.0 for identical partitions; raises ValueError if the node
+///   sets differ
+#[pyfunction(name = "partition_jaccard")]
+fn partition_jaccard(a: &Bound<'_, PyDict>, b: &Bound<'_, PyDict>) -> PyResult<f64> {
+    let mut labels = utils::NodeLabels::new(a.py());
+    let a = labels.intern_partition(a)?;
+    let b = labels.intern_partition(b)?;
+    utils::check_same_nodes(&a, &b)?;
+
+    Ok(comparison::jaccard(&a, &b))
+}
+
+/// Compares two partitions with several scores from one shared contingency table,
+/// cheaper than calling each metric in turn. Every value equals what the standalone
+/// function returns; `jaccard` is the share of node pairs grouped together in both
+/// partitions among those grouped together in either.
+///
+/// # Parameters
+/// - `a` (dict[int, int]): First node to community mapping
+/// - `b` (dict[int, int]): Second node to community mapping
+///
+/// # Returns
+/// - dict[str, float] with keys "nmi", "ari", "vi" and "jaccard"; raises ValueError
+///   if the node sets differ
+#[pyfunction(name = "compare")]
+fn compare<'py>(a: &Bound<'py, PyDict>, b: &Bound<'py, PyDict>) -> PyResult<Bound<'py, PyDict>> {
+    let py = a.py();
+    let mut labels = utils::NodeLabels::new(py);
+    let a = labels.intern_partition(a)?;
+    let b = labels.intern_partition(b)?;
+    utils::check_same_nodes(&a, &b)?;
+
+    let table = comparison::Contingency::new(&a, &b);
+    let scores = PyDict::new(py);
+    scores.set_item("nmi", table.nmi())?;
+    scores.set_item("ari", table.adjusted_rand_index())?;
+    scores.set_item("vi", table.variation_of_information())?;
+    scores.set_item("jaccard", table.jaccard())?;
+    Ok(scores)
+}
+
+/// Calculates the average conductance of the communities in a partition: for
+/// each community, the weight of edges leaving it divided by the smaller of its
+/// volume and the volume of the rest of the graph. Lower is better.
+///
+/// # Parameters
+/// - `graph` (networkx.Graph | list[tuple[int, int]]): The graph to analyze
+/// - `partition` (dict[int, int]): Dictionary mapping nodes to community IDs
+///
+/// # Returns
+/// - float in [0, 1]; a community spanning the whole graph contributes 0
+#[pyfunction(name = "conductance")]
+fn conductance(graph: &Bound<'_, PyAny>, partition: &Bound<'_, PyDict>) -> PyResult<f64> {
+    let (edges, labels) = utils::get_edges(graph)?;
+    let (graph, _) = utils::build_graph(edges, false, false, false, false);
+
+    Ok(quality::conductance(
+        &graph,
+        &utils::to_partition(partition, labels.as_ref())?,
+    ))
+}
+
+/// Calculates the coverage of a partition: the fraction of edges whose endpoints
+/// share a community.
+///
+/// # Parameters
+/// - `graph` (networkx.Graph | list[tuple[int, int]]): The graph to analyze
+/// - `partition` (dict[int, int]): Dictionary mapping nodes to community IDs
+///
+/// # Returns
+/// - float in [0, 1]
+#[pyfunction(name = "coverage")]
+fn coverage(graph: &Bound<'_, PyAny>, partition: &Bound<'_, PyDict>) -> PyResult<f64> {
+    let (edges, labels) = utils::get_edges(graph)?;
+    let (graph, _) = utils::build_graph(edges, false, false, false, false);
+
+    Ok(quality::coverage(
+        &graph,
+        &utils::to_partition(partition, labels.as_ref())?,
+    ))
+}
+
+/// Calculates the performance of a partition: intra-community edges plus
+/// inter-community non-edges, over all n * (n - 1) / 2 node pairs.
+///
+/// # Parameters
+/// - `graph` (networkx.Graph | list[tuple[int, int]]): The graph to analyze
+/// - `partition` (dict[int, int]): Dictionary mapping nodes to community IDs
+///
+/// # Returns
+/// - float in [0, 1]
+#[pyfunction(name = "performance")]
+fn performance(graph: &Bound<'_, PyAny>, partition: &Bound<'_, PyDict>) -> PyResult<f64> {
+    let (edges, labels) = utils::get_edges(graph)?;
+    let (graph, _) = utils::build_graph(edges, false, false, false, false);
+
+    Ok(quality::performance(
+        &graph,
+        &utils::to_partition(partition, labels.as_ref())?,
+    ))
+}
+
+/// Applies the segment crossover used by the optimizers to two partitions of `graph`.
+/// With probability `rate` a random run of nodes is exchanged between the parents,
+/// otherwise both come back unchanged.
+///
+/// # Parameters
+/// - `parent_a`, `parent_b` (dict[int, int]): Partitions covering every node of `graph`
+/// - `graph` (networkx.Graph | list[tuple[int, int]]): The graph the partitions belong to
+/// - `rate` (float): Crossover probability in [0, 1]
+/// - `seed` (int | None): Seed for the operator's random draws
+///
+/// # Returns
+/// - tuple[dict[int, int], dict[int, int]], with normalized community ids
+#[pyfunction(name = "crossover", signature = (parent_a, parent_b, graph, rate, seed = None))]
+fn crossover<'py>(
+    py: Python<'py>,
+    parent_a: &Bound<'py, PyDict>,
+    parent_b: &Bound<'py, PyDict>,
+    graph: &Bound<'py, PyAny>,
+    rate: f64,
+    seed: Option<u64>,
+) -> PyResult<(Bound<'py, PyDict>, Bound<'py, PyDict>)> {
+    utils::check_rate("rate", rate)?;
+    let (edges, labels) = utils::get_edges(graph)?;
+    let (graph, _) = utils::build_graph(edges, false, false, false, false);
+    let parent_a = utils::to_partition(parent_a, labels.as_ref())?;
+    let parent_b = utils::to_partition(parent_b, labels.as_ref())?;
+    utils::check_partition_nodes(&graph, &parent_a)?;
+    utils::check_partition_nodes(&graph, &parent_b)?;
+
+    let mut rng = operators::seeded_rng(seed.unwrap_or_else(|| rand::rng().random()), 0, 0);
+    let (child_a, child_b) = operators::paired_crossover(&parent_a, &parent_b, rate, &mut rng);
+
+    Ok((
+        utils::from_partition(
+            py,
+            &utils::normalize_community_ids(child_a),
+            labels.as_ref(),
+        )?,
+        utils::from_partition(
+            py,
+            &utils::normalize_community_ids(child_b),
+            labels.as_ref(),
+        )?,
+    ))
+}
+
+/// Applies the mutation used by the optimizers: each node, with probability `rate`,
+/// moves to the most common community among its neighbours unless that lowers Q.
+///
+/// # Parameters
+/// - `partition` (dict[int, int]): Partition covering every node of `graph`
+/// - `graph` (networkx.Graph | list[tuple[int, int]]): The graph the partition belongs to
+/// - `rate` (float): Per-node mutation probability in [0, 1]
+/// - `seed` (int | None): Seed for the operator's random draws
+///
+/// # Returns
+/// - dict[int, int], with normalized community ids
+#[pyfunction(name = "mutate", signature = (partition, graph, rate, seed = None))]
+fn mutate<'py>(
+    py: Python<'py>,
+    partition: &Bound<'py, PyDict>,
+    graph: &Bound<'py, PyAny>,
+    rate: f64,
+    seed: Option<u64>,
+) -> PyResult<Bound<'py, PyDict>> {
+    utils::check_rate("rate", rate)?;
+    let (edges, labels) = utils::get_edges(graph)?;
+    let (graph, _) = utils::build_graph(edges, false, false, false, false);
+    let mut partition = utils::to_partition(partition, labels.as_ref())?;
+    utils::check_partition_nodes(&graph, &partition)?;
+
+    let mut rng = operators::seeded_rng(seed.unwrap_or_else(|| rand::rng().random()), 0, 0);
+    operators::mutation(&mut partition, &graph, rate, 1.0, &mut rng);
+
+    utils::from_partition(
+        py,
+        &utils::normalize_community_ids(partition),
+        labels.as_ref(),
+    )
+}
+
+/// Sends the estimators' progress output (generation lines, convergence and time-limit
+/// notices, warnings) through the Rust `log` crate at `level`, printed to stdout by
+/// `env_logger`. `debug_level >= 1` on an estimator turns on `"info"` by itself.
+///
+/// # Parameters
+/// - `level` (str): One of "off", "error", "warn", "info", "debug" or "trace"
+#[pyfunction(name = "init_logging", signature = (level = "info"))]
+fn init_logging(level: &str) -> PyResult<()> {
+    let level = level.parse::<log::LevelFilter>().map_err(|_| {
+        PyValueError::new_err(format!(
+            "level must be one of off, error, warn, info, debug or trace, got {:?}",
+            level
+        ))
+    })?;
+    utils::init_logging(level);
+    Ok(())
+}
+
+/// Draws a G(n, p) random graph: every pair of the nodes `0..n` is linked with
+/// probability `p`. Isolated nodes do not appear in the returned edge list.
+///
+/// # Parameters
+/// - `n` (int): Number of nodes
+/// - `p` (float): Edge probability in [0, 1]
+/// - `seed` (int | None): Seed for the random draws
+///
+/// # Returns
+/// - list[tuple[int, int]], accepted by every estimator and metric
+#[pyfunction(name = "gen_erdos_renyi", signature = (n, p, seed = None))]
+fn gen_erdos_renyi(n: usize, p: f64, seed: Option<u64>) -> PyResult<Vec<(i32, i32)>> {
+    utils::check_rate("p", p)?;
+    let mut rng = operators::seeded_rng(seed.unwrap_or_else(|| rand::rng().random()), 0, 0);
+    Ok(graph::generators::erdos_renyi(n, p, &mut rng).edges)
+}
+
+/// Draws a Barabasi-Albert preferential attachment graph on the nodes `0..n`: each
+/// node from `m` on links to `m` earlier nodes, picked proportionally to their degree.
+///
+/// # Parameters
+/// - `n` (int): Number of nodes
+/// - `m` (int): Edges added per new node, 1 <= m < n
+/// - `seed` (int | None): Seed for the random draws
+///
+/// # Returns
+/// - list[tuple[int, int]] with m * (n - m) edges
+#[pyfunction(name = "gen_barabasi_albert", signature = (n, m, seed = None))]
+fn gen_barabasi_albert(n: usize, m: usize, seed: Option<u64>) -> PyResult<Vec<(i32, i32)>> {
+    if m < 1 || m >= n {
+        return Err(PyValueError::new_err(format!(
+            "m must satisfy 1 <= m < n, got m={} and n={}",
+            m, n
+        )));
+    }
+    let mut rng = operators::seeded_rng(seed.unwrap_or_else(|| rand::rng().random()), 0, 0);
+    Ok(graph::generators::barabasi_albert(n, m, &mut rng).edges)
+}
+
+/// Converts a node to community mapping into one list of nodes per community, the
+/// format tools such as cdlib expect. Communities are ordered by their normalized id
+/// (order of first appearance over the sorted nodes), as in the estimators' output;
+/// non-integer nodes are taken in the dict's order instead.
+///
+/// # Parameters
+/// - `partition` (dict[int, int]): Dictionary mapping nodes to community IDs
+///
+/// # Returns
+/// - list[list[int]], disjoint and covering every node of `partition`
+#[pyfunction(name = "partition_to_sets")]
+fn partition_to_sets<'py>(
+    py: Python<'py>,
+    partition: &Bound<'py, PyDict>,
+) -> PyResult<Vec<Vec<Bound<'py, PyAny>>>> {
+    // Integer nodes are sorted as they are; other labels keep their dict order
+    let (partition, labels) = match utils::to_partition(partition, None) {
+        Ok(partition) => (partition, None),
+        Err(_) => {
+            let mut labels = utils::NodeLabels::new(py);
+            (labels.intern_partition(partition)?, Some(labels))
+        }
+    };
+
+    utils::community_sets(partition)
+        .iter()
+        .map(|nodes| utils::node_labels(py, nodes, labels.as_ref()))
+        .collect()
+}
+
+/// Converts a node to community mapping into `(node, community)` pairs, one per node,
+/// for `pandas.DataFrame(records, columns=["node", "community"])`. Pairs are sorted by
+/// node and communities are normalized as in `partition_to_sets`; non-integer nodes
+/// are taken in the dict's order instead.
+///
+/// # Parameters
+/// - `partition` (dict[int, int]): Dictionary mapping nodes to community IDs
+///
+/// # Returns
+/// - list[tuple[int, int]], one pair per node of `partition`
+#[pyfunction(name = "partition_to_records")]
+fn partition_to_records<'py>(
+    py: Python<'py>,
+    partition: &Bound<'py, PyDict>,
+) -> PyResult<Vec<(Bound<'py, PyAny>, graph::CommunityId)>> {
+    // Integer nodes are sorted as they are; other labels keep their dict order
+    let (partition, labels) = match utils::to_partition(partition, None) {
+        Ok(partition) => (partition, None),
+        Err(_) => {
+            let mut labels = utils::NodeLabels::new(py);
+            (labels.intern_partition(partition)?, Some(labels))
+        }
+    };
+
+    utils::records_with_labels(py, utils::partition_records(partition), labels.as_ref())
+}
+
+/// Compares the nodes of a partition with those of `graph` without raising, to catch
+/// mislabelled nodes before scoring or a long run. Nodes are matched the way `fitness`
+/// matches them; the graph's nodes are the ends of its edges.
+///
+/// # Parameters
+/// - `graph` (networkx.Graph | list[tuple[int, int]]): The graph the partition is for
+/// - `partition` (dict[int, int]): Dictionary mapping nodes to community IDs
+///
+/// # Returns
+/// - dict with `missing_nodes` (graph nodes the partition leaves out, in id order),
+///   `extra_nodes` (partition nodes not in the graph, in dict order) and
+///   `num_communities` (distinct community values)
+#[pyfunction(name = "validate_partition")]
+fn validate_partition<'py>(
+    py: Python<'py>,
+    graph: &Bound<'py, PyAny>,
+    partition: &Bound<'py, PyDict>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let (edges, labels) = utils::get_edges(graph)?;
+    let (graph, _) = utils::build_graph(edges, false, false, false, false);
+
+    let mut assigned = FxHashSet::default();
+    let mut extra = Vec::new();
+    for node in partition.keys() {
+        let id = match &labels {
+            Some(labels) => labels.find(&node)?,
+            None => node.extract::<graph::NodeId>().ok(),
+        };
+        match id.filter(|id| graph.nodes.contains(id)) {
+            Some(id) => {
+                assigned.insert(id);
+            }
+            None => extra.push(node),
+        }
+    }
+
+    let missing = utils::missing_nodes(&graph, &assigned);
+    let report = PyDict::new(py);
+    report.set_item(
+        "missing_nodes",
+        utils::node_labels(py, &missing, labels.as_ref())?,
+    )?;
+    report.set_item("extra_nodes", extra)?;
+    report.set_item("num_communities", PySet::new(py, partition.values())?.len())?;
+    Ok(report)
+}
+
+/// Filters objective vectors to the non-dominated set under minimization, with the
+/// dominance test NSGA-II uses: a point is dropped when another is no worse in every
+/// objective and strictly better in one. Duplicate points are all kept.
+///
+/// # Parameters
+/// - `objectives` (list[list[float]]): One objective vector per candidate, all the
+///   same length, e.g. `[intra, inter]` from `fitness_components`
+///
+/// # Returns
+/// - list[int], indices of the non-dominated points in ascending order
+#[pyfunction(name = "pareto_filter")]
+fn pareto_filter(objectives: Vec<Vec<f64>>) -> PyResult<Vec<usize>> {
+    if let Some(first) = objectives.first()
+        && let Some(other) = objectives.iter().find(|point| point.len() != first.len())
+    {
+        return Err(PyValueError::new_err(format!(
+            "objective vectors differ in length: {} and {}",
+            first.len(),
+            other.len()
+        )));
+    }
+    Ok(hpc_mocd::pareto_filter(objectives))
+}
+
+/// Splits the edges of `graph` into train and test lists for model selection: fit on
+/// the train edges, then score the fitted communities on the held-out ones. About
+/// `frac` of the edges are held out at random, but never the last edge of a node, so
+/// train still reaches every node that had an edge; on sparse graphs the test list
+/// can come out shorter. The same `seed` gives the same split.
+///
+/// # Parameters
+/// - `graph` (networkx.Graph | list[tuple[int, int]]): The graph to split
+/// - `frac` (float): Share of the edges to hold out, in [0, 1]
+/// - `seed` (int | None): Seed for the random split
+///
+/// # Returns
+/// - tuple[list[tuple[node, node, float]], list[tuple[node, node, float]]], the train
+///   and test `(u, v, weight)` edges in input order
+#[pyfunction(name = "edge_holdout", signature = (graph, frac, seed = None))]
+fn edge_holdout<'py>(
+    py: Python<'py>,
+    graph: &Bound<'py, PyAny>,
+    frac: f64,
+    seed: Option<u64>,
+) -> PyResult<(utils::LabeledEdges<'py>, utils::LabeledEdges<'py>)> {
+    utils::check_rate("frac", frac)?;
+    let (edges, labels) = utils::get_edges(graph)?;
+    let (graph, _) = utils::build_graph(edges, false, false, false, false);
+
+    let mut rng = operators::seeded_rng(seed.unwrap_or_else(|| rand::rng().random()), 0, 0);
+    let (train, test) = graph.holdout_edges(frac, &mut rng);
+    Ok((
+        utils::edge_list(py, &train, labels.as_ref())?,
+        utils::edge_list(py, &test, labels.as_ref())?,
     ))
 }
 
+/// `MOCD` keyword arguments that hold per-graph data or a per-run callback, which
+/// `batch_max_q` cannot share across its graphs
+const PER_GRAPH_KWARGS: [&str; 7] = [
+    "initial_partition",
+    "node_weights",
+    "bipartite",
+    "node_types",
+    "must_link",
+    "cannot_link",
+    "on_generation",
+];
+
+/// Runs the PESA-II pipeline with max-Q selection on many graphs in one call. Every
+/// graph is parsed once, then the runs share a single release of the GIL and are
+/// spread over `num_threads` workers. Equivalent to `MOCD(graph, ...).max_q()` per
+/// graph with the same settings, so seeded runs give the same partitions.
+///
+/// # Parameters
+/// - `graphs` (list[networkx.Graph | list[tuple[int, int]]]): The graphs to analyze
+/// - `num_threads` (int | None): Workers shared by all runs; all cores when None
+/// - remaining keyword arguments are passed to `MOCD` as they are, except
+///   `initial_partition`, `node_weights`, `bipartite`/`node_types`,
+///   `must_link`/`cannot_link` and `on_generation`, which belong to a single graph or
+///   run and raise ValueError
+///
+/// # Returns
+/// - list[dict[int, int]], in the order of `graphs`
+#[pyfunction(name = "batch_max_q", signature = (graphs, num_threads = None, **kwargs))]
+fn batch_max_q<'py>(
+    py: Python<'py>,
+    graphs: Vec<Bound<'py, PyAny>>,
+    num_threads: Option<usize>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    if let Some(kwargs) = kwargs {
+        for key in PER_GRAPH_KWARGS {
+            if kwargs.contains(key)? {
+                return Err(PyValueError::new_err(format!(
+                    "batch_max_q does not take {}, which belongs to a single graph",
+                    key
+                )));
+            }
+        }
+    }
+    let models = graphs
+        .iter()
+        .map(|graph| {
+            Ok(py
+                .get_type::<MOCD>()
+                .call((graph,), kwargs)?
+                .downcast_into::<MOCD>()?)
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    let models: Vec<PyRef<'_, MOCD>> = models.iter().map(|model| model.borrow()).collect();
+    let models: Vec<&MOCD> = models.iter().map(|model| &**model).collect();
+
+    mocd::batch_max_q(py, &models, num_threads)
+}
+
 // ================================================================================================
 // Module
 // ================================================================================================
@@ -51,8 +779,32 @@ fn fitness(graph: &Bound<'_, PyAny>, partition: &Bound<'_, PyDict>) -> PyResult<
 #[pyo3(name = "pymocd")]
 fn pymocd(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(fitness, m)?)?;
+    m.add_function(wrap_pyfunction!(fitness_components, m)?)?;
+    m.add_function(wrap_pyfunction!(fitness_delta, m)?)?;
+    m.add_function(wrap_pyfunction!(nmi, m)?)?;
+    m.add_function(wrap_pyfunction!(ami, m)?)?;
+    m.add_function(wrap_pyfunction!(variation_of_information, m)?)?;
+    m.add_function(wrap_pyfunction!(rand_index, m)?)?;
+    m.add_function(wrap_pyfunction!(adjusted_rand_index, m)?)?;
+    m.add_function(wrap_pyfunction!(partition_jaccard, m)?)?;
+    m.add_function(wrap_pyfunction!(compare, m)?)?;
+    m.add_function(wrap_pyfunction!(conductance, m)?)?;
+    m.add_function(wrap_pyfunction!(coverage, m)?)?;
+    m.add_function(wrap_pyfunction!(performance, m)?)?;
+    m.add_function(wrap_pyfunction!(batch_max_q, m)?)?;
+    m.add_function(wrap_pyfunction!(pareto_filter, m)?)?;
+    m.add_function(wrap_pyfunction!(partition_to_sets, m)?)?;
+    m.add_function(wrap_pyfunction!(partition_to_records, m)?)?;
+    m.add_function(wrap_pyfunction!(edge_holdout, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_partition, m)?)?;
+    m.add_function(wrap_pyfunction!(crossover, m)?)?;
+    m.add_function(wrap_pyfunction!(mutate, m)?)?;
+    m.add_function(wrap_pyfunction!(gen_erdos_renyi, m)?)?;
+    m.add_function(wrap_pyfunction!(gen_barabasi_albert, m)?)?;
+    m.add_function(wrap_pyfunction!(init_logging, m)?)?;
     m.add_class::<HpMocd>()?;
     m.add_class::<CoCoMi>()?;
     m.add_class::<MOCD>()?;
+    m.add_class::<utils::ParetoIterator>()?;
     Ok(())
 }
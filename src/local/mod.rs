@@ -0,0 +1,152 @@
+//! local/mod.rs
+//! Local community detection around a seed node, without partitioning the graph
+//! This Source Code Form is subject to the terms of The GNU General Public License v3.0
+//! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
+//! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
+
+use crate::graph::{Graph, NodeId};
+use rustc_hash::FxHashSet as HashSet;
+use std::collections::BTreeSet;
+
+/// Grows a community from `seed` by greedily adding the neighbouring node that raises
+/// the local modularity R the most, until it holds `max_size` nodes or no candidate
+/// raises R. Ties go to the smaller node id. Nodes are returned in the order they
+/// joined, `seed` first.
+pub fn local_community(graph: &Graph, seed: NodeId, max_size: usize) -> Vec<NodeId> {
+    let mut community = vec![seed];
+    let mut members: HashSet<NodeId> = community.iter().copied().collect();
+    let mut score = local_modularity(graph, &members);
+
+    while community.len() < max_size {
+        let shell: BTreeSet<NodeId> = community
+            .iter()
+            .flat_map(|node| graph.neighbors(node))
+            .filter(|node| !members.contains(node))
+            .copied()
+            .collect();
+
+        let mut best: Option<(NodeId, f64)> = None;
+        for candidate in shell {
+            members.insert(candidate);
+            let candidate_score = local_modularity(graph, &members);
+            members.remove(&candidate);
+            if candidate_score > best.map_or(score, |(_, best_score)| best_score) {
+                best = Some((candidate, candidate_score));
+            }
+        }
+
+        let Some((node, best_score)) = best else {
+            break;
+        };
+        members.insert(node);
+        community.push(node);
+        score = best_score;
+    }
+    community
+}
+
+/// Local modularity R (Clauset, 2005) of the node set `members`: the share of the edge
+/// weight incident to its boundary (members with a neighbour outside) that stays
+/// inside. 1.0 when there is no boundary, 0.0 when the boundary has no edges.
+pub fn local_modularity(graph: &Graph, members: &HashSet<NodeId>) -> f64 {
+    let boundary: HashSet<NodeId> = members
+        .iter()
+        .copied()
+        .filter(|node| graph.neighbors(node).iter().any(|n| !members.contains(n)))
+        .collect();
+    if boundary.is_empty() {
+        return 1.0;
+    }
+
+    let (mut inside, mut total) = (0.0, 0.0);
+    for &node in &boundary {
+        for (neighbor, weight) in incident_edges(graph, node) {
+            // Edges between two boundary nodes are seen from both ends
+            let weight = if boundary.contains(&neighbor) {
+                weight / 2.0
+            } else {
+                weight
+            };
+            total += weight;
+            if members.contains(&neighbor) {
+                inside += weight;
+            }
+        }
+    }
+    if total == 0.0 { 0.0 } else { inside / total }
+}
+
+/// Other end and weight of every edge at `node`, whatever its direction
+fn incident_edges(graph: &Graph, node: NodeId) -> Vec<(NodeId, f64)> {
+    if !graph.directed {
+        return graph
+            .neighbors(&node)
+            .iter()
+            .map(|&neighbor| (neighbor, graph.weight(node, neighbor)))
+            .collect();
+    }
+    let outgoing = graph
+        .successors(&node)
+        .iter()
+        .map(|&to| (to, graph.weight(node, to)));
+    let incoming = graph
+        .predecessors(&node)
+        .iter()
+        .map(|&from| (from, graph.weight(from, node)));
+    outgoing.chain(incoming).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Two 5-cliques, 0..5 and 5..10, joined by the bridge 4-5
+    fn two_cliques() -> Graph {
+        let mut graph = Graph::new();
+        for offset in [0, 5] {
+            for i in 0..5 {
+                for j in (i + 1)..5 {
+                    graph.add_edge(offset + i, offset + j);
+                }
+            }
+        }
+        graph.add_edge(4, 5);
+        graph
+    }
+
+    #[test]
+    fn test_ego_community_is_the_seed_clique() {
+        let graph = two_cliques();
+        for seed in [0, 4] {
+            let mut community = local_community(&graph, seed, 10);
+            assert_eq!(community[0], seed);
+            community.sort_unstable();
+            assert_eq!(community, vec![0, 1, 2, 3, 4]);
+        }
+
+        // Only 4 touches the other clique: R = 4 / 5
+        let clique: HashSet<NodeId> = (0..5).collect();
+        assert!((local_modularity(&graph, &clique) - 0.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_max_size_and_isolated_seed() {
+        let mut graph = two_cliques();
+        assert_eq!(local_community(&graph, 7, 3).len(), 3);
+        assert_eq!(local_community(&graph, 7, 1), vec![7]);
+
+        graph.add_node(10);
+        assert_eq!(local_community(&graph, 10, 5), vec![10]);
+    }
+
+    #[test]
+    fn test_directed_edges_count_once() {
+        let mut graph = Graph::new_directed();
+        graph.add_weighted_edge(0, 1, 3.0);
+        graph.add_edge(2, 1);
+
+        // Boundary {1}: the 0 -> 1 edge stays inside, 2 -> 1 leaves
+        let members: HashSet<NodeId> = [0, 1].into_iter().collect();
+        assert!((local_modularity(&graph, &members) - 0.75).abs() < 1e-12);
+    }
+}
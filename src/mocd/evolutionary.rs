@@ -4,128 +4,120 @@
 //! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
 //! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
 
-use crate::mocd::{HyperBox, Solution, hypergrid};
+use crate::mocd::{HyperBox, Solution, hypergrid, model_selection};
 use crate::operators::*;
 
 use rayon::prelude::*;
 use rustc_hash::FxBuildHasher;
 use std::collections::HashMap;
-use std::sync::Arc;
-
-use rand::SeedableRng;
-use rand_chacha::ChaCha8Rng;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 
 use crate::graph::{Graph, Partition};
 
-pub const MAX_ARCHIVE_SIZE: usize = 100;
-
-/// Thread-safe random number generator management
-struct SafeRng {
-    seed_counter: AtomicU64,
-}
-
-impl SafeRng {
-    fn new() -> Self {
-        Self {
-            seed_counter: AtomicU64::new(0),
-        }
-    }
-
-    fn get_rng(&self) -> ChaCha8Rng {
-        let seed = self.seed_counter.fetch_add(1, Ordering::SeqCst);
-        ChaCha8Rng::seed_from_u64(seed)
-    }
-}
-
 /// Parallel population generation using PESA-II selection and reproduction
 fn generate_new_population(
     hyperboxes: &[HyperBox],
-    pop_size: usize,
-    cross_rate: f64,
-    mut_rate: f64,
+    config: &EvolutionConfig,
     graph: &Graph,
+    seed: u64,
+    generation: usize,
 ) -> Vec<Partition> {
-    // Create thread-safe RNG
-    let safe_rng = Arc::new(SafeRng::new());
-
-    // Calculate chunk size based on available threads
-    let chunk_size = (pop_size / rayon::current_num_threads()).max(1);
-
-    // Create chunks for parallel processing
-    let chunks: Vec<_> = (0..pop_size)
-        .collect::<Vec<_>>()
-        .chunks(chunk_size)
-        .map(|c| c.len())
-        .collect();
-
-    // Process chunks in parallel
-    let results: Vec<Partition> = chunks
-        .par_iter()
-        .flat_map(|&chunk_size| {
-            let mut local_children = Vec::with_capacity(chunk_size);
-            let safe_rng = Arc::clone(&safe_rng);
-
-            // Create a thread-local RNG
-            let mut local_rng = safe_rng.get_rng();
-
-            // Process each chunk
-            for _ in 0..chunk_size {
-                // Thread-safe selection of parents
-                let parent1 = hypergrid::select(hyperboxes, &mut local_rng);
-                let parent2 = hypergrid::select(hyperboxes, &mut local_rng);
-
-                // Perform crossover and mutation
-                let mut child = crossover(&parent1.partition, &parent2.partition, cross_rate);
-                mutation(&mut child, graph, mut_rate);
-                local_children.push(child);
-            }
-
-            local_children
+    (0..config.pop_size)
+        .into_par_iter()
+        .map(|index| {
+            // Each child draws from its own stream, keeping runs reproducible
+            let mut rng = seeded_rng(seed, generation + 1, index);
+
+            let parent1 = hypergrid::select(hyperboxes, &mut rng);
+            let parent2 = hypergrid::select(hyperboxes, &mut rng);
+
+            // Perform crossover and mutation
+            let mut child = crossover(
+                &parent1.partition,
+                &parent2.partition,
+                config.crossover_type.unwrap_or(CrossoverType::TwoPoint),
+                config.cross_rate,
+                &mut rng,
+            );
+            mutate_child(&mut child, graph, config, generation, &mut rng);
+            constrain_communities(&mut child, graph, config, &mut rng);
+            child
         })
-        .collect();
-
-    results
+        .collect()
 }
 
 pub fn evolutionary_phase(
     graph: &Graph,
     debug_level: i8,
-    num_gens: usize,
-    pop_size: usize,
-    cross_rate: f64,
-    mut_rate: f64,
+    config: &EvolutionConfig,
     degrees: &HashMap<i32, usize, FxBuildHasher>,
-) -> Vec<Solution> {
-    // Validate graph
-    if graph.nodes.is_empty() || graph.edges.is_empty() {
-        println!("[evolutionary_phase]: Empty graph detected");
-        return Vec::new();
+    on_generation: Option<&GenerationCallback<'_>>,
+) -> EvolutionResult<Solution> {
+    // Nothing to optimize without edges: the front is the singleton partition
+    if graph.edges.is_empty() {
+        if debug_level >= 1 {
+            log::info!("[evolutionary_phase]: Empty graph detected");
+        }
+        let mut partition = singleton_partition(graph);
+        enforce_links(&mut partition, config);
+        let metrics = get_fitness(
+            graph,
+            &partition,
+            degrees,
+            false,
+            config.resolution,
+            config.modularity_variant,
+        );
+        return EvolutionResult {
+            front: vec![Solution {
+                partition,
+                objectives: vec![metrics.intra, metrics.inter],
+            }],
+            history: Vec::new(),
+        };
     }
 
     // Debug print graph information
     if debug_level >= 2 {
-        println!(
+        log::debug!(
             "[evolutionary_phase]: Starting with graph - nodes: {}, edges: {}",
             graph.nodes.len(),
             graph.edges.len()
         );
     }
 
-    let mut archive: Vec<Solution> = Vec::with_capacity(pop_size);
+    let seed = config.resolve_seed();
+    let mut archive: Vec<Solution> = Vec::with_capacity(config.pop_size);
 
     // Generate and validate initial population
-    let mut population = generate_population(graph, pop_size);
-    let mut best_fitness_history: Vec<f64> = Vec::with_capacity(num_gens);
-    let mut max_local: ConvergenceCriteria = ConvergenceCriteria::default();
+    let mut rng = seeded_rng(seed, 0, 0);
+    let mut population =
+        generate_population(graph, config.pop_size, config.init_strategy, &mut rng);
+    warm_start(&mut population, config.initial_partition.as_ref());
+    for partition in &mut population {
+        constrain_communities(partition, graph, config, &mut rng);
+    }
+    let mut best_fitness_history: Vec<f64> = Vec::with_capacity(config.num_gens);
+    let mut max_local: ConvergenceCriteria = config.convergence();
+    let mut last_occupancy: Option<u64> = None;
+    let mut stagnant_archive = 0;
+
+    let start = Instant::now();
+    for generation in 0..config.num_gens {
+        // Out of time: keep the archive of the generations that completed
+        if generation > 0 && config.time_exceeded(start) {
+            if debug_level >= 1 {
+                log::info!("[evolutionary_phase]: Time limit reached");
+            }
+            break;
+        }
 
-    for generation in 0..num_gens {
         // Validate population size before parallel processing
         let num_threads = rayon::current_num_threads();
         let chunk_size = population.len().max(1) / num_threads;
 
         if chunk_size == 0 {
-            println!("[evolutionary_phase]: Population too small for parallelization");
+            log::warn!("[evolutionary_phase]: Population too small for parallelization");
             break;
         }
 
@@ -136,10 +128,19 @@ pub fn evolutionary_phase(
                 chunk
                     .iter()
                     .map(|partition| {
-                        let metrics = get_fitness(graph, partition, degrees, true);
+                        let metrics = get_fitness(
+                            graph,
+                            partition,
+                            degrees,
+                            true,
+                            config.resolution,
+                            config.modularity_variant,
+                        );
+                        let mut objectives = vec![metrics.intra, metrics.inter];
+                        round_objectives(&mut objectives, config.round_objectives);
                         Solution {
                             partition: partition.clone(),
-                            objectives: vec![metrics.inter, metrics.intra],
+                            objectives,
                         }
                     })
                     .collect::<Vec<_>>()
@@ -147,7 +148,7 @@ pub fn evolutionary_phase(
             .collect();
 
         if solutions.is_empty() {
-            println!("[evolutionary_phase]: No valid solutions generated");
+            log::warn!("[evolutionary_phase]: No valid solutions generated");
             break;
         }
 
@@ -160,25 +161,62 @@ pub fn evolutionary_phase(
         }
 
         if archive.is_empty() {
-            println!("[evolutionary_phase]: Empty archive after update");
+            log::warn!("[evolutionary_phase]: Empty archive after update");
             break;
         }
 
-        if archive.len() > MAX_ARCHIVE_SIZE {
-            hypergrid::truncate_archive(&mut archive, MAX_ARCHIVE_SIZE);
+        // Read before truncation, which thins crowded boxes differently every generation
+        if config.archive_patience.is_some() {
+            let occupancy = hypergrid::occupancy_hash(&hypergrid::create(
+                &archive,
+                config.grid_divisions,
+                config.obj_min,
+                config.obj_max,
+            ));
+            if last_occupancy == Some(occupancy) {
+                stagnant_archive += 1;
+            } else {
+                last_occupancy = Some(occupancy);
+                stagnant_archive = 0;
+            }
+        }
+
+        if archive.len() > config.archive_size {
+            // Truncation keeps the best fifth by Q, which is nobody in archives under five
+            let elite = config
+                .elitism
+                .then(|| model_selection::max_q_selection(&archive).clone());
+            hypergrid::truncate_archive(
+                &mut archive,
+                config.archive_size,
+                config.grid_divisions,
+                config.obj_min,
+                config.obj_max,
+            );
+            if let Some(elite) = elite
+                && !archive.iter().any(|sol| sol.partition == elite.partition)
+            {
+                archive.pop();
+                archive.push(elite);
+            }
         }
 
         // Validate archive before creating hyperboxes
         if archive.is_empty() {
-            println!("[evolutionary_phase]: Empty archive after truncation");
+            log::warn!("[evolutionary_phase]: Empty archive after truncation");
             break;
         }
 
         // Create hyperboxes from archive
-        let hyperboxes: Vec<HyperBox> = hypergrid::create(&archive, hypergrid::GRID_DIVISIONS);
+        let hyperboxes: Vec<HyperBox> = hypergrid::create(
+            &archive,
+            config.grid_divisions,
+            config.obj_min,
+            config.obj_max,
+        );
 
         if hyperboxes.is_empty() {
-            println!("[evolutionary_phase]: No valid hyperboxes created");
+            log::warn!("[evolutionary_phase]: No valid hyperboxes created");
             break;
         }
 
@@ -191,26 +229,45 @@ pub fn evolutionary_phase(
 
         best_fitness_history.push(best_fitness);
 
+        if let Some(callback) = on_generation
+            && !callback(generation, best_fitness)
+        {
+            break;
+        }
+
         // Generate new population with validation
-        let new_population =
-            generate_new_population(&hyperboxes, pop_size, cross_rate, mut_rate, graph);
+        let mut new_population =
+            generate_new_population(&hyperboxes, config, graph, seed, generation);
         if new_population.is_empty() {
-            println!("[evolutionary_phase]: Failed to generate new population");
+            log::warn!("[evolutionary_phase]: Failed to generate new population");
             break;
         }
+        // The max-Q solution replaces the first child unchanged
+        if config.elitism {
+            new_population[0] = model_selection::max_q_selection(&archive).partition.clone();
+        }
         population = new_population;
 
         // Early stopping
         if max_local.has_converged(best_fitness) {
             if debug_level >= 1 {
-                println!("[evolutionary_phase]: Converged!");
+                log::info!("[evolutionary_phase]: Converged!");
+            }
+            break;
+        }
+        if config
+            .archive_patience
+            .is_some_and(|patience| stagnant_archive >= patience)
+        {
+            if debug_level >= 1 {
+                log::info!("[evolutionary_phase]: Archive stagnated");
             }
             break;
         }
 
         if debug_level >= 1 {
-            println!(
-                "\x1b[1A\x1b[2K[evolutionary_phase]: gen: {} | bf: {:.4} | pop/arch: {}/{} | bA: {:.4} |",
+            log::info!(
+                "[evolutionary_phase]: gen: {} | bf: {:.4} | pop/arch: {}/{} | bA: {:.4} |",
                 generation,
                 best_fitness,
                 population.len(),
@@ -220,5 +277,494 @@ pub fn evolutionary_phase(
         }
     }
 
-    archive
+    EvolutionResult {
+        front: archive,
+        history: best_fitness_history,
+    }
+}
+
+/// Reassigns `kicks` random nodes of the max-Q partition of a fitted `archive`, then
+/// runs the evolutionary phase again warm-started from the perturbed partition (see
+/// `warm_start`). Returns the new run only when its max-Q beats the old one.
+pub fn warm_restart(
+    graph: &Graph,
+    debug_level: i8,
+    config: &EvolutionConfig,
+    archive: &[Solution],
+    kicks: usize,
+    degrees: &HashMap<i32, usize, FxBuildHasher>,
+    on_generation: Option<&GenerationCallback<'_>>,
+) -> Option<EvolutionResult<Solution>> {
+    let max_q = |front: &[Solution]| {
+        let best = model_selection::max_q_selection(front);
+        1.0 - best.objectives[0] - best.objectives[1]
+    };
+    let seed = config.resolve_seed();
+    let mut partition = model_selection::max_q_selection(archive).partition.clone();
+    // Stream 1 of generation 0 is left unused by the run itself
+    kick(&mut partition, kicks, &mut seeded_rng(seed, 0, 1));
+    let config = EvolutionConfig {
+        seed: Some(seed),
+        initial_partition: Some(partition),
+        ..config.clone()
+    };
+
+    let result = evolutionary_phase(graph, debug_level, &config, degrees, on_generation);
+    let (before, after) = (max_q(archive), max_q(&result.front));
+    if debug_level >= 1 {
+        log::info!(
+            "[warm_restart]: max Q {:.4} -> {:.4} after {} kicks",
+            before,
+            after,
+            kicks
+        );
+    }
+    (after > before).then_some(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::operators::install;
+
+    fn two_cliques() -> Graph {
+        let mut graph = Graph::new();
+        for offset in [0, 5] {
+            for i in offset..offset + 5 {
+                for j in (i + 1)..offset + 5 {
+                    graph.add_edge(i, j);
+                }
+            }
+        }
+        graph.add_edge(4, 5);
+        graph
+    }
+
+    fn config(seed: Option<u64>) -> EvolutionConfig {
+        EvolutionConfig {
+            num_gens: 20,
+            pop_size: 40,
+            seed,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_edgeless_graphs_return_singletons() {
+        // No nodes, one node, and two nodes without an edge between them
+        for num_nodes in 0..3 {
+            let mut graph = Graph::new();
+            for node in 0..num_nodes {
+                graph.add_node(node);
+            }
+            let degrees = graph.precompute_degrees();
+
+            let result = evolutionary_phase(&graph, 0, &config(Some(1)), &degrees, None);
+            assert_eq!(result.front.len(), 1);
+            let expected: Partition = (0..num_nodes).map(|node| (node, node)).collect();
+            assert_eq!(result.front[0].partition, expected);
+            assert_eq!(result.front[0].objectives, vec![1.0, 0.0]);
+
+            let random_graphs = crate::mocd::model_selection::generate_random_networks(
+                &graph,
+                2,
+                crate::mocd::model_selection::NullModel::ErdosRenyi,
+                1,
+            );
+            assert!(random_graphs.iter().all(|random| random.edges.is_empty()));
+        }
+    }
+
+    #[test]
+    fn test_isolated_node_is_assigned() {
+        let mut graph = two_cliques();
+        graph.add_node(10);
+        let degrees = graph.precompute_degrees();
+
+        let front = evolutionary_phase(&graph, 0, &config(Some(3)), &degrees, None).front;
+        assert!(!front.is_empty());
+        assert!(
+            front
+                .iter()
+                .all(|solution| solution.partition.contains_key(&10))
+        );
+    }
+
+    #[test]
+    fn test_front_spans_one_and_two_communities() {
+        let graph = two_cliques();
+        let degrees = graph.precompute_degrees();
+
+        let config = EvolutionConfig {
+            // Merges reach the single-community end, and an archive that never fills
+            // keeps truncation from thinning that lone extreme
+            macro_mutation_rate: 0.3,
+            archive_size: 1000,
+            ..config(Some(7))
+        };
+        let front = evolutionary_phase(&graph, 0, &config, &degrees, None).front;
+        let counts: Vec<usize> = front
+            .iter()
+            .map(|solution| crate::utils::community_count(&solution.partition))
+            .collect();
+        assert!(counts.contains(&1), "{:?}", counts);
+        assert!(counts.contains(&2), "{:?}", counts);
+    }
+
+    #[test]
+    fn test_label_propagation_start_leads_at_generation_ten() {
+        // Eight 5-cliques joined in a ring
+        let mut graph = Graph::new();
+        for clique in 0..8 {
+            let offset = clique * 5;
+            for i in offset..offset + 5 {
+                for j in (i + 1)..offset + 5 {
+                    graph.add_edge(i, j);
+                }
+            }
+            graph.add_edge(offset + 4, (offset + 5) % 40);
+        }
+        let degrees = graph.precompute_degrees();
+
+        let q_at_ten = |init_strategy| {
+            let config = EvolutionConfig {
+                patience: None,
+                init_strategy,
+                ..config(Some(3))
+            };
+            evolutionary_phase(&graph, 0, &config, &degrees, None).history[9]
+        };
+        let random = q_at_ten(InitStrategy::Random);
+        let label_propagation = q_at_ten(InitStrategy::LabelPropagation);
+        assert!(
+            label_propagation > random,
+            "{} <= {}",
+            label_propagation,
+            random
+        );
+    }
+
+    #[test]
+    fn test_warm_restart_never_lowers_max_q() {
+        let graph = two_cliques();
+        let degrees = graph.precompute_degrees();
+        let max_q = |front: &[Solution]| {
+            let best = model_selection::max_q_selection(front);
+            1.0 - best.objectives[0] - best.objectives[1]
+        };
+
+        for seed in 0..4 {
+            let config = EvolutionConfig {
+                num_gens: 3,
+                ..config(Some(seed))
+            };
+            let front = evolutionary_phase(&graph, 0, &config, &degrees, None).front;
+            let before = max_q(&front);
+            let after = match warm_restart(&graph, 0, &config, &front, 3, &degrees, None) {
+                Some(restarted) => max_q(&restarted.front),
+                None => before,
+            };
+            assert!(after >= before);
+        }
+    }
+
+    #[test]
+    fn test_seeded_runs_are_identical() {
+        let graph = two_cliques();
+        let degrees = graph.precompute_degrees();
+
+        let first = evolutionary_phase(&graph, 0, &config(Some(42)), &degrees, None).front;
+        let second = evolutionary_phase(&graph, 0, &config(Some(42)), &degrees, None).front;
+
+        assert_eq!(first.len(), second.len());
+        for (a, b) in first.iter().zip(second.iter()) {
+            assert_eq!(a.partition, b.partition);
+            assert_eq!(a.objectives, b.objectives);
+        }
+    }
+
+    #[test]
+    fn test_thread_count_does_not_change_seeded_runs() {
+        let graph = two_cliques();
+        let degrees = graph.precompute_degrees();
+        let run = |num_threads| {
+            install(Some(num_threads), || {
+                evolutionary_phase(&graph, 0, &config(Some(42)), &degrees, None).front
+            })
+            .unwrap()
+        };
+
+        let single = run(1);
+        let multi = run(4);
+        assert_eq!(single.len(), multi.len());
+        for (a, b) in single.iter().zip(multi.iter()) {
+            assert_eq!(a.partition, b.partition);
+            assert_eq!(a.objectives, b.objectives);
+        }
+    }
+
+    #[test]
+    fn test_callback_runs_every_generation() {
+        let graph = two_cliques();
+        let degrees = graph.precompute_degrees();
+        let calls = std::sync::Mutex::new(Vec::new());
+        let callback = |generation: usize, best_q: f64| {
+            calls.lock().unwrap().push((generation, best_q));
+            true
+        };
+
+        evolutionary_phase(&graph, 0, &config(Some(1)), &degrees, Some(&callback));
+
+        let calls = calls.into_inner().unwrap();
+        assert!(!calls.is_empty());
+        assert!(
+            calls
+                .iter()
+                .enumerate()
+                .all(|(i, &(generation, _))| i == generation)
+        );
+    }
+
+    #[test]
+    fn test_num_communities_is_enforced() {
+        let graph = two_cliques();
+        let config = EvolutionConfig {
+            num_communities: Some(3),
+            ..config(Some(5))
+        };
+
+        let front = evolutionary_phase(&graph, 0, &config, &graph.precompute_degrees(), None).front;
+        assert!(!front.is_empty());
+        for solution in &front {
+            let labels: std::collections::BTreeSet<_> = solution.partition.values().collect();
+            assert_eq!(labels.len(), 3);
+        }
+    }
+
+    #[test]
+    fn test_link_constraints_hold_across_the_front() {
+        let graph = two_cliques();
+        let config = EvolutionConfig {
+            must_link: vec![(0, 9)],
+            cannot_link: vec![(1, 2)],
+            ..config(Some(5))
+        };
+
+        let front = evolutionary_phase(&graph, 0, &config, &graph.precompute_degrees(), None).front;
+        assert!(!front.is_empty());
+        for solution in &front {
+            assert_eq!(solution.partition[&0], solution.partition[&9]);
+            assert_ne!(solution.partition[&1], solution.partition[&2]);
+        }
+    }
+
+    #[test]
+    fn test_max_community_size_is_enforced() {
+        let mut graph = Graph::new();
+        for i in 0..12 {
+            for j in (i + 1)..12 {
+                graph.add_edge(i, j);
+            }
+        }
+        let config = EvolutionConfig {
+            max_community_size: Some(4),
+            ..config(Some(5))
+        };
+
+        let front = evolutionary_phase(&graph, 0, &config, &graph.precompute_degrees(), None).front;
+        assert!(!front.is_empty());
+        for solution in &front {
+            let mut sizes = std::collections::BTreeMap::new();
+            for community in solution.partition.values() {
+                *sizes.entry(community).or_insert(0) += 1;
+            }
+            assert!(sizes.values().all(|&size| size <= 4), "{:?}", sizes);
+        }
+    }
+
+    #[test]
+    fn test_time_limit_keeps_first_generation() {
+        let graph = two_cliques();
+        let config = EvolutionConfig {
+            num_gens: 1_000,
+            time_limit_secs: Some(0.0),
+            ..config(Some(3))
+        };
+        let generations = std::sync::atomic::AtomicUsize::new(0);
+        let callback = |_: usize, _: f64| {
+            generations.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            true
+        };
+
+        let front = evolutionary_phase(
+            &graph,
+            0,
+            &config,
+            &graph.precompute_degrees(),
+            Some(&callback),
+        )
+        .front;
+        assert_eq!(generations.into_inner(), 1);
+        assert!(!front.is_empty());
+    }
+
+    #[test]
+    fn test_patience_stops_early() {
+        let graph = two_cliques();
+        let config = EvolutionConfig {
+            num_gens: 500,
+            patience: Some(5),
+            ..config(Some(3))
+        };
+        let generations = std::sync::atomic::AtomicUsize::new(0);
+        let callback = |_: usize, _: f64| {
+            generations.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            true
+        };
+
+        let result = evolutionary_phase(
+            &graph,
+            0,
+            &config,
+            &graph.precompute_degrees(),
+            Some(&callback),
+        );
+        let generations = generations.into_inner();
+        assert!(generations < 100);
+        assert_eq!(result.history.len(), generations);
+    }
+
+    #[test]
+    fn test_finer_grid_spreads_the_archive() {
+        // Six 6-cliques in a ring, large enough for the archive to fill up
+        let mut graph = Graph::new();
+        for offset in (0..36).step_by(6) {
+            for i in offset..offset + 6 {
+                for j in (i + 1)..offset + 6 {
+                    graph.add_edge(i, j);
+                }
+            }
+            graph.add_edge(offset, (offset + 6) % 36);
+            graph.add_edge(offset + 1, (offset + 9) % 36);
+        }
+        let degrees = graph.precompute_degrees();
+        let occupied_boxes = |grid_divisions| {
+            let config = EvolutionConfig {
+                num_gens: 60,
+                pop_size: 60,
+                patience: None,
+                grid_divisions,
+                ..config(Some(0))
+            };
+            let front = evolutionary_phase(&graph, 0, &config, &degrees, None).front;
+            hypergrid::create(&front, 32, None, None).len()
+        };
+
+        assert!(occupied_boxes(32) > occupied_boxes(1));
+    }
+
+    #[test]
+    fn test_archive_patience_stops_early() {
+        let graph = two_cliques();
+        let degrees = graph.precompute_degrees();
+        let generations = |archive_patience| {
+            let config = EvolutionConfig {
+                num_gens: 200,
+                patience: None,
+                archive_patience,
+                ..config(Some(5))
+            };
+            evolutionary_phase(&graph, 0, &config, &degrees, None)
+                .history
+                .len()
+        };
+
+        // Two cliques settle on a fixed front long before 200 generations
+        assert_eq!(generations(None), 200);
+        assert!(generations(Some(3)) < 200);
+    }
+
+    #[test]
+    fn test_front_never_exceeds_archive_size() {
+        // Six 6-cliques in a ring, large enough for the archive to fill up
+        let mut graph = Graph::new();
+        for offset in (0..36).step_by(6) {
+            for i in offset..offset + 6 {
+                for j in (i + 1)..offset + 6 {
+                    graph.add_edge(i, j);
+                }
+            }
+            graph.add_edge(offset, (offset + 6) % 36);
+        }
+        let degrees = graph.precompute_degrees();
+        let front_size = |archive_size| {
+            let config = EvolutionConfig {
+                num_gens: 40,
+                pop_size: 20,
+                patience: None,
+                archive_size,
+                ..config(Some(3))
+            };
+            evolutionary_phase(&graph, 0, &config, &degrees, None)
+                .front
+                .len()
+        };
+
+        // A 20-individual population still feeds a front larger than 4
+        assert!(front_size(200) > 4);
+        assert!(front_size(4) <= 4);
+    }
+
+    #[test]
+    fn test_warm_start_reaches_target_sooner() {
+        // Eight 6-cliques in a ring; the cliques themselves are the best partition
+        let mut graph = Graph::new();
+        for offset in (0..48).step_by(6) {
+            for i in offset..offset + 6 {
+                for j in (i + 1)..offset + 6 {
+                    graph.add_edge(i, j);
+                }
+            }
+            graph.add_edge(offset, (offset + 6) % 48);
+            graph.add_edge(offset + 1, (offset + 9) % 48);
+        }
+        let cliques: Partition = (0..48).map(|node| (node, node / 6)).collect();
+        let target = get_modularity_from_partition(&cliques, &graph, 1.0) - 1e-9;
+        let degrees = graph.precompute_degrees();
+        let generations_to_target = |initial_partition| {
+            let config = EvolutionConfig {
+                num_gens: 60,
+                patience: None,
+                initial_partition,
+                ..config(Some(2))
+            };
+            evolutionary_phase(&graph, 0, &config, &degrees, None)
+                .history
+                .iter()
+                .position(|&q| q >= target)
+                .unwrap_or(usize::MAX)
+        };
+
+        let warm = generations_to_target(Some(cliques.clone()));
+        let cold = generations_to_target(None);
+        assert_eq!(warm, 0);
+        assert!(cold > warm);
+    }
+
+    #[test]
+    fn test_elitism_keeps_best_q_non_decreasing() {
+        let graph = two_cliques();
+        let degrees = graph.precompute_degrees();
+        for seed in 0..4 {
+            let config = EvolutionConfig {
+                archive_size: 2,
+                patience: None,
+                ..config(Some(seed))
+            };
+
+            let history = evolutionary_phase(&graph, 0, &config, &degrees, None).history;
+            assert_eq!(history.len(), config.num_gens);
+            assert!(history.windows(2).all(|pair| pair[1] >= pair[0]));
+        }
+    }
 }
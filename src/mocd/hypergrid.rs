@@ -4,11 +4,12 @@
 //! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.h
 
 use crate::graph::Partition;
+use crate::persist::{Decode, Encode};
 use rayon::prelude::*;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashSet, FxHasher};
 use std::cmp::Ordering;
-
-pub const GRID_DIVISIONS: usize = 8;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
 
 #[derive(Clone, Debug)]
 pub struct Solution {
@@ -23,6 +24,22 @@ pub struct HyperBox {
     pub coordinates: Vec<usize>,
 }
 
+impl Encode for Solution {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.partition.encode(out);
+        self.objectives.encode(out);
+    }
+}
+
+impl Decode for Solution {
+    fn decode(input: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Solution {
+            partition: Decode::decode(input)?,
+            objectives: Decode::decode(input)?,
+        })
+    }
+}
+
 impl HyperBox {
     #[allow(dead_code)]
     pub fn density(&self) -> f64 {
@@ -32,7 +49,7 @@ impl HyperBox {
 
 impl Solution {
     /// Determines if this solution dominates another solution
-    /// For the two objectives (inter, intra), lower values are better
+    /// For the two objectives (intra, inter), lower values are better
     pub fn dominates(&self, other: &Solution) -> bool {
         let mut has_better = false;
         for (self_obj, other_obj) in self.objectives.iter().zip(other.objectives.iter()) {
@@ -48,7 +65,13 @@ impl Solution {
     }
 }
 
-pub fn truncate_archive(archive: &mut Vec<Solution>, max_size: usize) {
+pub fn truncate_archive(
+    archive: &mut Vec<Solution>,
+    max_size: usize,
+    divisions: usize,
+    obj_min: Option<(f64, f64)>,
+    obj_max: Option<(f64, f64)>,
+) {
     if archive.len() <= max_size {
         return;
     }
@@ -87,7 +110,7 @@ pub fn truncate_archive(archive: &mut Vec<Solution>, max_size: usize) {
         .map(|(index, _)| *index)
         .collect();
 
-    let hyperboxes = create(archive, GRID_DIVISIONS);
+    let hyperboxes = create(archive, divisions, obj_min, obj_max);
 
     let mut solution_scores: Vec<(usize, f64)> = archive
         .iter()
@@ -153,26 +176,26 @@ fn calculate_crowding_distance(solution: &Solution, neighbors: &[Solution]) -> f
         if let Some(pos) = sorted_neighbors
             .iter()
             .position(|s| s.objectives == solution.objectives)
+            && pos > 0
+            && pos < sorted_neighbors.len() - 1
         {
-            if pos > 0 && pos < sorted_neighbors.len() - 1 {
-                let range = sorted_neighbors
-                    .iter()
-                    .map(|s| s.objectives[obj_index])
-                    .fold((f64::INFINITY, f64::NEG_INFINITY), |acc, val| {
-                        (acc.0.min(val), acc.1.max(val))
-                    });
-
-                let diff = if range.1 > range.0 {
-                    (sorted_neighbors[pos + 1].objectives[obj_index]
-                        - sorted_neighbors[pos - 1].objectives[obj_index])
-                        .abs()
-                        / (range.1 - range.0)
-                } else {
-                    0.0
-                };
-
-                distances.push(diff);
-            }
+            let range = sorted_neighbors
+                .iter()
+                .map(|s| s.objectives[obj_index])
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |acc, val| {
+                    (acc.0.min(val), acc.1.max(val))
+                });
+
+            let diff = if range.1 > range.0 {
+                (sorted_neighbors[pos + 1].objectives[obj_index]
+                    - sorted_neighbors[pos - 1].objectives[obj_index])
+                    .abs()
+                    / (range.1 - range.0)
+            } else {
+                0.0
+            };
+
+            distances.push(diff);
         }
     }
 
@@ -183,15 +206,26 @@ fn calculate_crowding_distance(solution: &Solution, neighbors: &[Solution]) -> f
     }
 }
 
-/// Creates hyperboxes for the two-objective space (inter and intra)
-pub fn create(solutions: &[Solution], divisions: usize) -> Vec<HyperBox> {
+/// Creates hyperboxes for the two-objective space (intra and inter), splitting the
+/// range spanned by `solutions` into `divisions` equal bins per objective. Coarser
+/// grids crowd more solutions into each box; MOCD defaults to 8.
+///
+/// `obj_min` and `obj_max` together fix the (intra, inter) ends of that range instead,
+/// so a solution keeps its box from one generation to the next; objectives outside
+/// them fall into the outermost boxes.
+pub fn create(
+    solutions: &[Solution],
+    divisions: usize,
+    obj_min: Option<(f64, f64)>,
+    obj_max: Option<(f64, f64)>,
+) -> Vec<HyperBox> {
     if solutions.is_empty() {
         return Vec::new();
     }
 
     // Calculate min/max values in parallel
     let obj_len = solutions[0].objectives.len();
-    let (min_values, max_values) = rayon::join(
+    let (mut min_values, mut max_values) = rayon::join(
         || {
             (0..obj_len)
                 .into_par_iter()
@@ -218,32 +252,40 @@ pub fn create(solutions: &[Solution], divisions: usize) -> Vec<HyperBox> {
         },
     );
 
-    // Use a concurrent HashMap for grouping solutions
-    use dashmap::DashMap;
-    let hyperbox_map = DashMap::new();
+    if let (Some(min), Some(max)) = (obj_min, obj_max) {
+        min_values = vec![min.0, min.1];
+        max_values = vec![max.0, max.1];
+    }
 
-    solutions.par_iter().for_each(|solution| {
-        let coordinates = solution
-            .objectives
-            .iter()
-            .enumerate()
-            .map(|(i, &obj)| {
-                let normalized = if (max_values[i] - min_values[i]).abs() < f64::EPSILON {
-                    0.0
-                } else {
-                    (obj - min_values[i]) / (max_values[i] - min_values[i])
-                };
-                (normalized * divisions as f64).min((divisions - 1) as f64) as usize
-            })
-            .collect::<Vec<_>>();
+    // Compute box coordinates in parallel; `collect` keeps the input order
+    let coordinates: Vec<Vec<usize>> = solutions
+        .par_iter()
+        .map(|solution| {
+            solution
+                .objectives
+                .iter()
+                .enumerate()
+                .map(|(i, &obj)| {
+                    let normalized = if (max_values[i] - min_values[i]).abs() < f64::EPSILON {
+                        0.0
+                    } else {
+                        (obj - min_values[i]) / (max_values[i] - min_values[i])
+                    };
+                    (normalized * divisions as f64).clamp(0.0, (divisions - 1) as f64) as usize
+                })
+                .collect()
+        })
+        .collect();
 
+    // Group sequentially in coordinate order so the boxes are deterministic
+    let mut hyperbox_map: BTreeMap<Vec<usize>, Vec<Solution>> = BTreeMap::new();
+    for (solution, coordinates) in solutions.iter().zip(coordinates) {
         hyperbox_map
-            .entry(coordinates.clone())
-            .and_modify(|solutions: &mut Vec<Solution>| solutions.push(solution.clone()))
-            .or_insert_with(|| vec![solution.clone()]);
-    });
+            .entry(coordinates)
+            .or_default()
+            .push(solution.clone());
+    }
 
-    // Convert DashMap to Vec<HyperBox>
     hyperbox_map
         .into_iter()
         .map(|(coordinates, solutions)| HyperBox {
@@ -253,11 +295,21 @@ pub fn create(solutions: &[Solution], divisions: usize) -> Vec<HyperBox> {
         .collect()
 }
 
+/// Fingerprint of which boxes are occupied. Box counts are left out: the archive keeps
+/// copies of equal solutions, so counts drift while the front itself stays put.
+pub fn occupancy_hash(hyperboxes: &[HyperBox]) -> u64 {
+    let mut hasher = FxHasher::default();
+    for hyperbox in hyperboxes {
+        hyperbox.coordinates.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
 /// Selects a solution from a hyperbox based on the two-objective space
 pub fn select<'a>(hyperboxes: &'a [HyperBox], rng: &mut impl rand::Rng) -> &'a Solution {
-    // Compute total weight in parallel
+    // Summed sequentially: a parallel float sum is not reproducible
     let total_weight: f64 = hyperboxes
-        .par_iter()
+        .iter()
         .map(|hb| 1.0 / (hb.solutions.len() as f64))
         .sum();
 
@@ -281,3 +333,78 @@ pub fn select<'a>(hyperboxes: &'a [HyperBox], rng: &mut impl rand::Rng) -> &'a S
     let last_box = hyperboxes.last().unwrap();
     &last_box.solutions[rng.random_range(0..last_box.solutions.len())]
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::Rng;
+
+    fn solution(intra: f64, inter: f64) -> Solution {
+        Solution {
+            partition: Partition::new(),
+            objectives: vec![intra, inter],
+        }
+    }
+
+    fn coordinates_of(boxes: &[HyperBox], target: &Solution) -> Vec<usize> {
+        boxes
+            .iter()
+            .find(|hb| {
+                hb.solutions
+                    .iter()
+                    .any(|s| s.objectives == target.objectives)
+            })
+            .unwrap()
+            .coordinates
+            .clone()
+    }
+
+    #[test]
+    fn test_fixed_bounds_keep_box_assignments_stable() {
+        let mut rng = crate::operators::seeded_rng(11, 0, 0);
+        let tracked = solution(0.4, 0.3);
+        let mut archive = vec![tracked.clone()];
+        let bounds = (Some((0.0, 0.0)), Some((1.0, 1.0)));
+
+        let mut fixed = Vec::new();
+        let mut adaptive = Vec::new();
+        // Each "generation" widens the archive's own range with a new random solution
+        for _ in 0..10 {
+            archive.push(solution(rng.random::<f64>(), rng.random::<f64>()));
+            fixed.push(coordinates_of(
+                &create(&archive, 8, bounds.0, bounds.1),
+                &tracked,
+            ));
+            adaptive.push(coordinates_of(&create(&archive, 8, None, None), &tracked));
+        }
+
+        assert!(fixed.iter().all(|coords| *coords == vec![3, 2]));
+        assert!(adaptive.iter().any(|coords| *coords != adaptive[0]));
+    }
+
+    #[test]
+    fn test_rounded_objectives_tie_instead_of_dominating() {
+        use crate::operators::round_objectives;
+
+        // 0.375 is a box edge of an 8-division grid over [0, 1]
+        let a = solution(0.375 - 1e-13, 0.5);
+        let b = solution(0.375, 0.5);
+        let bounds = (Some((0.0, 0.0)), Some((1.0, 1.0)));
+        let boxes = create(&[a.clone(), b.clone()], 8, bounds.0, bounds.1);
+        assert!(a.dominates(&b));
+        assert_ne!(coordinates_of(&boxes, &a), coordinates_of(&boxes, &b));
+
+        let (mut a, mut b) = (a, b);
+        round_objectives(&mut a.objectives, Some(9));
+        round_objectives(&mut b.objectives, Some(9));
+        assert_eq!(a.objectives, b.objectives);
+        assert!(!a.dominates(&b) && !b.dominates(&a));
+        let boxes = create(&[a.clone(), b], 8, bounds.0, bounds.1);
+        assert_eq!(boxes.len(), 1);
+        assert_eq!(coordinates_of(&boxes, &a), vec![3, 4]);
+
+        let mut unrounded = vec![0.1 + 0.2, 0.7];
+        round_objectives(&mut unrounded, None);
+        assert_eq!(unrounded, [0.1 + 0.2, 0.7]);
+    }
+}
@@ -8,43 +8,218 @@ mod evolutionary;
 mod hypergrid;
 mod model_selection;
 
-use crate::graph::{Graph, Partition};
+use crate::graph::{CommunityId, Graph, NodeId, Partition};
+use crate::local;
+use crate::operators::{
+    EvolutionConfig, EvolutionResult, assign_node, consensus_partition, enforce_links, get_fitness,
+    get_modularity_from_partition, hypervolume, knee_point, merge_gains, refine_partition,
+    soft_memberships,
+};
+use crate::persist;
 use hypergrid::{HyperBox, Solution};
+use model_selection::NullModel;
 
 use pyo3::{pyclass, pymethods};
 
-use crate::utils::{build_graph, get_edges, normalize_community_ids};
+use crate::utils::{
+    EdgeFile, EdgeRules, LabeledEdges, LinkPairs, NodeLabels, ParetoIterator, add_edge_with_rules,
+    adjacency_array, adjacency_dict, build_graph, check_objective_bounds, check_overlap_threshold,
+    check_partition_nodes, check_rate, check_resolution, check_round_objectives, check_typed_ends,
+    community_count, community_sets, edge_list, edge_node_ids, enable_debug_logging,
+    from_partition, front_csv, get_edges, link_constraints, new_node_ids, node_id, node_labels,
+    normalize_community_ids, objective_dict, objectives_array, parse_crossover_type,
+    parse_init_strategy, parse_modularity_variant, parse_mutation_schedule, partition_records,
+    records_with_labels, sample_graph, set_bipartite, set_node_weights, to_partition,
+    warn_if_disconnected, with_generation_callback,
+};
 
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyAny;
+use pyo3::types::{PyAny, PyDict};
+use rayon::prelude::*;
+use rustc_hash::FxHashSet as HashSet;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+/// Share of runs that must group two nodes together for `consensus` to join them
+const CONSENSUS_THRESHOLD: f64 = 0.5;
 
 #[pyclass]
 pub struct MOCD {
     graph: Graph,
     debug_level: i8,
     rand_networks: usize,
-    pop_size: usize,
-    num_gens: usize,
-    cross_rate: f64,
-    mut_rate: f64,
+    config: EvolutionConfig,
+    archive: Option<Vec<Solution>>, // Cached by `fit`
+    history: Vec<f64>,              // Best Q per generation of the last `fit`
+    runtime_secs: f64,              // Wall-clock time of the last `fit`
+    labels: Option<NodeLabels>,     // Original node labels when they were not i32 ids
+    edge_rules: EdgeRules,          // Self-loop and parallel-edge handling for `add_edge`
+    on_generation: Option<Py<PyAny>>,
 }
 
 impl MOCD {
-    pub fn envolve(&self) -> Vec<Solution> {
+    pub fn envolve(&self) -> PyResult<EvolutionResult<Solution>> {
+        enable_debug_logging(self.debug_level);
         if self.debug_level >= 1 {
             self.graph.print();
         }
 
-        evolutionary::evolutionary_phase(
-            &self.graph,
-            self.debug_level,
-            self.num_gens,
-            self.pop_size,
-            self.cross_rate,
-            self.mut_rate,
-            &self.graph.precompute_degrees(),
+        with_generation_callback(
+            self.on_generation.as_ref(),
+            self.config.num_threads,
+            |on_generation| {
+                evolutionary::evolutionary_phase(
+                    &self.graph,
+                    self.debug_level,
+                    &self.config,
+                    &self.graph.precompute_degrees(),
+                    on_generation,
+                )
+            },
+        )
+    }
+
+    /// Normalized partition as a dict keyed by the original node labels
+    fn to_dict<'py>(&self, py: Python<'py>, partition: Partition) -> PyResult<Bound<'py, PyDict>> {
+        from_partition(
+            py,
+            &normalize_community_ids(partition),
+            self.labels.as_ref(),
         )
     }
+
+    /// Like `to_dict`, but lists every community of a node when `overlap_threshold`
+    /// is set (see `operators::soft_memberships`)
+    fn to_soft_dict<'py>(
+        &self,
+        py: Python<'py>,
+        partition: Partition,
+        overlap_threshold: Option<f64>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let Some(threshold) = overlap_threshold else {
+            return self.to_dict(py, partition);
+        };
+        let partition = normalize_community_ids(partition);
+        let memberships = soft_memberships(&self.graph, &partition, threshold);
+        from_partition(py, &memberships, self.labels.as_ref())
+    }
+
+    fn fitted_archive(&self) -> PyResult<&[Solution]> {
+        self.archive
+            .as_deref()
+            .ok_or_else(|| PyRuntimeError::new_err("MOCD is not fitted yet, call fit() first"))
+    }
+
+    /// Runs the evolutionary phase unless an archive is already cached, by `fit` or an
+    /// earlier call; `reset` drops the cache
+    fn ensure_fitted(&mut self) -> PyResult<()> {
+        if self.archive.is_none() {
+            self.refit()?;
+        }
+        Ok(())
+    }
+
+    /// Co-association consensus of the max-Q partitions of `runs` independent runs,
+    /// seeded `seed`, `seed + 1`, ... from the configured (or a drawn) base seed and
+    /// spread over the worker threads; per-generation callbacks are not called
+    fn consensus_partition(&self, runs: usize) -> PyResult<Partition> {
+        let base_seed = self.config.resolve_seed();
+        let partitions: Vec<Partition> =
+            with_generation_callback(None, self.config.num_threads, |_| {
+                (0..runs as u64)
+                    .into_par_iter()
+                    .map(|run| {
+                        let config = EvolutionConfig {
+                            seed: Some(base_seed.wrapping_add(run)),
+                            ..self.config.clone()
+                        };
+                        let archive = evolutionary::evolutionary_phase(
+                            &self.graph,
+                            self.debug_level,
+                            &config,
+                            &self.graph.precompute_degrees(),
+                            None,
+                        )
+                        .front;
+                        max_q_partition(&archive, &self.graph, &config).0
+                    })
+                    .collect()
+            })?;
+        // Must-linked nodes agree in every run, but components can chain cannot-linked ones
+        let mut consensus = consensus_partition(&partitions, CONSENSUS_THRESHOLD);
+        enforce_links(&mut consensus, &self.config);
+        Ok(consensus)
+    }
+
+    /// Runs the evolutionary phase and caches its front, history and runtime
+    fn refit(&mut self) -> PyResult<()> {
+        let start = Instant::now();
+        let result = self.envolve()?;
+        self.runtime_secs = start.elapsed().as_secs_f64();
+        self.archive = Some(result.front);
+        self.history = result.history;
+        Ok(())
+    }
+}
+
+/// Max-Q partition of a fitted `archive` with its Q, polished by local moves first when
+/// `config.refine` is set (the moves keep the community count and size limits, and the
+/// Q is rescored on `graph` after they are repaired against the configured must-link
+/// and cannot-link pairs)
+fn max_q_partition(
+    archive: &[Solution],
+    graph: &Graph,
+    config: &EvolutionConfig,
+) -> (Partition, f64) {
+    let best = model_selection::max_q_selection(archive);
+    let mut partition = best.partition.clone();
+    if !config.refine {
+        return (partition, 1.0 - best.objectives[0] - best.objectives[1]);
+    }
+
+    refine_partition(&mut partition, graph, config);
+    enforce_links(&mut partition, config);
+    let metrics = get_fitness(
+        graph,
+        &partition,
+        &graph.precompute_degrees(),
+        false,
+        config.resolution,
+        config.modularity_variant,
+    );
+    (partition, 1.0 - metrics.intra - metrics.inter)
+}
+
+/// Runs `max_q` on every model, spreading the models over `num_threads` workers.
+/// Per-generation callbacks are not called. Results keep the order of `models`.
+pub fn batch_max_q<'py>(
+    py: Python<'py>,
+    models: &[&MOCD],
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let partitions: Vec<Partition> = with_generation_callback(None, num_threads, |_| {
+        models
+            .par_iter()
+            .map(|model| {
+                let archive = evolutionary::evolutionary_phase(
+                    &model.graph,
+                    model.debug_level,
+                    &model.config,
+                    &model.graph.precompute_degrees(),
+                    None,
+                )
+                .front;
+                max_q_partition(&archive, &model.graph, &model.config).0
+            })
+            .collect()
+    })?;
+
+    models
+        .iter()
+        .zip(partitions)
+        .map(|(model, partition)| model.to_dict(py, partition))
+        .collect()
 }
 
 #[pymethods]
@@ -56,8 +231,42 @@ impl MOCD {
         pop_size = 100,
         num_gens = 100,
         cross_rate = 0.8,
-        mut_rate = 0.2
+        mut_rate = 0.2,
+        seed = None,
+        on_generation = None,
+        patience = Some(100),
+        tol = 1e-6,
+        drop_self_loops = true,
+        num_threads = None,
+        num_communities = None,
+        directed = false,
+        time_limit_secs = None,
+        grid_divisions = 8,
+        initial_partition = None,
+        resolution = 1.0,
+        macro_mutation_rate = 0.0,
+        archive_size = 100,
+        edge_sample_rate = None,
+        archive_patience = None,
+        modularity_variant = "newman",
+        max_community_size = None,
+        mut_rate_schedule = "constant",
+        mut_rate_min = 0.01,
+        parallel_as_weight = false,
+        crossover_type = None,
+        node_weights = None,
+        elitism = true,
+        refine = false,
+        obj_min = None,
+        obj_max = None,
+        init_strategy = "random",
+        bipartite = false,
+        node_types = None,
+        must_link = None,
+        cannot_link = None,
+        round_objectives = None
     ))]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         graph: &Bound<'_, PyAny>,
         debug_level: i8,
@@ -66,65 +275,888 @@ impl MOCD {
         num_gens: usize,
         cross_rate: f64,
         mut_rate: f64,
+        seed: Option<u64>,
+        on_generation: Option<Py<PyAny>>,
+        patience: Option<usize>,
+        tol: f64,
+        drop_self_loops: bool,
+        num_threads: Option<usize>,
+        num_communities: Option<usize>,
+        directed: bool,
+        time_limit_secs: Option<f64>,
+        grid_divisions: usize,
+        initial_partition: Option<&Bound<'_, PyDict>>,
+        resolution: f64,
+        macro_mutation_rate: f64,
+        archive_size: usize,
+        edge_sample_rate: Option<f64>,
+        archive_patience: Option<usize>,
+        modularity_variant: &str,
+        max_community_size: Option<usize>,
+        mut_rate_schedule: &str,
+        mut_rate_min: f64,
+        parallel_as_weight: bool,
+        crossover_type: Option<&str>,
+        node_weights: Option<&Bound<'_, PyDict>>,
+        elitism: bool,
+        refine: bool,
+        obj_min: Option<(f64, f64)>,
+        obj_max: Option<(f64, f64)>,
+        init_strategy: &str,
+        bipartite: bool,
+        node_types: Option<&Bound<'_, PyDict>>,
+        must_link: Option<LinkPairs<'_>>,
+        cannot_link: Option<LinkPairs<'_>>,
+        round_objectives: Option<u32>,
     ) -> PyResult<Self> {
-        let edges = get_edges(graph)?;
-        let graph = build_graph(edges);
+        check_resolution(resolution)?;
+        let modularity_variant = parse_modularity_variant(modularity_variant)?;
+        let crossover_type = parse_crossover_type(crossover_type)?;
+        let init_strategy = parse_init_strategy(init_strategy)?;
+        let mut_rate_schedule = parse_mutation_schedule(mut_rate_schedule, mut_rate, mut_rate_min)?;
+        // Oversized communities are split after every mutation; a very small cap leaves
+        // little room to improve Q and can keep the run from converging
+        if max_community_size == Some(0) {
+            return Err(PyValueError::new_err(
+                "max_community_size must be at least 1",
+            ));
+        }
+        check_rate("macro_mutation_rate", macro_mutation_rate)?;
+        // Rounded objectives tie instead of dominating on differences below the precision
+        check_round_objectives(round_objectives)?;
+        if grid_divisions == 0 {
+            return Err(PyValueError::new_err("grid_divisions must be at least 1"));
+        }
+        // The archive is the front `front()` returns; 100 solutions by default, pruned
+        // by hypergrid crowding independently of `pop_size`
+        if archive_size == 0 {
+            return Err(PyValueError::new_err("archive_size must be at least 1"));
+        }
+        check_objective_bounds(obj_min, obj_max)?;
+        let py = graph.py();
+        let (edges, labels) = get_edges(graph)?;
+        let (graph, self_loops) =
+            build_graph(edges, drop_self_loops, false, parallel_as_weight, directed);
+        let mut graph = sample_graph(graph, edge_sample_rate, seed)?;
+        if let Some(node_weights) = node_weights {
+            set_node_weights(&mut graph, node_weights, labels.as_ref())?;
+        }
+        let modularity_variant = set_bipartite(
+            &mut graph,
+            bipartite,
+            node_types,
+            labels.as_ref(),
+            modularity_variant,
+        )?;
+        enable_debug_logging(debug_level);
+        if debug_level >= 1 && self_loops > 0 {
+            log::info!("[MOCD::new]: Dropped {} self-loops", self_loops);
+        }
+        if debug_level >= 1 {
+            warn_if_disconnected(py, &graph, "MOCD")?;
+        }
+        let initial_partition = match initial_partition {
+            Some(partition) => {
+                let partition = to_partition(partition, labels.as_ref())?;
+                check_partition_nodes(&graph, &partition)?;
+                Some(partition)
+            }
+            None => None,
+        };
+        // Repaired into every partition after mutation and crossover: must-linked nodes
+        // are merged into one community, cannot-linked ones split apart
+        let (must_link, cannot_link) =
+            link_constraints(py, &graph, labels.as_ref(), must_link, cannot_link)?;
+
+        Ok(MOCD {
+            graph,
+            debug_level,
+            rand_networks,
+            config: EvolutionConfig {
+                num_gens,
+                pop_size,
+                cross_rate,
+                mut_rate,
+                seed,
+                patience,
+                tol,
+                num_threads,
+                num_communities,
+                time_limit_secs,
+                grid_divisions,
+                archive_size,
+                archive_patience,
+                resolution,
+                macro_mutation_rate,
+                elitism,
+                refine,
+                max_community_size,
+                modularity_variant,
+                crossover_type,
+                mut_rate_schedule,
+                mut_rate_min,
+                obj_min,
+                obj_max,
+                init_strategy,
+                initial_partition,
+                must_link,
+                cannot_link,
+                round_objectives,
+                ..Default::default()
+            },
+            archive: None,
+            history: Vec::new(),
+            runtime_secs: 0.0,
+            labels,
+            edge_rules: EdgeRules {
+                drop_self_loops,
+                parallel_as_weight,
+            },
+            on_generation,
+        })
+    }
+
+    /// Saves the parsed graph, settings and fitted front (if any) to `path`.
+    /// The `on_generation` callback is not saved.
+    #[pyo3(signature = (path))]
+    pub fn save(&self, py: Python<'_>, path: &str) -> PyResult<()> {
+        let labels = match &self.labels {
+            Some(labels) => Some(labels.to_bytes(py)?),
+            None => None,
+        };
+        let state = (
+            &self.graph,
+            self.debug_level,
+            self.rand_networks,
+            &self.config,
+            &self.archive,
+            &self.history,
+            labels,
+            self.edge_rules,
+        );
+        Ok(persist::save(path, "MOCD", &state)?)
+    }
 
+    /// Loads an estimator written by `save`; it comes back fitted if it was saved fitted.
+    #[staticmethod]
+    #[pyo3(signature = (path))]
+    pub fn load(py: Python<'_>, path: &str) -> PyResult<Self> {
+        let (graph, debug_level, rand_networks, config, archive, history, labels, edge_rules): (
+            _,
+            _,
+            _,
+            _,
+            _,
+            _,
+            Option<Vec<u8>>,
+            _,
+        ) = persist::load(path, "MOCD")?;
+        let labels = match labels {
+            Some(bytes) => Some(NodeLabels::from_bytes(py, &bytes)?),
+            None => None,
+        };
         Ok(MOCD {
             graph,
             debug_level,
             rand_networks,
-            pop_size,
-            num_gens,
-            cross_rate,
-            mut_rate,
+            config,
+            archive,
+            history,
+            runtime_secs: 0.0,
+            labels,
+            edge_rules,
+            on_generation: None,
         })
     }
 
+    /// Builds the estimator from an edge list file read directly in Rust, one `u v` or
+    /// `u v weight` line of integer node ids per edge. `delimiter` defaults to any
+    /// whitespace; blank and `#` comment lines are skipped. Other keyword arguments go
+    /// to the constructor. Raises ValueError naming the line of a malformed entry.
+    #[staticmethod]
+    #[pyo3(signature = (path, delimiter = None, **kwargs))]
+    pub fn from_file<'py>(
+        py: Python<'py>,
+        path: &str,
+        delimiter: Option<&str>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, Self>> {
+        let edges = Bound::new(py, EdgeFile::read(path, delimiter)?)?;
+        Ok(py
+            .get_type::<Self>()
+            .call((edges,), kwargs)?
+            .downcast_into()?)
+    }
+
+    /// Builds the estimator from a GraphML file read directly in Rust. Node ids become
+    /// string labels; edges read their `source`/`target` attributes and a numeric
+    /// `weight` data key when declared, other attributes are ignored. Other keyword
+    /// arguments go to the constructor. Raises ValueError naming a malformed element.
+    #[staticmethod]
+    #[pyo3(signature = (path, **kwargs))]
+    pub fn from_graphml<'py>(
+        py: Python<'py>,
+        path: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, Self>> {
+        let edges = Bound::new(py, EdgeFile::read_graphml(path)?)?;
+        Ok(py
+            .get_type::<Self>()
+            .call((edges,), kwargs)?
+            .downcast_into()?)
+    }
+
+    /// Number of distinct nodes in the parsed graph.
+    #[getter]
+    pub fn num_nodes(&self) -> usize {
+        self.graph.num_nodes()
+    }
+
+    /// Number of edges in the parsed graph, after dropping parallel edges.
+    #[getter]
+    pub fn num_edges(&self) -> usize {
+        self.graph.num_edges()
+    }
+
+    /// Number of connected components in the parsed graph (weak components when directed).
+    #[getter]
+    pub fn num_components(&self) -> usize {
+        self.graph.connected_components().len()
+    }
+
+    /// Node degrees ordered by node id; a self-loop counts twice.
+    #[pyo3(signature = ())]
+    pub fn degree_sequence(&self) -> Vec<usize> {
+        self.graph.degree_sequence()
+    }
+
+    /// Fraction of the possible node pairs that are linked, 2E / (N(N-1)), or
+    /// E / (N(N-1)) when directed; 0.0 below two nodes.
+    #[pyo3(signature = ())]
+    pub fn density(&self) -> f64 {
+        self.graph.density()
+    }
+
+    /// Pearson correlation of the degrees at either end of each edge: negative when
+    /// hubs link to low-degree nodes, NaN when every edge joins equal degrees (e.g.
+    /// a regular graph) or the graph has no edges.
+    #[pyo3(signature = ())]
+    pub fn degree_assortativity(&self) -> f64 {
+        self.graph.degree_assortativity()
+    }
+
+    /// Number of triangles in the parsed graph, ignoring direction and self-loops.
+    #[pyo3(signature = ())]
+    pub fn triangle_count(&self) -> usize {
+        self.graph.triangle_count()
+    }
+
+    /// Mean local clustering coefficient over all nodes, nodes with fewer than two
+    /// neighbours counting as 0.0 (as in NetworkX); ignores direction and self-loops.
+    #[pyo3(signature = ())]
+    pub fn average_clustering(&self) -> f64 {
+        self.graph.average_clustering()
+    }
+
+    /// Core number of every node, keyed by the original labels: the largest k such
+    /// that the node stays in the k-core (every node of degree >= k). Keep the nodes
+    /// with core number >= k to get the k-core before building another estimator.
+    #[pyo3(signature = ())]
+    pub fn core_numbers<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let cores: BTreeMap<NodeId, usize> = self.graph.core_numbers().into_iter().collect();
+        from_partition(py, &cores, self.labels.as_ref())
+    }
+
+    /// Leading eigenvector of the modularity matrix B = A - k kᵀ / 2m, keyed by the
+    /// original labels, from `iters` rounds of power iteration that never build B. Its
+    /// signs give the spectral two-way split; entries are unit-norm, the largest positive.
+    #[pyo3(signature = (iters = 1000))]
+    pub fn leading_eigenvector<'py>(
+        &self,
+        py: Python<'py>,
+        iters: usize,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let vector: BTreeMap<NodeId, f64> =
+            self.graph.leading_eigenvector(iters).into_iter().collect();
+        from_partition(py, &vector, self.labels.as_ref())
+    }
+
+    /// Node ids in ascending order (the original labels, in order of first appearance,
+    /// when they were remapped); row and column i of `to_adjacency()` is node i here.
+    #[pyo3(signature = ())]
+    pub fn node_order<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        node_labels(py, &self.graph.sorted_nodes(), self.labels.as_ref())
+    }
+
+    /// Returns the parsed graph, after self-loop and parallel-edge handling, as a
+    /// dense (N, N) int32 NumPy adjacency matrix ordered by `node_order()`. Symmetric
+    /// unless the graph is directed; weights are not included. This materializes all
+    /// N * N entries, so it is meant for small graphs. Requires NumPy.
+    #[pyo3(signature = ())]
+    pub fn to_adjacency(&self, py: Python<'_>) -> PyResult<PyObject> {
+        adjacency_array(py, &self.graph)
+    }
+
+    /// Returns a copy of the parsed adjacency list as `dict[node, list[neighbor]]`, after
+    /// self-loop and parallel-edge handling: a kept self-loop lists its node twice, and
+    /// directed graphs list neighbours in either direction. Changing it does not touch
+    /// the estimator.
+    #[pyo3(signature = ())]
+    pub fn adjacency<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        adjacency_dict(py, &self.graph, self.labels.as_ref())
+    }
+
+    /// Runs the evolutionary phase once and caches the resulting archive.
+    /// Calling it again replaces the cached archive.
+    #[pyo3(signature = ())]
+    pub fn fit(mut slf: PyRefMut<'_, Self>) -> PyResult<PyRefMut<'_, Self>> {
+        slf.refit()?;
+        Ok(slf)
+    }
+
+    /// Drops the cached archive, history and runtime, so the next call runs the evolutionary
+    /// phase again.
+    #[pyo3(signature = ())]
+    pub fn reset(&mut self) {
+        self.archive = None;
+        self.history.clear();
+        self.runtime_secs = 0.0;
+    }
+
+    /// Wall-clock seconds the cached run took, callbacks included. 0.0 before the
+    /// first run and after `load`, which restores the front but not its timing.
+    #[pyo3(signature = ())]
+    pub fn last_runtime_secs(&self) -> f64 {
+        self.runtime_secs
+    }
+
+    /// Generations the cached run completed, at most `num_gens` and fewer when
+    /// `patience`, `time_limit_secs` or `on_generation` stopped it early. 0 before the
+    /// first run.
+    #[pyo3(signature = ())]
+    pub fn last_generations(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Best Q recorded at each generation of the cached run, one entry per generation
+    /// that ran. Empty until the first run.
+    #[pyo3(signature = ())]
+    pub fn history(&self) -> Vec<f64> {
+        self.history.clone()
+    }
+
+    /// Returns the max-Q partition of the fitted archive, as `max_q` does.
+    #[pyo3(signature = ())]
+    pub fn predict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let (partition, _) = max_q_partition(self.fitted_archive()?, &self.graph, &self.config);
+
+        self.to_dict(py, partition)
+    }
+
+    /// Returns every solution of the fitted archive with its objectives.
+    #[pyo3(signature = ())]
+    pub fn front<'py>(&self, py: Python<'py>) -> PyResult<Vec<(Bound<'py, PyDict>, Vec<f64>)>> {
+        self.fitted_archive()?
+            .iter()
+            .map(|sol| {
+                Ok((
+                    self.to_dict(py, sol.partition.clone())?,
+                    sol.objectives.clone(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Returns the fitted archive objectives as an (N, 2) NumPy array of [intra, inter],
+    /// row i matching the i-th solution of `front()`. Requires NumPy.
     #[pyo3(signature = ())]
-    pub fn generate_pareto_front(&self) -> PyResult<Vec<(Partition, Vec<f64>)>> {
-        let first_front = self.envolve();
+    pub fn pareto_objectives(&self, py: Python<'_>) -> PyResult<PyObject> {
+        objectives_array(py, self.fitted_archive()?.iter().map(|sol| &sol.objectives))
+    }
 
-        Ok(first_front
+    /// Edges of the subgraph induced by community `community_id` of `partition`, as
+    /// `(u, v, weight)` tuples that any estimator accepts, for re-running detection
+    /// inside one community. Nodes without an edge in the community are left out.
+    /// Raises ValueError unless the partition assigns exactly the graph's nodes and
+    /// holds `community_id`.
+    #[pyo3(signature = (partition, community_id))]
+    pub fn community_subgraph<'py>(
+        &self,
+        py: Python<'py>,
+        partition: &Bound<'py, PyDict>,
+        community_id: CommunityId,
+    ) -> PyResult<LabeledEdges<'py>> {
+        let partition = to_partition(partition, self.labels.as_ref())?;
+        check_partition_nodes(&self.graph, &partition)?;
+
+        let nodes: HashSet<NodeId> = partition
+            .iter()
+            .filter(|&(_, &community)| community == community_id)
+            .map(|(&node, _)| node)
+            .collect();
+        if nodes.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "community {} is not in the partition",
+                community_id
+            )));
+        }
+        edge_list(py, &self.graph.subgraph(&nodes), self.labels.as_ref())
+    }
+
+    /// Community of the max-Q partition (ids as in `max_q`) that a new `node` with
+    /// edges to `neighbors` would join: the one whose modularity gain is largest, or a
+    /// fresh id one past the last when joining no neighbor's community gains. A cheap
+    /// one-node prediction, not a re-run; the cached front and the graph stay as they
+    /// are. Directed graphs read the edges as pointing from `node`. Raises ValueError
+    /// if `node` is already in the graph or a neighbor is not.
+    #[pyo3(signature = (node, neighbors))]
+    pub fn assign_node(
+        &mut self,
+        node: &Bound<'_, PyAny>,
+        neighbors: Vec<Bound<'_, PyAny>>,
+    ) -> PyResult<CommunityId> {
+        let (node, neighbors) = new_node_ids(&self.graph, self.labels.as_ref(), node, &neighbors)?;
+        self.ensure_fitted()?;
+        let (partition, _) = max_q_partition(self.fitted_archive()?, &self.graph, &self.config);
+
+        Ok(assign_node(
+            &self.graph,
+            &normalize_community_ids(partition),
+            node,
+            &neighbors,
+            self.config.resolution,
+        ))
+    }
+
+    /// Adds the edge `u`-`v` to the graph, nodes included, and drops the cached front
+    /// so the next `max_q` re-optimizes the updated graph. A new node starts in a
+    /// community of its own in `initial_partition`, if one was given. The edge follows
+    /// the constructor's rules: a self-loop only adds its node with `drop_self_loops`,
+    /// and an edge already in the graph gains a unit of weight with
+    /// `parallel_as_weight` and is left as is otherwise. With `bipartite=True` both
+    /// nodes must be in `node_types`, else ValueError is raised.
+    #[pyo3(signature = (u, v))]
+    pub fn add_edge(&mut self, u: &Bound<'_, PyAny>, v: &Bound<'_, PyAny>) -> PyResult<()> {
+        check_typed_ends(&self.graph, self.labels.as_ref(), u, v)?;
+        let (u, v) = edge_node_ids(self.labels.as_mut(), u, v, true)?;
+        let new_nodes: Vec<NodeId> = [u, v]
             .into_iter()
-            .map(|ind| (normalize_community_ids(ind.partition), ind.objectives))
-            .collect())
+            .filter(|node| !self.graph.nodes.contains(node))
+            .collect();
+        if !add_edge_with_rules(&mut self.graph, u, v, 1.0, self.edge_rules) {
+            return Ok(());
+        }
+        for node in new_nodes {
+            self.config.extend_initial_partition(node);
+        }
+        self.reset();
+        Ok(())
+    }
+
+    /// Removes the edge `u`-`v` and drops the cached front, like `add_edge`. Nodes
+    /// left without edges stay in the graph as isolated nodes. Raises ValueError if
+    /// there is no such edge.
+    #[pyo3(signature = (u, v))]
+    pub fn remove_edge(&mut self, u: &Bound<'_, PyAny>, v: &Bound<'_, PyAny>) -> PyResult<()> {
+        let (from, to) = edge_node_ids(self.labels.as_mut(), u, v, false)?;
+        if !self.graph.remove_edge(from, to) {
+            return Err(PyValueError::new_err(format!(
+                "edge ({}, {}) is not in the graph",
+                u.repr()?,
+                v.repr()?
+            )));
+        }
+        self.reset();
+        Ok(())
+    }
+
+    /// Community grown around `seed` alone, without partitioning the graph or running
+    /// the evolutionary phase: neighbouring nodes join one at a time while they raise
+    /// the local modularity R (the share of the boundary's edge weight that stays
+    /// inside), up to `max_size` nodes. Nodes come in the order they joined, `seed`
+    /// first. Raises ValueError if `seed` is not in the graph or `max_size` is 0.
+    #[pyo3(signature = (seed, max_size))]
+    pub fn local_community<'py>(
+        &self,
+        py: Python<'py>,
+        seed: &Bound<'py, PyAny>,
+        max_size: usize,
+    ) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        if max_size == 0 {
+            return Err(PyValueError::new_err("max_size must be at least 1"));
+        }
+        let seed = node_id(&self.graph, self.labels.as_ref(), seed)?;
+        let community = local::local_community(&self.graph, seed, max_size);
+        node_labels(py, &community, self.labels.as_ref())
+    }
+
+    /// Sets a `community` node attribute on the NetworkX `graph` from the max-Q
+    /// partition (ids as in `max_q`), through `networkx.set_node_attributes`. Nodes the
+    /// estimator was not built with are left without the attribute. Uses the cached
+    /// front, running the evolutionary phase if needed. Requires NetworkX.
+    #[pyo3(signature = (graph))]
+    pub fn annotate(&mut self, graph: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.ensure_fitted()?;
+        let (partition, _) = max_q_partition(self.fitted_archive()?, &self.graph, &self.config);
+        let partition = self.to_dict(graph.py(), partition)?;
+
+        graph
+            .py()
+            .import("networkx")?
+            .call_method1("set_node_attributes", (graph, partition, "community"))?;
+        Ok(())
+    }
+
+    /// The max-Q partition as one list of nodes per community, ordered by community id
+    /// as in `max_q`. Uses the cached front, running the evolutionary phase if needed.
+    #[pyo3(signature = ())]
+    pub fn community_sets<'py>(
+        &mut self,
+        py: Python<'py>,
+    ) -> PyResult<Vec<Vec<Bound<'py, PyAny>>>> {
+        self.ensure_fitted()?;
+        let (partition, _) = max_q_partition(self.fitted_archive()?, &self.graph, &self.config);
+
+        community_sets(partition)
+            .iter()
+            .map(|nodes| node_labels(py, nodes, self.labels.as_ref()))
+            .collect()
+    }
+
+    /// The max-Q partition as `(node, community)` pairs ordered by node id (ids as in
+    /// `max_q`), ready for `pandas.DataFrame(records, columns=["node", "community"])`.
+    /// Uses the cached front, running the evolutionary phase if needed.
+    #[pyo3(signature = ())]
+    pub fn partition_records<'py>(
+        &mut self,
+        py: Python<'py>,
+    ) -> PyResult<Vec<(Bound<'py, PyAny>, CommunityId)>> {
+        self.ensure_fitted()?;
+        let (partition, _) = max_q_partition(self.fitted_archive()?, &self.graph, &self.config);
+
+        records_with_labels(py, partition_records(partition), self.labels.as_ref())
+    }
+
+    /// Area of objective space dominated by the front's [intra, inter] vectors and
+    /// bounded by `reference`, for comparing runs; points outside the reference box
+    /// add nothing. Uses the cached front, running the evolutionary phase if needed.
+    #[pyo3(signature = (reference))]
+    pub fn hypervolume(&mut self, reference: (f64, f64)) -> PyResult<f64> {
+        self.ensure_fitted()?;
+        let archive = self.fitted_archive()?;
+        Ok(hypervolume(
+            archive.iter().map(|sol| &sol.objectives),
+            reference,
+        ))
+    }
+
+    /// Returns the objective vector [intra, inter] of a user-supplied partition on
+    /// the stored graph, comparable with the objectives of `front()`.
+    /// Raises ValueError unless the partition assigns exactly the graph's nodes.
+    #[pyo3(signature = (partition))]
+    pub fn score_partition(&self, partition: &Bound<'_, PyDict>) -> PyResult<Vec<f64>> {
+        let partition = to_partition(partition, self.labels.as_ref())?;
+        check_partition_nodes(&self.graph, &partition)?;
+
+        let metrics = get_fitness(
+            &self.graph,
+            &partition,
+            &self.graph.precompute_degrees(),
+            true,
+            self.config.resolution,
+            self.config.modularity_variant,
+        );
+        Ok(vec![metrics.intra, metrics.inter])
+    }
+
+    /// Modularity change from merging each pair of communities of `partition` joined by
+    /// an edge, as `((a, b), gain)` with `a < b` in ascending order: the candidate moves
+    /// of greedy agglomeration (CNM). Uses the configured resolution and the Newman null
+    /// model, ignoring node weights. Raises ValueError unless the partition assigns
+    /// exactly the graph's nodes.
+    #[pyo3(signature = (partition))]
+    pub fn merge_gains(
+        &self,
+        partition: &Bound<'_, PyDict>,
+    ) -> PyResult<Vec<((CommunityId, CommunityId), f64)>> {
+        let partition = to_partition(partition, self.labels.as_ref())?;
+        check_partition_nodes(&self.graph, &partition)?;
+        Ok(merge_gains(&self.graph, &partition, self.config.resolution))
+    }
+
+    /// Runs the evolutionary phase and returns every front solution with its objectives.
+    /// With `overlap_threshold`, each node maps to a list of communities instead: its own
+    /// first, then, in ascending order, every other community holding strictly more than
+    /// that fraction of its incident edges.
+    #[pyo3(signature = (overlap_threshold = None))]
+    pub fn generate_pareto_front<'py>(
+        &mut self,
+        py: Python<'py>,
+        overlap_threshold: Option<f64>,
+    ) -> PyResult<Vec<(Bound<'py, PyDict>, Vec<f64>)>> {
+        check_overlap_threshold(overlap_threshold)?;
+        self.ensure_fitted()?;
+
+        self.fitted_archive()?
+            .iter()
+            .map(|ind| {
+                let partition = self.to_soft_dict(py, ind.partition.clone(), overlap_threshold)?;
+                Ok((partition, ind.objectives.clone()))
+            })
+            .collect()
     }
 
+    /// Like `generate_pareto_front`, but returns a `ParetoIterator` that builds one
+    /// `(partition, objectives)` pair at a time, for fronts too large to hold as dicts.
     #[pyo3(signature = ())]
-    pub fn max_q(&self) -> PyResult<Partition> {
-        let archive = self.envolve();
-        let best_solution = model_selection::max_q_selection(&archive);
+    pub fn iter_front(&mut self, py: Python<'_>) -> PyResult<ParetoIterator> {
+        self.ensure_fitted()?;
+        let front = self
+            .fitted_archive()?
+            .iter()
+            .map(|ind| (ind.partition.clone(), ind.objectives.clone()))
+            .collect();
+        let labels = match &self.labels {
+            Some(labels) => Some(labels.copy(py)?),
+            None => None,
+        };
 
-        Ok(normalize_community_ids(best_solution.partition.clone()))
+        Ok(ParetoIterator::new(front, labels))
     }
 
-    pub fn min_max(&self) -> PyResult<Partition> {
-        let archive = self.envolve();
+    /// Like `generate_pareto_front`, but with each objective vector as a dict
+    /// `{"intra": x, "inter": y, "modularity": 1 - x - y}`.
+    #[pyo3(signature = (overlap_threshold = None))]
+    pub fn labeled_pareto_front<'py>(
+        &mut self,
+        py: Python<'py>,
+        overlap_threshold: Option<f64>,
+    ) -> PyResult<Vec<(Bound<'py, PyDict>, Bound<'py, PyDict>)>> {
+        check_overlap_threshold(overlap_threshold)?;
+        self.ensure_fitted()?;
+
+        self.fitted_archive()?
+            .iter()
+            .map(|ind| {
+                let partition = self.to_soft_dict(py, ind.partition.clone(), overlap_threshold)?;
+                Ok((partition, objective_dict(py, &ind.objectives)?))
+            })
+            .collect()
+    }
 
-        let best_solution = {
-            let random_networks =
-                model_selection::generate_random_networks(&self.graph, self.rand_networks);
+    /// Modularity Q of every front solution at the configured resolution, in the order
+    /// of `generate_pareto_front`, scored on the stored graph instead of rebuilding it
+    /// per `fitness` call. Always Newman's Q, whatever `modularity_variant`.
+    #[pyo3(signature = ())]
+    pub fn front_modularities(&mut self) -> PyResult<Vec<f64>> {
+        self.ensure_fitted()?;
+        Ok(self
+            .fitted_archive()?
+            .iter()
+            .map(|ind| {
+                get_modularity_from_partition(&ind.partition, &self.graph, self.config.resolution)
+            })
+            .collect())
+    }
 
-            let random_archives: Vec<Vec<Solution>> = random_networks
+    /// The front as CSV text, an `intra,inter,modularity,num_communities` header and
+    /// one row per solution in the order of `front()`, ready to write to a file.
+    /// Uses the cached front, running the evolutionary phase if needed.
+    #[pyo3(signature = ())]
+    pub fn front_to_csv(&mut self) -> PyResult<String> {
+        self.ensure_fitted()?;
+        Ok(front_csv(
+            self.fitted_archive()?
                 .iter()
-                .map(|random_graph| {
-                    let random_degrees = random_graph.precompute_degrees();
-
-                    evolutionary::evolutionary_phase(
-                        random_graph,
-                        self.debug_level,
-                        self.num_gens / 2,
-                        self.pop_size / 3,
-                        self.cross_rate,
-                        self.mut_rate,
-                        &random_degrees,
-                    )
-                })
-                .collect();
-            model_selection::min_max_selection(&archive, &random_archives)
+                .map(|ind| (&ind.partition, ind.objectives.as_slice())),
+        ))
+    }
+
+    /// Number of distinct communities in every front solution, in the order of
+    /// `generate_pareto_front`, showing the range of resolutions the front spans.
+    #[pyo3(signature = ())]
+    pub fn num_communities_distribution(&mut self) -> PyResult<Vec<usize>> {
+        self.ensure_fitted()?;
+        Ok(self
+            .fitted_archive()?
+            .iter()
+            .map(|ind| community_count(&ind.partition))
+            .collect())
+    }
+
+    /// Consensus over `runs` seeded runs: nodes go together when the max-Q partitions of
+    /// more than half the runs agree, chained through shared nodes. One run returns
+    /// that run's `max_q`. Leaves the fitted front untouched.
+    #[pyo3(signature = (runs))]
+    pub fn consensus<'py>(&self, py: Python<'py>, runs: usize) -> PyResult<Bound<'py, PyDict>> {
+        if runs == 0 {
+            return Err(PyValueError::new_err("runs must be at least 1"));
+        }
+        let partition = self.consensus_partition(runs)?;
+        self.to_dict(py, partition)
+    }
+
+    /// The front solution with the highest Q; with `refine=True`, polished by greedy
+    /// local node moves until no move raises Q.
+    #[pyo3(signature = ())]
+    pub fn max_q<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.ensure_fitted()?;
+        let (partition, _) = max_q_partition(self.fitted_archive()?, &self.graph, &self.config);
+
+        self.to_dict(py, partition)
+    }
+
+    /// Perturb-and-restart out of a local optimum: reassigns `kicks` random nodes of the
+    /// max-Q partition to random communities, reruns the evolutionary phase warm-started
+    /// from the result and returns `max_q` of the better of the two fronts. The new
+    /// front, history and runtime replace the cached ones only when Q improved.
+    /// `on_generation` is called as in `fit`; with a fixed `seed` the kicks repeat.
+    #[pyo3(signature = (kicks))]
+    pub fn warm_restart<'py>(
+        &mut self,
+        py: Python<'py>,
+        kicks: usize,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        self.ensure_fitted()?;
+        let start = Instant::now();
+        let archive = self.fitted_archive()?;
+        let restarted = with_generation_callback(
+            self.on_generation.as_ref(),
+            self.config.num_threads,
+            |on_generation| {
+                evolutionary::warm_restart(
+                    &self.graph,
+                    self.debug_level,
+                    &self.config,
+                    archive,
+                    kicks,
+                    &self.graph.precompute_degrees(),
+                    on_generation,
+                )
+            },
+        )?;
+        if let Some(result) = restarted {
+            self.runtime_secs = start.elapsed().as_secs_f64();
+            self.archive = Some(result.front);
+            self.history = result.history;
+        }
+        self.max_q(py)
+    }
+
+    /// Like `max_q`, but also returns the chosen partition's Q = 1 - intra - inter,
+    /// read from the same front (rescored after refinement).
+    #[pyo3(signature = ())]
+    pub fn best<'py>(&mut self, py: Python<'py>) -> PyResult<(Bound<'py, PyDict>, f64)> {
+        self.ensure_fitted()?;
+        let (partition, q) = max_q_partition(self.fitted_archive()?, &self.graph, &self.config);
+
+        Ok((self.to_dict(py, partition)?, q))
+    }
+
+    /// Picks the knee of the front, the solution farthest from the chord joining its
+    /// two extremes, with its objectives. Fronts of fewer than three solutions fall back
+    /// to the max-Q solution.
+    #[pyo3(signature = ())]
+    pub fn knee_point<'py>(&mut self, py: Python<'py>) -> PyResult<(Bound<'py, PyDict>, Vec<f64>)> {
+        self.ensure_fitted()?;
+        let archive = self.fitted_archive()?;
+        let knee = match knee_point(archive.iter().map(|sol| &sol.objectives)) {
+            Some(index) => &archive[index],
+            None => model_selection::max_q_selection(archive),
+        };
+
+        Ok((
+            self.to_dict(py, knee.partition.clone())?,
+            knee.objectives.clone(),
+        ))
+    }
+
+    /// Picks the front solution farthest from the fronts of `rand_networks` random graphs.
+    /// `null_model` is `"erdos_renyi"` (same edge count, the default) or `"configuration"`
+    /// (same degree sequence); weighted graphs keep their weights, shuffled over the
+    /// random edges. With a `seed`, repeated calls pick the same solution. The null runs
+    /// report to `on_generation` (generations restart at 0 for each random graph) and,
+    /// with `debug_level >= 1`, log which random graph is running.
+    #[pyo3(signature = (null_model = "erdos_renyi"))]
+    pub fn min_max<'py>(
+        &mut self,
+        py: Python<'py>,
+        null_model: &str,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let null_model = NullModel::parse(null_model).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "null_model must be 'configuration' or 'erdos_renyi', got '{}'",
+                null_model
+            ))
+        })?;
+        self.ensure_fitted()?;
+
+        let random_archives: Vec<Vec<Solution>> = with_generation_callback(
+            self.on_generation.as_ref(),
+            self.config.num_threads,
+            |on_generation| {
+                model_selection::null_fronts(
+                    &self.graph,
+                    self.rand_networks,
+                    null_model,
+                    &self.config,
+                    self.debug_level,
+                    on_generation,
+                )
+            },
+        )?;
+        let best_solution =
+            model_selection::min_max_selection(self.fitted_archive()?, &random_archives);
+
+        self.to_dict(py, best_solution.partition.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn two_cliques() -> Graph {
+        let mut graph = Graph::new();
+        for offset in [0, 4] {
+            for i in offset..offset + 4 {
+                for j in (i + 1)..offset + 4 {
+                    graph.add_edge(i, j);
+                }
+            }
+        }
+        graph.add_edge(3, 4);
+        graph
+    }
+
+    #[test]
+    fn test_max_q_partition_refines_and_enforces_links() {
+        // Node 3 sits with the other clique; the stored objectives are stale on purpose
+        let graph = two_cliques();
+        let partition: Partition = (0..8).map(|node| (node, (node >= 3) as i32)).collect();
+        let archive = [Solution {
+            partition: partition.clone(),
+            objectives: vec![0.5, 0.5],
+        }];
+
+        let (raw, q) = max_q_partition(&archive, &graph, &EvolutionConfig::default());
+        assert_eq!(raw, partition);
+        assert_eq!(q, 0.0);
+
+        let config = EvolutionConfig {
+            refine: true,
+            ..Default::default()
         };
+        let (refined, q) = max_q_partition(&archive, &graph, &config);
+        assert_eq!(refined[&3], refined[&0]);
+        assert_ne!(refined[&3], refined[&4]);
+        assert!((q - get_modularity_from_partition(&refined, &graph, 1.0)).abs() < 1e-12);
 
-        Ok(normalize_community_ids(best_solution.partition.clone()))
+        let config = EvolutionConfig {
+            must_link: vec![(0, 7)],
+            ..config
+        };
+        let (linked, _) = max_q_partition(&archive, &graph, &config);
+        assert_eq!(linked[&0], linked[&7]);
     }
 }
@@ -66,51 +66,337 @@ pub fn min_max_selection<'a>(
     best_solution.expect("Real Pareto front is empty.")
 }
 
-use crate::graph::Graph;
-use rand::rng;
+use crate::graph::{Graph, NodeId};
+use crate::mocd::evolutionary;
+use crate::operators::{EvolutionConfig, GenerationCallback, seeded_rng};
+use rand::Rng;
 use rand::seq::SliceRandom as _;
+use rustc_hash::FxHashSet as HashSet;
 
-/// Generates multiple random networks and combines their solutions
-pub fn generate_random_networks(original: &Graph, num_networks: usize) -> Vec<Graph> {
+/// Stream of `operators::seeded_rng` reserved for the null networks, apart from the
+/// ones the optimizer and edge sampling draw from
+const NULL_MODEL_STREAM: usize = usize::MAX - 1;
+
+/// Random graphs the real front is compared against in `min_max_selection`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NullModel {
+    /// Rewires the original edges, preserving every node's degree
+    Configuration,
+    /// Places the same number of edges uniformly among all node pairs
+    ErdosRenyi,
+}
+
+impl NullModel {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "configuration" => Some(NullModel::Configuration),
+            "erdos_renyi" => Some(NullModel::ErdosRenyi),
+            _ => None,
+        }
+    }
+}
+
+/// Generates `num_networks` random networks under `null_model`, the i-th drawn from its
+/// own stream of `seed` so the ensemble is reproducible. Weighted graphs have their
+/// edge weights shuffled onto the random edges.
+pub fn generate_random_networks(
+    original: &Graph,
+    num_networks: usize,
+    null_model: NullModel,
+    seed: u64,
+) -> Vec<Graph> {
     (0..num_networks)
-        .map(|_| {
-            let mut random_graph = Graph {
-                nodes: original.nodes.clone(),
-                ..Default::default()
+        .map(|i| {
+            let mut rng = seeded_rng(seed, i, NULL_MODEL_STREAM);
+            let edges = match null_model {
+                NullModel::Configuration => configuration_edges(original, &mut rng),
+                NullModel::ErdosRenyi => erdos_renyi_edges(original, &mut rng),
             };
+            null_network(original, edges, &mut rng)
+        })
+        .collect()
+}
 
-            let node_vec: Vec<_> = random_graph.nodes.iter().cloned().collect();
-            let num_nodes = node_vec.len();
-            let num_edges = original.edges.len();
-            let mut rng = rng();
-            let mut possible_pairs = Vec::with_capacity(num_nodes * (num_nodes - 1) / 2);
+/// Fronts of `num_networks` null networks of `graph` (see `generate_random_networks`),
+/// each evolved with half the generations and a third of the population of `config`
+/// and no warm start. At `debug_level >= 1` the index of each network is logged before
+/// its run, and `on_generation` sees every run's generations, restarting from 0 per
+/// network; neither changes the fronts.
+pub fn null_fronts(
+    graph: &Graph,
+    num_networks: usize,
+    null_model: NullModel,
+    config: &EvolutionConfig,
+    debug_level: i8,
+    on_generation: Option<&GenerationCallback<'_>>,
+) -> Vec<Vec<Solution>> {
+    let null_config = EvolutionConfig {
+        num_gens: config.num_gens / 2,
+        pop_size: config.pop_size / 3,
+        initial_partition: None,
+        ..config.clone()
+    };
+    generate_random_networks(graph, num_networks, null_model, config.resolve_seed())
+        .iter()
+        .enumerate()
+        .map(|(index, random_graph)| {
+            if debug_level >= 1 {
+                log::info!(
+                    "[min_max]: Null network {}/{} - nodes: {}, edges: {}",
+                    index + 1,
+                    num_networks,
+                    random_graph.num_nodes(),
+                    random_graph.num_edges()
+                );
+            }
+            evolutionary::evolutionary_phase(
+                random_graph,
+                debug_level,
+                &null_config,
+                &random_graph.precompute_degrees(),
+                on_generation,
+            )
+            .front
+        })
+        .collect()
+}
 
-            for i in 0..num_nodes {
-                for j in (i + 1)..num_nodes {
-                    possible_pairs.push((node_vec[i], node_vec[j]));
-                }
+/// Graph over the nodes of `original` with `edges`, which take a random permutation of
+/// the original edge weights when there are any
+fn null_network(original: &Graph, edges: Vec<(NodeId, NodeId)>, rng: &mut impl Rng) -> Graph {
+    let mut random_graph = Graph {
+        nodes: original.nodes.clone(),
+        directed: original.directed,
+        ..Default::default()
+    };
+    if !original.is_weighted() {
+        for (from, to) in edges {
+            random_graph.add_edge(from, to);
+        }
+        return random_graph;
+    }
+
+    let mut weights: Vec<f64> = original
+        .edges
+        .iter()
+        .map(|&(from, to)| original.weight(from, to))
+        .collect();
+    weights.shuffle(rng);
+    for ((from, to), weight) in edges.into_iter().zip(weights) {
+        random_graph.add_weighted_edge(from, to, weight);
+    }
+    random_graph
+}
+
+/// As many edges as `original`, drawn uniformly among the pairs of its nodes
+fn erdos_renyi_edges(original: &Graph, rng: &mut impl Rng) -> Vec<(NodeId, NodeId)> {
+    // Sorted so the draw depends only on the seed
+    let node_vec = original.sorted_nodes();
+    let num_nodes = node_vec.len();
+    let num_edges = original.edges.len();
+    let mut possible_pairs = Vec::with_capacity(num_nodes * num_nodes.saturating_sub(1) / 2);
+
+    for i in 0..num_nodes {
+        for j in (i + 1)..num_nodes {
+            possible_pairs.push((node_vec[i], node_vec[j]));
+        }
+    }
+
+    possible_pairs.shuffle(rng);
+    possible_pairs
+        .into_iter()
+        .take(num_edges)
+        .map(|(src, dst)| {
+            // Directed nulls orient each pair at random
+            if original.directed && rng.random_bool(0.5) {
+                (dst, src)
+            } else {
+                (src, dst)
             }
+        })
+        .collect()
+}
 
-            possible_pairs.shuffle(&mut rng);
-            let selected_edges = possible_pairs
-                .into_iter()
-                .take(num_edges)
-                .collect::<Vec<_>>();
+/// Degree-preserving rewiring of `original`: repeated double edge swaps turn
+/// `(a, b), (c, d)` into `(a, d), (c, b)`, skipping any swap that would add a
+/// self-loop or a parallel edge. Directed graphs keep both in- and out-degrees.
+fn configuration_edges(original: &Graph, rng: &mut impl Rng) -> Vec<(NodeId, NodeId)> {
+    let mut edges: Vec<(NodeId, NodeId)> = original.edges.clone();
+    let mut present: HashSet<(NodeId, NodeId)> = edges
+        .iter()
+        .map(|&(from, to)| original.key(from, to))
+        .collect();
 
-            for (src, dst) in &selected_edges {
-                random_graph.edges.push((*src, *dst));
+    if edges.len() >= 2 {
+        for _ in 0..10 * edges.len() {
+            let i = rng.random_range(0..edges.len());
+            let j = rng.random_range(0..edges.len());
+            let (a, b) = edges[i];
+            let (mut c, mut d) = edges[j];
+            // Undirected edges can be read either way round
+            if !original.directed && rng.random_bool(0.5) {
+                (c, d) = (d, c);
+            }
+            if i == j
+                || a == d
+                || c == b
+                || present.contains(&original.key(a, d))
+                || present.contains(&original.key(c, b))
+            {
+                continue;
             }
 
-            for node in &random_graph.nodes {
-                random_graph.adjacency_list.insert(*node, Vec::new());
+            present.remove(&original.key(a, b));
+            present.remove(&original.key(c, d));
+            present.insert(original.key(a, d));
+            present.insert(original.key(c, b));
+            edges[i] = (a, d);
+            edges[j] = (c, b);
+        }
+    }
+
+    edges
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lollipop(directed: bool) -> Graph {
+        let mut graph = if directed {
+            Graph::new_directed()
+        } else {
+            Graph::new()
+        };
+        for i in 0..6 {
+            for j in (i + 1)..6 {
+                graph.add_edge(i, j);
             }
+        }
+        for i in 5..12 {
+            graph.add_edge(i, i + 1);
+        }
+        graph.add_node(20);
+        graph
+    }
+
+    #[test]
+    fn test_null_run_logging_does_not_change_min_max() {
+        let graph = lollipop(false);
+        let config = EvolutionConfig {
+            num_gens: 20,
+            pop_size: 30,
+            seed: Some(9),
+            ..Default::default()
+        };
+        let front =
+            evolutionary::evolutionary_phase(&graph, 0, &config, &graph.precompute_degrees(), None)
+                .front;
+
+        let quiet = null_fronts(&graph, 3, NullModel::ErdosRenyi, &config, 0, None);
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let count = |_: usize, _: f64| {
+            calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            true
+        };
+        let verbose = null_fronts(&graph, 3, NullModel::ErdosRenyi, &config, 2, Some(&count));
+
+        assert!(calls.into_inner() > 0);
+        let objectives = |fronts: &[Vec<Solution>]| -> Vec<Vec<Vec<f64>>> {
+            fronts
+                .iter()
+                .map(|front| front.iter().map(|s| s.objectives.clone()).collect())
+                .collect()
+        };
+        assert_eq!(objectives(&quiet), objectives(&verbose));
+        assert_eq!(
+            min_max_selection(&front, &quiet).partition,
+            min_max_selection(&front, &verbose).partition
+        );
+    }
+
+    #[test]
+    fn test_configuration_model_preserves_degrees() {
+        let graph = lollipop(false);
+        for random_graph in generate_random_networks(&graph, 5, NullModel::Configuration, 7) {
+            assert_eq!(
+                random_graph.precompute_degrees(),
+                graph.precompute_degrees()
+            );
+            assert_eq!(random_graph.num_edges(), graph.num_edges());
+            let keys: HashSet<_> = random_graph
+                .edges
+                .iter()
+                .map(|&(u, v)| random_graph.key(u, v))
+                .collect();
+            assert_eq!(keys.len(), random_graph.num_edges());
+            assert!(random_graph.edges.iter().all(|&(u, v)| u != v));
+        }
+    }
+
+    #[test]
+    fn test_configuration_model_preserves_directed_degrees() {
+        let graph = lollipop(true);
+        let out_degrees = |g: &Graph| {
+            g.sorted_nodes()
+                .iter()
+                .map(|n| g.successors(n).len())
+                .collect::<Vec<_>>()
+        };
+        let in_degrees = |g: &Graph| {
+            g.sorted_nodes()
+                .iter()
+                .map(|n| g.predecessors(n).len())
+                .collect::<Vec<_>>()
+        };
+
+        for random_graph in generate_random_networks(&graph, 5, NullModel::Configuration, 7) {
+            assert_eq!(out_degrees(&random_graph), out_degrees(&graph));
+            assert_eq!(in_degrees(&random_graph), in_degrees(&graph));
+        }
+    }
+
+    #[test]
+    fn test_erdos_renyi_keeps_edge_count() {
+        let graph = lollipop(false);
+        for random_graph in generate_random_networks(&graph, 5, NullModel::ErdosRenyi, 7) {
+            assert_eq!(random_graph.num_edges(), graph.num_edges());
+            assert_eq!(random_graph.nodes, graph.nodes);
+        }
+    }
 
-            for (src, dst) in &random_graph.edges {
-                random_graph.adjacency_list.get_mut(src).unwrap().push(*dst);
-                random_graph.adjacency_list.get_mut(dst).unwrap().push(*src);
+    #[test]
+    fn test_seeded_networks_are_reproducible_and_keep_weights() {
+        let mut graph = lollipop(false);
+        let edges = graph.edges.clone();
+        for (i, &(u, v)) in edges.iter().enumerate() {
+            graph.weights.insert(graph.key(u, v), 1.0 + i as f64);
+        }
+        let sorted_weights = |g: &Graph| {
+            let mut weights: Vec<f64> = g.edges.iter().map(|&(u, v)| g.weight(u, v)).collect();
+            weights.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            weights
+        };
+
+        for null_model in [NullModel::Configuration, NullModel::ErdosRenyi] {
+            let first = generate_random_networks(&graph, 3, null_model, 11);
+            let second = generate_random_networks(&graph, 3, null_model, 11);
+            for (a, b) in first.iter().zip(&second) {
+                assert_eq!(a.edges, b.edges);
+                assert_eq!(a.weights, b.weights);
+                assert_eq!(sorted_weights(a), sorted_weights(&graph));
             }
+            assert_ne!(first[0].edges, first[1].edges);
+        }
+    }
 
-            random_graph
-        })
-        .collect()
+    #[test]
+    fn test_parse_null_model() {
+        assert_eq!(
+            NullModel::parse("configuration"),
+            Some(NullModel::Configuration)
+        );
+        assert_eq!(NullModel::parse("erdos_renyi"), Some(NullModel::ErdosRenyi));
+        assert_eq!(NullModel::parse("barabasi"), None);
+    }
 }
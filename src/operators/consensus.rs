@@ -0,0 +1,87 @@
+//! operators/consensus.rs
+//! Co-association consensus of several partitions of the same nodes
+//! This Source Code Form is subject to the terms of The GNU General Public License v3.0
+//! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
+//! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
+
+use crate::graph::{CommunityId, Graph, NodeId, Partition};
+
+use rustc_hash::FxHashMap as HashMap;
+
+/// Links every node pair grouped together in more than `threshold` of `partitions`
+/// and returns the connected components of those links as communities, numbered in
+/// ascending order of their smallest node. Pairs never grouped together are not
+/// stored, so the co-association matrix costs the squared community sizes, not N².
+pub fn co_association(partitions: &[Partition], threshold: f64) -> Partition {
+    let mut together: HashMap<(NodeId, NodeId), usize> = HashMap::default();
+    let mut agreement = Graph::new();
+
+    for partition in partitions {
+        let mut communities: HashMap<CommunityId, Vec<NodeId>> = HashMap::default();
+        for (&node, &community) in partition {
+            agreement.add_node(node);
+            communities.entry(community).or_default().push(node);
+        }
+        for members in communities.values() {
+            for (i, &a) in members.iter().enumerate() {
+                for &b in &members[i + 1..] {
+                    *together.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    let runs = partitions.len() as f64;
+    let mut pairs: Vec<(NodeId, NodeId)> = together
+        .into_iter()
+        .filter(|&(_, count)| count as f64 / runs > threshold)
+        .map(|(pair, _)| pair)
+        .collect();
+    // Sorted so the agreement graph does not depend on hash order
+    pairs.sort_unstable();
+    for (a, b) in pairs {
+        agreement.add_edge(a, b);
+    }
+
+    let mut consensus = Partition::new();
+    for (community, component) in agreement.connected_components().into_iter().enumerate() {
+        for node in component {
+            consensus.insert(node, community as CommunityId);
+        }
+    }
+    consensus
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::normalize_community_ids;
+
+    #[test]
+    fn test_identical_partitions_are_their_own_consensus() {
+        let partition: Partition = [(0, 5), (1, 5), (2, 9), (3, 9), (4, 5), (5, 2)]
+            .into_iter()
+            .collect();
+        let runs = vec![partition.clone(); 4];
+
+        assert_eq!(
+            co_association(&runs, 0.5),
+            normalize_community_ids(partition)
+        );
+    }
+
+    #[test]
+    fn test_minority_groupings_are_dropped() {
+        let runs: Vec<Partition> = [
+            [(0, 0), (1, 0), (2, 1), (3, 1)],
+            [(0, 0), (1, 0), (2, 1), (3, 1)],
+            [(0, 0), (1, 1), (2, 1), (3, 0)],
+        ]
+        .into_iter()
+        .map(|run| run.into_iter().collect())
+        .collect();
+
+        let expected: Partition = [(0, 0), (1, 0), (2, 1), (3, 1)].into_iter().collect();
+        assert_eq!(co_association(&runs, 0.5), expected);
+    }
+}
@@ -0,0 +1,349 @@
+//! operators/constraint.rs
+//! Repairs partitions so they satisfy user-imposed structural constraints
+//! This Source Code Form is subject to the terms of The GNU General Public License v3.0
+//! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
+//! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
+
+use crate::graph::{CommunityId, Graph, NodeId, Partition};
+
+use rand::Rng;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Relabels `partition` until it has exactly `k` communities (or one per node when
+/// `k` exceeds the node count). Extra communities are merged, smallest first, into the
+/// neighbouring community they share most edges with; missing ones are carved out of
+/// the largest community by a breadth-first walk from a random member.
+pub fn repair_community_count(
+    partition: &mut Partition,
+    graph: &Graph,
+    k: usize,
+    rng: &mut impl Rng,
+) {
+    let k = k.clamp(1, partition.len().max(1));
+    let mut members: BTreeMap<CommunityId, Vec<NodeId>> = BTreeMap::new();
+    for (&node, &community) in partition.iter() {
+        members.entry(community).or_default().push(node);
+    }
+
+    if members.len() > k {
+        merge_communities(partition, graph, &mut members, k);
+    }
+    while members.len() < k {
+        split_largest(partition, graph, &mut members, rng);
+    }
+}
+
+/// Splits every community larger than `max_size` into connected chunks of at most
+/// `max_size` nodes, each carved by a breadth-first walk from a random member and
+/// given a fresh id. Communities within the cap are left alone.
+pub fn repair_community_size(
+    partition: &mut Partition,
+    graph: &Graph,
+    max_size: usize,
+    rng: &mut impl Rng,
+) {
+    let max_size = max_size.max(1);
+    let mut members: BTreeMap<CommunityId, Vec<NodeId>> = BTreeMap::new();
+    for (&node, &community) in partition.iter() {
+        members.entry(community).or_default().push(node);
+    }
+    let mut next_community = members.keys().next_back().map_or(0, |&last| last + 1);
+
+    for nodes in members.into_values() {
+        let mut remaining: HashSet<NodeId> = nodes.iter().copied().collect();
+        let mut order = nodes;
+        while remaining.len() > max_size {
+            order.retain(|node| remaining.contains(node));
+            let start = order[rng.random_range(0..order.len())];
+            remaining.remove(&start);
+            let mut carved: Vec<NodeId> = vec![start];
+            let mut queue: VecDeque<NodeId> = VecDeque::from([start]);
+            while let Some(node) = queue.pop_front() {
+                for &neighbor in graph.neighbors(&node) {
+                    if carved.len() >= max_size {
+                        break;
+                    }
+                    if remaining.remove(&neighbor) {
+                        carved.push(neighbor);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            for node in carved {
+                partition.insert(node, next_community);
+            }
+            next_community += 1;
+        }
+    }
+}
+
+/// Moves every must-link group (pairs joined transitively) into the community of its
+/// smallest node, then, for each cannot-link pair still sharing a community, moves the
+/// second node's group to a fresh community. A fresh community only ever holds one
+/// group, so the result satisfies every link as long as `link_conflict` finds none.
+/// Pairs with a node missing from `partition` are left out.
+pub fn repair_links(
+    partition: &mut Partition,
+    must_link: &[(NodeId, NodeId)],
+    cannot_link: &[(NodeId, NodeId)],
+) {
+    let roots = link_roots(must_link);
+    let mut groups: BTreeMap<NodeId, Vec<NodeId>> = BTreeMap::new();
+    for (&node, &root) in &roots {
+        groups.entry(root).or_default().push(node);
+    }
+    for (root, members) in &groups {
+        let Some(&community) = partition.get(root) else {
+            continue;
+        };
+        for node in members {
+            if let Some(assigned) = partition.get_mut(node) {
+                *assigned = community;
+            }
+        }
+    }
+
+    let mut next_community = partition.values().max().map_or(0, |&last| last + 1);
+    for &(a, b) in cannot_link {
+        match (partition.get(&a), partition.get(&b)) {
+            (Some(x), Some(y)) if x == y => {}
+            _ => continue,
+        }
+        let group = roots
+            .get(&b)
+            .map_or_else(|| vec![b], |root| groups[root].clone());
+        for node in group {
+            if let Some(assigned) = partition.get_mut(&node) {
+                *assigned = next_community;
+            }
+        }
+        next_community += 1;
+    }
+}
+
+/// First cannot-link pair whose nodes are the same or must-linked, which no partition
+/// can satisfy
+pub fn link_conflict(
+    must_link: &[(NodeId, NodeId)],
+    cannot_link: &[(NodeId, NodeId)],
+) -> Option<(NodeId, NodeId)> {
+    let roots = link_roots(must_link);
+    let root = |node: NodeId| roots.get(&node).copied().unwrap_or(node);
+    cannot_link
+        .iter()
+        .copied()
+        .find(|&(a, b)| root(a) == root(b))
+}
+
+/// Smallest node of the must-link group of every node in a pair, by union-find
+fn link_roots(must_link: &[(NodeId, NodeId)]) -> HashMap<NodeId, NodeId> {
+    let mut parent: HashMap<NodeId, NodeId> = HashMap::default();
+    for &(a, b) in must_link {
+        let (a, b) = (find_root(&mut parent, a), find_root(&mut parent, b));
+        parent.insert(a.max(b), a.min(b));
+    }
+    let nodes: Vec<NodeId> = parent.keys().copied().collect();
+    nodes
+        .into_iter()
+        .map(|node| (node, find_root(&mut parent, node)))
+        .collect()
+}
+
+fn find_root(parent: &mut HashMap<NodeId, NodeId>, node: NodeId) -> NodeId {
+    let mut root = *parent.entry(node).or_insert(node);
+    while parent[&root] != root {
+        root = parent[&root];
+    }
+    parent.insert(node, root);
+    root
+}
+
+fn merge_communities(
+    partition: &mut Partition,
+    graph: &Graph,
+    members: &mut BTreeMap<CommunityId, Vec<NodeId>>,
+    k: usize,
+) {
+    // Edge counts between communities, kept symmetric as communities merge
+    let mut links: HashMap<CommunityId, BTreeMap<CommunityId, usize>> = HashMap::default();
+    for &(from, to) in &graph.edges {
+        if let (Some(&a), Some(&b)) = (partition.get(&from), partition.get(&to))
+            && a != b
+        {
+            *links.entry(a).or_default().entry(b).or_insert(0) += 1;
+            *links.entry(b).or_default().entry(a).or_insert(0) += 1;
+        }
+    }
+
+    while members.len() > k {
+        let (&smallest, _) = members
+            .iter()
+            .min_by_key(|&(&community, nodes)| (nodes.len(), community))
+            .unwrap();
+
+        // Most connected neighbour, or the next smallest community when isolated
+        let own_links = links.remove(&smallest).unwrap_or_default();
+        let target = own_links
+            .iter()
+            .max_by_key(|&(&community, &count)| (count, std::cmp::Reverse(community)))
+            .map(|(&community, _)| community)
+            .unwrap_or_else(|| {
+                *members
+                    .iter()
+                    .filter(|(community, _)| **community != smallest)
+                    .min_by_key(|&(&community, nodes)| (nodes.len(), community))
+                    .unwrap()
+                    .0
+            });
+
+        for (neighbor, count) in own_links {
+            let neighbor_links = links.get_mut(&neighbor).unwrap();
+            neighbor_links.remove(&smallest);
+            if neighbor != target {
+                *neighbor_links.entry(target).or_insert(0) += count;
+                *links
+                    .entry(target)
+                    .or_default()
+                    .entry(neighbor)
+                    .or_insert(0) += count;
+            }
+        }
+
+        let moved = members.remove(&smallest).unwrap();
+        for &node in &moved {
+            partition.insert(node, target);
+        }
+        members.get_mut(&target).unwrap().extend(moved);
+    }
+}
+
+fn split_largest(
+    partition: &mut Partition,
+    graph: &Graph,
+    members: &mut BTreeMap<CommunityId, Vec<NodeId>>,
+    rng: &mut impl Rng,
+) {
+    let (&largest, nodes) = members
+        .iter()
+        .max_by_key(|&(&community, nodes)| (nodes.len(), std::cmp::Reverse(community)))
+        .unwrap();
+    let inside: HashSet<NodeId> = nodes.iter().copied().collect();
+    let start = nodes[rng.random_range(0..nodes.len())];
+    let target_size = nodes.len() / 2;
+
+    let mut carved: Vec<NodeId> = vec![start];
+    let mut visited: HashSet<NodeId> = HashSet::from_iter([start]);
+    let mut queue: VecDeque<NodeId> = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        for &neighbor in graph.neighbors(&node) {
+            if carved.len() >= target_size {
+                break;
+            }
+            if inside.contains(&neighbor) && visited.insert(neighbor) {
+                carved.push(neighbor);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    let new_community = members.keys().next_back().unwrap() + 1;
+    let carved_set: HashSet<NodeId> = carved.iter().copied().collect();
+    members
+        .get_mut(&largest)
+        .unwrap()
+        .retain(|node| !carved_set.contains(node));
+    for &node in &carved {
+        partition.insert(node, new_community);
+    }
+    members.insert(new_community, carved);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn path(n: i32) -> Graph {
+        let mut graph = Graph::new();
+        for node in 0..n - 1 {
+            graph.add_edge(node, node + 1);
+        }
+        graph
+    }
+
+    fn num_labels(partition: &Partition) -> usize {
+        partition.values().collect::<HashSet<_>>().len()
+    }
+
+    #[test]
+    fn test_repair_merges_down_to_k() {
+        let graph = path(10);
+        let mut partition: Partition = (0..10).map(|node| (node, node)).collect();
+
+        repair_community_count(&mut partition, &graph, 3, &mut ChaCha8Rng::seed_from_u64(1));
+        assert_eq!(num_labels(&partition), 3);
+    }
+
+    #[test]
+    fn test_repair_splits_up_to_k() {
+        let graph = path(10);
+        let mut partition: Partition = (0..10).map(|node| (node, 0)).collect();
+
+        repair_community_count(&mut partition, &graph, 4, &mut ChaCha8Rng::seed_from_u64(1));
+        assert_eq!(num_labels(&partition), 4);
+
+        // More communities than nodes caps at one node per community
+        repair_community_count(
+            &mut partition,
+            &graph,
+            50,
+            &mut ChaCha8Rng::seed_from_u64(1),
+        );
+        assert_eq!(num_labels(&partition), 10);
+    }
+
+    #[test]
+    fn test_repair_links_merges_and_splits() {
+        let must_link = [(0, 3), (3, 5), (7, 8)];
+        let cannot_link = [(0, 1), (5, 6), (8, 9)];
+        let mut partition: Partition = (0..10).map(|node| (node, node % 2)).collect();
+
+        repair_links(&mut partition, &must_link, &cannot_link);
+        assert_eq!(partition[&0], partition[&3]);
+        assert_eq!(partition[&3], partition[&5]);
+        assert_eq!(partition[&7], partition[&8]);
+        for (a, b) in cannot_link {
+            assert_ne!(partition[&a], partition[&b]);
+        }
+        assert_eq!(partition.len(), 10);
+    }
+
+    #[test]
+    fn test_link_conflict_follows_must_link_chains() {
+        assert_eq!(
+            link_conflict(&[(0, 1), (1, 2)], &[(3, 0), (2, 0)]),
+            Some((2, 0))
+        );
+        assert_eq!(link_conflict(&[(0, 1)], &[(4, 4)]), Some((4, 4)));
+        assert_eq!(link_conflict(&[(0, 1)], &[(1, 2)]), None);
+    }
+
+    #[test]
+    fn test_repair_caps_community_size() {
+        let mut graph = path(10);
+        graph.add_node(10);
+        let mut partition: Partition = (0..11).map(|node| (node, 0)).collect();
+        partition.insert(9, 1);
+
+        repair_community_size(&mut partition, &graph, 3, &mut ChaCha8Rng::seed_from_u64(2));
+        let mut sizes: BTreeMap<CommunityId, usize> = BTreeMap::new();
+        for &community in partition.values() {
+            *sizes.entry(community).or_insert(0) += 1;
+        }
+        assert!(sizes.values().all(|&size| size <= 3));
+        assert_eq!(partition[&9], 1, "communities within the cap keep their id");
+        assert_eq!(partition.len(), 11);
+    }
+}
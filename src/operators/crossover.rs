@@ -6,16 +6,15 @@
 
 use crate::graph::{NodeId, Partition};
 use rand::{Rng, seq::IndexedRandom};
+use rustc_hash::FxHashMap as HashMap;
 use std::collections::BTreeMap;
-use std::collections::HashMap;
 
 pub fn optimized_crossover(
     parent1: &Partition,
     parent2: &Partition,
     crossover_rate: f64,
+    rng: &mut impl Rng,
 ) -> Partition {
-    let mut rng = rand::rng();
-
     if rng.random::<f64>() > crossover_rate {
         // If no crossover, randomly return either parent1 or parent2
         return if rng.random_bool(0.5) {
@@ -27,38 +26,121 @@ pub fn optimized_crossover(
 
     // Use Vec for faster sequential access
     let keys: Vec<NodeId> = parent1.keys().copied().collect();
-    let len = keys.len();
+    let crossover_points = crossover_points(keys.len(), rng);
+
+    splice(parent1, parent2, &keys, crossover_points)
+}
+
+/// One-point crossover: the child takes `parent1`'s communities up to a random cut in
+/// node order and `parent2`'s after it. Skipped as in `optimized_crossover`.
+pub fn one_point_crossover(
+    parent1: &Partition,
+    parent2: &Partition,
+    crossover_rate: f64,
+    rng: &mut impl Rng,
+) -> Partition {
+    if parent1.len() < 2 || rng.random::<f64>() > crossover_rate {
+        return if rng.random_bool(0.5) {
+            parent1.clone()
+        } else {
+            parent2.clone()
+        };
+    }
+
+    let keys: Vec<NodeId> = parent1.keys().copied().collect();
+    let cut = rng.random_range(1..keys.len());
+    splice(parent2, parent1, &keys, (0, cut))
+}
+
+/// Uniform crossover: each node takes its community from either parent with even
+/// odds. Skipped as in `optimized_crossover`.
+pub fn uniform_crossover(
+    parent1: &Partition,
+    parent2: &Partition,
+    crossover_rate: f64,
+    rng: &mut impl Rng,
+) -> Partition {
+    if rng.random::<f64>() > crossover_rate {
+        return if rng.random_bool(0.5) {
+            parent1.clone()
+        } else {
+            parent2.clone()
+        };
+    }
+
+    parent1
+        .iter()
+        .map(|(&node, &community)| {
+            let other = parent2.get(&node).copied().unwrap_or(community);
+            (
+                node,
+                if rng.random_bool(0.5) {
+                    community
+                } else {
+                    other
+                },
+            )
+        })
+        .collect()
+}
+
+/// Segment crossover returning both complementary children. With probability
+/// `1 - crossover_rate` (or fewer than two nodes) the parents come back unchanged.
+pub fn paired_crossover(
+    parent1: &Partition,
+    parent2: &Partition,
+    crossover_rate: f64,
+    rng: &mut impl Rng,
+) -> (Partition, Partition) {
+    if parent1.len() < 2 || !rng.random_bool(crossover_rate) {
+        return (parent1.clone(), parent2.clone());
+    }
+
+    let keys: Vec<NodeId> = parent1.keys().copied().collect();
+    let crossover_points = crossover_points(keys.len(), rng);
+    (
+        splice(parent1, parent2, &keys, crossover_points),
+        splice(parent2, parent1, &keys, crossover_points),
+    )
+}
 
-    // Optimize crossover point selection
-    let crossover_points: (usize, usize) = {
-        let point1: usize = rng.random_range(0..len);
-        let point2: usize = (point1 + rng.random_range(1..len / 2)).min(len - 1);
-        (point1, point2)
-    };
+/// Segment `[start, end)` of a crossover over `len` nodes
+fn crossover_points(len: usize, rng: &mut impl Rng) -> (usize, usize) {
+    let point1: usize = rng.random_range(0..len);
+    let point2: usize = (point1 + rng.random_range(1..(len / 2).max(2))).min(len - 1);
+    (point1, point2)
+}
 
+/// Child taking `outer`'s communities outside the crossover segment and `inner`'s inside
+fn splice(
+    outer: &Partition,
+    inner: &Partition,
+    keys: &[NodeId],
+    crossover_points: (usize, usize),
+) -> Partition {
     // Pre-allocate with capacity
     let mut child: BTreeMap<i32, i32> = Partition::new();
 
-    // Copy elements before crossover point from parent1
+    // Copy elements before crossover point from the outer parent
     keys.iter().take(crossover_points.0).for_each(|&key| {
-        if let Some(&community) = parent1.get(&key) {
+        if let Some(&community) = outer.get(&key) {
             child.insert(key, community);
         }
     });
 
-    // Copy elements in crossover region from parent2
+    // Copy elements in crossover region from the inner parent
     keys.iter()
         .skip(crossover_points.0)
         .take(crossover_points.1 - crossover_points.0)
         .for_each(|&key| {
-            if let Some(&community) = parent2.get(&key) {
+            if let Some(&community) = inner.get(&key) {
                 child.insert(key, community);
             }
         });
 
-    // Copy remaining elements from parent1
+    // Copy remaining elements from the outer parent
     keys.iter().skip(crossover_points.1).for_each(|&key| {
-        if let Some(&community) = parent1.get(&key) {
+        if let Some(&community) = outer.get(&key) {
             child.insert(key, community);
         }
     });
@@ -67,9 +149,11 @@ pub fn optimized_crossover(
 }
 
 // Ensemble Learning-Based Multi-Individual Crossover
-pub fn ensemble_crossover(parents: &[Partition], crossover_rate: f64) -> Partition {
-    let mut rng = rand::rng();
-
+pub fn ensemble_crossover(
+    parents: &[Partition],
+    crossover_rate: f64,
+    rng: &mut impl Rng,
+) -> Partition {
     // Check if crossover should be skipped
     if rng.random::<f64>() > crossover_rate {
         // Return a random parent if no crossover
@@ -82,7 +166,7 @@ pub fn ensemble_crossover(parents: &[Partition], crossover_rate: f64) -> Partiti
 
     for &node in &keys {
         // Count community occurrences across all parents
-        let mut community_counts = HashMap::new();
+        let mut community_counts = HashMap::default();
         for parent in parents {
             if let Some(&community) = parent.get(&node) {
                 *community_counts.entry(community).or_insert(0) += 1;
@@ -100,7 +184,7 @@ pub fn ensemble_crossover(parents: &[Partition], crossover_rate: f64) -> Partiti
 
         // Select community with tie-breaking
         let selected = candidates
-            .choose(&mut rng)
+            .choose(rng)
             .copied()
             .unwrap_or_else(|| parents[0][&node]);
 
@@ -0,0 +1,53 @@
+//! operators/hypervolume.rs
+//! Hypervolume indicator of a bi-objective front
+//! This Source Code Form is subject to the terms of The GNU General Public License v3.0
+//! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
+//! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
+
+use std::cmp::Ordering;
+
+/// Area dominated by `points` (minimization) and bounded by `reference`, by a sweep over
+/// the points in ascending first objective: O(n log n). Points that do not strictly
+/// dominate the reference add nothing; dominated points and duplicates are harmless.
+pub fn hypervolume_2d(points: &[(f64, f64)], reference: (f64, f64)) -> f64 {
+    let mut inside: Vec<(f64, f64)> = points
+        .iter()
+        .copied()
+        .filter(|&(x, y)| x < reference.0 && y < reference.1)
+        .collect();
+    inside.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+    let mut volume = 0.0;
+    let mut lowest = reference.1;
+    for (x, y) in inside {
+        if y < lowest {
+            volume += (reference.0 - x) * (lowest - y);
+            lowest = y;
+        }
+    }
+    volume
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_two_point_front() {
+        // [1, 4] x [3, 4] and [2, 4] x [1, 4] overlap on [2, 4] x [3, 4]: 3 + 6 - 2
+        let front = [(1.0, 3.0), (2.0, 1.0)];
+        assert!((hypervolume_2d(&front, (4.0, 4.0)) - 7.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_dominated_and_outside_points_add_nothing() {
+        let front = [(1.0, 3.0), (2.0, 1.0)];
+        let noisy = [(2.0, 1.0), (3.0, 3.5), (1.0, 3.0), (5.0, 0.0), (2.0, 1.0)];
+        assert_eq!(
+            hypervolume_2d(&noisy, (4.0, 4.0)),
+            hypervolume_2d(&front, (4.0, 4.0))
+        );
+        assert_eq!(hypervolume_2d(&[], (4.0, 4.0)), 0.0);
+        assert_eq!(hypervolume_2d(&[(4.0, 1.0)], (4.0, 4.0)), 0.0);
+    }
+}
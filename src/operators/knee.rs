@@ -0,0 +1,70 @@
+//! operators/knee.rs
+//! Knee point of a bi-objective front
+//! This Source Code Form is subject to the terms of The GNU General Public License v3.0
+//! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
+//! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
+
+use std::cmp::Ordering;
+
+/// Index of the point farthest from the chord joining the two extremes of the front
+/// (lowest and highest first objective), i.e. the point of maximum curvature. `None`
+/// for fewer than three points or when the extremes coincide, since there is no chord
+/// to measure against. Ties go to the point with the lower first objective.
+pub fn knee_point_2d(points: &[(f64, f64)]) -> Option<usize> {
+    if points.len() < 3 {
+        return None;
+    }
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| {
+        points[a]
+            .0
+            .partial_cmp(&points[b].0)
+            .unwrap_or(Ordering::Equal)
+    });
+
+    let (x1, y1) = points[order[0]];
+    let (x2, y2) = points[order[order.len() - 1]];
+    let length = (x2 - x1).hypot(y2 - y1);
+    if length == 0.0 {
+        return None;
+    }
+
+    let mut knee = order[0];
+    let mut farthest = f64::NEG_INFINITY;
+    for &i in &order {
+        let (x, y) = points[i];
+        let distance = ((y2 - y1) * x - (x2 - x1) * y + x2 * y1 - y2 * x1).abs() / length;
+        if distance > farthest {
+            farthest = distance;
+            knee = i;
+        }
+    }
+    Some(knee)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_convex_front() {
+        // y = 1 / x on [0.5, 4]: the chord is x + 2 y = 4.5, and (1.5, 2/3) is farthest.
+        // Points are shuffled so the index has to survive the sort
+        let front = [
+            (4.0, 0.25),
+            (1.0, 1.0),
+            (0.5, 2.0),
+            (1.5, 1.0 / 1.5),
+            (3.0, 1.0 / 3.0),
+            (2.0, 0.5),
+        ];
+        assert_eq!(knee_point_2d(&front), Some(3));
+    }
+
+    #[test]
+    fn test_too_few_points() {
+        assert_eq!(knee_point_2d(&[]), None);
+        assert_eq!(knee_point_2d(&[(0.2, 0.8), (0.8, 0.2)]), None);
+        assert_eq!(knee_point_2d(&[(0.5, 0.5); 3]), None);
+    }
+}
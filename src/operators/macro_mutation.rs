@@ -0,0 +1,194 @@
+//! operators/macro_mutation.rs
+//! Community-level mutations that merge or split whole communities
+//! This Source Code Form is subject to the terms of The GNU General Public License v3.0
+//! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
+//! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
+
+use crate::graph::{CommunityId, Graph, NodeId, Partition};
+
+use rand::Rng;
+use rustc_hash::FxHashSet as HashSet;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// Merges a random community into a random community it shares an edge with.
+/// Returns false, leaving `partition` untouched, when no edge crosses communities.
+pub fn merge_adjacent(partition: &mut Partition, graph: &Graph, rng: &mut impl Rng) -> bool {
+    let mut adjacent: BTreeMap<CommunityId, BTreeSet<CommunityId>> = BTreeMap::new();
+    for &(from, to) in &graph.edges {
+        if let (Some(&a), Some(&b)) = (partition.get(&from), partition.get(&to))
+            && a != b
+        {
+            adjacent.entry(a).or_default().insert(b);
+            adjacent.entry(b).or_default().insert(a);
+        }
+    }
+    if adjacent.is_empty() {
+        return false;
+    }
+
+    let (&source, neighbors) = adjacent
+        .iter()
+        .nth(rng.random_range(0..adjacent.len()))
+        .unwrap();
+    let target = *neighbors
+        .iter()
+        .nth(rng.random_range(0..neighbors.len()))
+        .unwrap();
+    for community in partition.values_mut() {
+        if *community == source {
+            *community = target;
+        }
+    }
+    true
+}
+
+/// Bisects a random community of two or more nodes along a sweep cut: members are
+/// ordered breadth-first from a random start, and the prefix minimizing
+/// cut weight / size of the smaller side becomes a new community. A disconnected
+/// community is split between its components at no cut. Returns false, leaving
+/// `partition` untouched, when every community is a single node.
+pub fn split_sparsest(partition: &mut Partition, graph: &Graph, rng: &mut impl Rng) -> bool {
+    let mut members: BTreeMap<CommunityId, Vec<NodeId>> = BTreeMap::new();
+    for (&node, &community) in partition.iter() {
+        members.entry(community).or_default().push(node);
+    }
+    let splittable: Vec<&Vec<NodeId>> = members.values().filter(|nodes| nodes.len() > 1).collect();
+    if splittable.is_empty() {
+        return false;
+    }
+    let nodes = splittable[rng.random_range(0..splittable.len())];
+    let inside: HashSet<NodeId> = nodes.iter().copied().collect();
+
+    // Breadth-first order over the community, restarting in each unreached component
+    let start = rng.random_range(0..nodes.len());
+    let mut order: Vec<NodeId> = Vec::with_capacity(nodes.len());
+    let mut visited: HashSet<NodeId> = HashSet::default();
+    for &root in nodes[start..].iter().chain(&nodes[..start]) {
+        if !visited.insert(root) {
+            continue;
+        }
+        let mut queue = VecDeque::from([root]);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for &neighbor in graph.neighbors(&node) {
+                if inside.contains(&neighbor) && visited.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+
+    // Sweep the prefixes, updating the cut as each node crosses over
+    let mut carved: HashSet<NodeId> = HashSet::default();
+    let mut cut = 0.0;
+    let mut best = (f64::INFINITY, 1);
+    for (index, &node) in order[..order.len() - 1].iter().enumerate() {
+        for &neighbor in graph.neighbors(&node) {
+            if neighbor == node || !inside.contains(&neighbor) {
+                continue;
+            }
+            let weight = graph.weight(node, neighbor);
+            if carved.contains(&neighbor) {
+                cut -= weight;
+            } else {
+                cut += weight;
+            }
+        }
+        carved.insert(node);
+
+        let size = index + 1;
+        let sparsity = cut / size.min(order.len() - size) as f64;
+        if sparsity < best.0 {
+            best = (sparsity, size);
+        }
+    }
+
+    let new_community = members.keys().next_back().unwrap() + 1;
+    for &node in &order[..best.1] {
+        partition.insert(node, new_community);
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::operators::seeded_rng;
+
+    /// Three 4-cliques in a row, each tied to the next by one edge
+    fn clique_chain() -> Graph {
+        let mut graph = Graph::new();
+        for offset in [0, 4, 8] {
+            for i in offset..offset + 4 {
+                for j in (i + 1)..offset + 4 {
+                    graph.add_edge(i, j);
+                }
+            }
+        }
+        graph.add_edge(3, 4);
+        graph.add_edge(7, 8);
+        graph
+    }
+
+    fn num_communities(partition: &Partition) -> usize {
+        partition.values().collect::<HashSet<_>>().len()
+    }
+
+    #[test]
+    fn test_merge_removes_one_community() {
+        let graph = clique_chain();
+        for seed in 0..10 {
+            let mut partition: Partition = (0..12).map(|node| (node, node / 4)).collect();
+            assert!(merge_adjacent(
+                &mut partition,
+                &graph,
+                &mut seeded_rng(seed, 0, 0)
+            ));
+            assert_eq!(num_communities(&partition), 2);
+        }
+
+        let mut whole: Partition = (0..12).map(|node| (node, 0)).collect();
+        assert!(!merge_adjacent(
+            &mut whole,
+            &graph,
+            &mut seeded_rng(0, 0, 0)
+        ));
+    }
+
+    #[test]
+    fn test_split_adds_one_community() {
+        let graph = clique_chain();
+        for seed in 0..10 {
+            let mut partition: Partition = (0..12).map(|node| (node, node / 4)).collect();
+            assert!(split_sparsest(
+                &mut partition,
+                &graph,
+                &mut seeded_rng(seed, 0, 0)
+            ));
+            assert_eq!(num_communities(&partition), 4);
+        }
+
+        let mut singletons: Partition = (0..12).map(|node| (node, node)).collect();
+        assert!(!split_sparsest(
+            &mut singletons,
+            &graph,
+            &mut seeded_rng(0, 0, 0)
+        ));
+    }
+
+    #[test]
+    fn test_split_follows_the_sparsest_cut() {
+        let graph = clique_chain();
+        let pairs: Partition = (0..12).map(|node| (node, (node >= 8) as i32)).collect();
+        for seed in 0..10 {
+            let mut partition = pairs.clone();
+            // Community 0 (two cliques) or community 1 (one clique) may be drawn
+            split_sparsest(&mut partition, &graph, &mut seeded_rng(seed, 0, 0));
+            if partition[&0] != partition[&4] {
+                let first: HashSet<_> = (0..4).map(|node| partition[&node]).collect();
+                let second: HashSet<_> = (4..8).map(|node| partition[&node]).collect();
+                assert_eq!((first.len(), second.len()), (1, 1));
+            }
+        }
+    }
+}
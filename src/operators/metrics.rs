@@ -3,7 +3,7 @@
 //! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
 //! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 #[allow(dead_code)]
 pub struct Metrics {
     pub modularity: f64,
@@ -26,4 +26,10 @@ impl Metrics {
     pub fn get_modularity(&self) -> f64 {
         self.modularity
     }
+
+    /// Q with weighted objectives, 1 - alpha * intra - beta * inter; unit weights
+    /// give back `modularity`
+    pub fn weighted_modularity(&self, alpha: f64, beta: f64) -> f64 {
+        1.0 - alpha * self.intra - beta * self.inter
+    }
 }
@@ -3,17 +3,289 @@
 //! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
 //! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
 
-use crate::graph::{Graph, Partition};
+use crate::graph::{CommunityId, Graph, NodeId, Partition};
 use metrics::Metrics;
+pub use objective::CommunityStrengths;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::{ThreadPoolBuildError, ThreadPoolBuilder};
 use rustc_hash::FxBuildHasher;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Instant;
 
 pub mod metrics;
 
+mod consensus;
+mod constraint;
 mod crossover;
+mod hypervolume;
+mod knee;
+mod macro_mutation;
 mod mutation;
 mod objective;
+mod overlap;
 mod population;
+mod refine;
+
+/// Share of the first population copied from `EvolutionConfig::initial_partition`;
+/// the rest stays random to keep the search diverse
+const WARM_START_SHARE: f64 = 0.25;
+
+/// Null model behind the expected-edges (inter) objective
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ModularityVariant {
+    /// Newman-Girvan configuration null, inter = resolution * sum_c (D_c / 2m)^2
+    #[default]
+    Newman,
+    /// Planted-partition degree-corrected SBM, see `objective::degree_corrected_objectives`
+    Dcsbm,
+    /// Barber's bipartite null over the two sides in `Graph::node_types`, see
+    /// `objective::bipartite_objectives`. Set by the `bipartite` flag, not by name.
+    Bipartite,
+}
+
+impl ModularityVariant {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "newman" => Some(ModularityVariant::Newman),
+            "dcsbm" => Some(ModularityVariant::Dcsbm),
+            _ => None,
+        }
+    }
+}
+
+/// Recombination of parent partitions, over nodes in ascending id order
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CrossoverType {
+    /// Per-node majority vote of the parents, ties broken at random
+    Ensemble,
+    /// Each node from either parent with even odds
+    Uniform,
+    /// First parent up to a random cut, second parent after it
+    OnePoint,
+    /// A short random segment from the second parent, the rest from the first
+    TwoPoint,
+}
+
+impl CrossoverType {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "ensemble" => Some(CrossoverType::Ensemble),
+            "uniform" => Some(CrossoverType::Uniform),
+            "one_point" => Some(CrossoverType::OnePoint),
+            "two_point" => Some(CrossoverType::TwoPoint),
+            _ => None,
+        }
+    }
+}
+
+/// How the first population of a run is drawn
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum InitStrategy {
+    /// Every node in a uniformly random community
+    #[default]
+    Random,
+    /// A few label propagation sweeps from singletons, in a random order per individual
+    LabelPropagation,
+}
+
+impl InitStrategy {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "random" => Some(InitStrategy::Random),
+            "label_propagation" => Some(InitStrategy::LabelPropagation),
+            _ => None,
+        }
+    }
+}
+
+/// How the node-move mutation rate changes over the generations of a run
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MutationSchedule {
+    /// `mut_rate` throughout
+    #[default]
+    Constant,
+    /// Straight line from `mut_rate` to `mut_rate_min` at the last generation
+    Linear,
+    /// Geometric decay from `mut_rate` to `mut_rate_min` at the last generation
+    Exponential,
+}
+
+impl MutationSchedule {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "constant" => Some(MutationSchedule::Constant),
+            "linear" => Some(MutationSchedule::Linear),
+            "exponential" => Some(MutationSchedule::Exponential),
+            _ => None,
+        }
+    }
+}
+
+/// Hyper-parameters shared by the evolutionary optimizers
+#[derive(Debug, Clone)]
+pub struct EvolutionConfig {
+    pub num_gens: usize,
+    pub pop_size: usize,
+    pub cross_rate: f64,
+    pub mut_rate: f64,
+    pub seed: Option<u64>,               // None draws a fresh seed on every run
+    pub patience: Option<usize>,         // Stagnant generations before stopping, None disables
+    pub tol: f64,                        // Minimum best-Q gain that counts as an improvement
+    pub num_threads: Option<usize>,      // Worker threads, None uses the global rayon pool
+    pub num_communities: Option<usize>,  // Exact community count to enforce, None leaves it free
+    pub time_limit_secs: Option<f64>,    // Wall-clock budget, checked once per generation
+    pub tournament_size: usize,          // Candidates per NSGA-II tournament, ignored by PESA-II
+    pub grid_divisions: usize,           // PESA-II hypergrid bins per objective, ignored by NSGA-II
+    pub archive_size: usize,             // PESA-II archive capacity, ignored by NSGA-II
+    pub archive_patience: Option<usize>, // PESA-II generations with unchanged boxes before stopping
+    pub resolution: f64,                 // Modularity resolution, scales the inter objective
+    pub macro_mutation_rate: f64,        // Chance a child gets a merge/split, not node moves
+    pub elitism: bool,                   // Carry the max-Q solution into every next generation
+    pub refine: bool,                    // Local moves on PESA-II's max-Q pick, ignored by NSGA-II
+    // Largest community allowed, None leaves it free; tiny caps can stall convergence
+    pub max_community_size: Option<usize>,
+    // Null model of the inter objective; mutation gains always use Newman's
+    pub modularity_variant: ModularityVariant,
+    // Crossover operator, None keeps the optimizer's own (two-point or ensemble)
+    pub crossover_type: Option<CrossoverType>,
+    // Decay of `mut_rate` over the generations, see `mut_rate_at`
+    pub mut_rate_schedule: MutationSchedule,
+    // Rate reached at the last generation by a decaying schedule
+    pub mut_rate_min: f64,
+    // Fixed (intra, inter) ends of PESA-II's hypergrid, set together; None rescales per archive
+    pub obj_min: Option<(f64, f64)>,
+    pub obj_max: Option<(f64, f64)>,
+    // Draw of the first population, before any warm start replaces part of it
+    pub init_strategy: InitStrategy,
+    // Warm start copied into part of the first population, None starts cold
+    pub initial_partition: Option<Partition>,
+    // Node pairs every partition keeps together / apart, see `enforce_links`
+    pub must_link: Vec<(NodeId, NodeId)>,
+    pub cannot_link: Vec<(NodeId, NodeId)>,
+    // Decimals objectives are rounded to when scored, see `round_objectives`
+    pub round_objectives: Option<u32>,
+}
+
+impl Default for EvolutionConfig {
+    fn default() -> Self {
+        EvolutionConfig {
+            num_gens: 100,
+            pop_size: 100,
+            cross_rate: 0.8,
+            mut_rate: 0.2,
+            seed: None,
+            patience: Some(100),
+            tol: 1e-6,
+            num_threads: None,
+            num_communities: None,
+            time_limit_secs: None,
+            tournament_size: 2,
+            grid_divisions: 8,
+            archive_size: 100,
+            archive_patience: None,
+            resolution: 1.0,
+            macro_mutation_rate: 0.0,
+            elitism: true,
+            refine: false,
+            max_community_size: None,
+            modularity_variant: ModularityVariant::Newman,
+            crossover_type: None,
+            mut_rate_schedule: MutationSchedule::Constant,
+            mut_rate_min: 0.01,
+            obj_min: None,
+            obj_max: None,
+            init_strategy: InitStrategy::Random,
+            initial_partition: None,
+            must_link: Vec::new(),
+            cannot_link: Vec::new(),
+            round_objectives: None,
+        }
+    }
+}
+
+impl EvolutionConfig {
+    /// Base seed for a run: the configured one, or a random one when unset
+    pub fn resolve_seed(&self) -> u64 {
+        self.seed.unwrap_or_else(|| rand::rng().random())
+    }
+
+    /// True once the wall-clock budget, if any, has been used up since `start`
+    pub fn time_exceeded(&self, start: Instant) -> bool {
+        self.time_limit_secs
+            .is_some_and(|limit| start.elapsed().as_secs_f64() >= limit)
+    }
+
+    /// Early-stopping state for a run under this configuration
+    pub fn convergence(&self) -> ConvergenceCriteria {
+        ConvergenceCriteria::new(self.patience, self.tol)
+    }
+
+    /// Node-move mutation rate at `generation` (0-based) under `mut_rate_schedule`
+    pub fn mut_rate_at(&self, generation: usize) -> f64 {
+        // Fraction of the run elapsed, 1.0 at the last generation
+        let progress = (generation as f64 / self.num_gens.saturating_sub(1).max(1) as f64).min(1.0);
+        match self.mut_rate_schedule {
+            MutationSchedule::Constant => self.mut_rate,
+            MutationSchedule::Linear => {
+                self.mut_rate + (self.mut_rate_min - self.mut_rate) * progress
+            }
+            MutationSchedule::Exponential if self.mut_rate > 0.0 => {
+                self.mut_rate * (self.mut_rate_min / self.mut_rate).powf(progress)
+            }
+            MutationSchedule::Exponential => self.mut_rate_min,
+        }
+    }
+
+    /// Puts a node added to the graph in a community of its own in the warm start,
+    /// so it keeps covering every node
+    pub fn extend_initial_partition(&mut self, node: NodeId) {
+        if let Some(partition) = &mut self.initial_partition {
+            let community = partition.values().max().map_or(0, |&max| max + 1);
+            partition.entry(node).or_insert(community);
+        }
+    }
+}
+
+/// Runs `op` on a dedicated pool of `num_threads` workers, or on the global rayon pool
+/// when `None`. Seeded runs give the same result whatever the thread count.
+pub fn install<T: Send>(
+    num_threads: Option<usize>,
+    op: impl FnOnce() -> T + Send,
+) -> Result<T, ThreadPoolBuildError> {
+    match num_threads {
+        Some(num_threads) => Ok(ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()?
+            .install(op)),
+        None => Ok(op()),
+    }
+}
+
+/// Hook called once per generation with the generation index and the best Q
+/// found so far. Returning false stops the run early.
+pub type GenerationCallback<'a> = dyn Fn(usize, f64) -> bool + Sync + 'a;
+
+/// Replacement for the built-in [intra, inter] objectives: scores a partition with
+/// two values, both minimized
+pub type ObjectiveFn<'a> = dyn Fn(&Partition) -> Vec<f64> + Sync + 'a;
+
+/// Outcome of an evolutionary run: the final non-dominated front and the best Q
+/// recorded at each generation that ran
+#[derive(Debug, Clone)]
+pub struct EvolutionResult<T> {
+    pub front: Vec<T>,
+    pub history: Vec<f64>,
+}
+
+/// Returns the RNG used for the `index`-th draw of `generation`. Every draw
+/// gets its own stream, so results do not depend on rayon's scheduling.
+pub fn seeded_rng(seed: u64, generation: usize, index: usize) -> ChaCha8Rng {
+    let mut rng =
+        ChaCha8Rng::seed_from_u64(seed ^ (generation as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    rng.set_stream(index as u64);
+    rng
+}
 
 /// Represents the convergence criteria and state for the genetic algorithm
 #[derive(Debug)]
@@ -36,11 +308,21 @@ impl Default for ConvergenceCriteria {
 }
 
 impl ConvergenceCriteria {
+    /// Stops after `patience` generations without the best fitness improving by
+    /// more than `tolerance`; `None` never stops early
+    pub fn new(patience: Option<usize>, tolerance: f64) -> Self {
+        ConvergenceCriteria {
+            max_stagnant_generations: patience.unwrap_or(usize::MAX),
+            tolerance,
+            ..Default::default()
+        }
+    }
+
     /// Checks if the algorithm has converged based on the latest fitness value
     /// Returns true if convergence criteria are met, false otherwise
     pub fn has_converged(&mut self, new_fitness: f64) -> bool {
         // Check if there's a significant improvement
-        let has_improved = (new_fitness - self.current_best_fitness).abs() > self.tolerance;
+        let has_improved = new_fitness > self.current_best_fitness + self.tolerance;
 
         if has_improved {
             // Reset counter if we found a better solution
@@ -62,16 +344,251 @@ impl ConvergenceCriteria {
     }
 }
 
-pub fn crossover(parent1: &Partition, parent2: &Partition, crossover_rate: f64) -> Partition {
-    crossover::optimized_crossover(parent1, parent2, crossover_rate)
+/// Child of two parents under `kind`; `Ensemble` votes between just the two
+pub fn crossover(
+    parent1: &Partition,
+    parent2: &Partition,
+    kind: CrossoverType,
+    crossover_rate: f64,
+    rng: &mut impl Rng,
+) -> Partition {
+    match kind {
+        CrossoverType::Ensemble => {
+            crossover::ensemble_crossover(&[parent1.clone(), parent2.clone()], crossover_rate, rng)
+        }
+        CrossoverType::Uniform => {
+            crossover::uniform_crossover(parent1, parent2, crossover_rate, rng)
+        }
+        CrossoverType::OnePoint => {
+            crossover::one_point_crossover(parent1, parent2, crossover_rate, rng)
+        }
+        CrossoverType::TwoPoint => {
+            crossover::optimized_crossover(parent1, parent2, crossover_rate, rng)
+        }
+    }
+}
+
+/// Both children of a segment crossover, see `crossover::paired_crossover`
+pub fn paired_crossover(
+    parent1: &Partition,
+    parent2: &Partition,
+    crossover_rate: f64,
+    rng: &mut impl Rng,
+) -> (Partition, Partition) {
+    crossover::paired_crossover(parent1, parent2, crossover_rate, rng)
+}
+
+pub fn mutation(
+    partition: &mut Partition,
+    graph: &Graph,
+    mutation_rate: f64,
+    resolution: f64,
+    rng: &mut impl Rng,
+) {
+    mutation::optimized_mutate(partition, graph, mutation_rate, resolution, rng);
+}
+
+/// Reassigns `kicks` random nodes to random communities, see
+/// `mutation::random_reassign`
+pub fn kick(partition: &mut Partition, kicks: usize, rng: &mut impl Rng) {
+    mutation::random_reassign(partition, kicks, rng);
+}
+
+/// Polishes `partition` with greedy local node moves until none raises Q at
+/// `config.resolution`, see `refine::local_moves`; returns the gain in Q. Moves that
+/// would break `config.max_community_size` or change the number of communities under
+/// `config.num_communities` are skipped.
+pub fn refine_partition(partition: &mut Partition, graph: &Graph, config: &EvolutionConfig) -> f64 {
+    refine::local_moves(
+        partition,
+        graph,
+        config.resolution,
+        config.max_community_size,
+        config.num_communities.is_some(),
+    )
+}
+
+/// Consensus of `partitions` over the same nodes: communities are the connected groups
+/// of node pairs that share a community in more than `threshold` of them, see
+/// `consensus::co_association`
+pub fn consensus_partition(partitions: &[Partition], threshold: f64) -> Partition {
+    consensus::co_association(partitions, threshold)
+}
+
+/// Mutates a child: with probability `config.macro_mutation_rate` one whole community is
+/// merged into a neighbour or split in two (even odds), otherwise nodes move as in
+/// `mutation` at the scheduled rate for `generation`
+pub fn mutate_child(
+    partition: &mut Partition,
+    graph: &Graph,
+    config: &EvolutionConfig,
+    generation: usize,
+    rng: &mut impl Rng,
+) {
+    // A zero rate draws nothing, so seeded runs are unchanged
+    if config.macro_mutation_rate > 0.0 && rng.random_bool(config.macro_mutation_rate) {
+        if rng.random_bool(0.5) {
+            macro_mutation::merge_adjacent(partition, graph, rng);
+        } else {
+            macro_mutation::split_sparsest(partition, graph, rng);
+        }
+    } else {
+        let rate = config.mut_rate_at(generation);
+        mutation(partition, graph, rate, config.resolution, rng);
+    }
+}
+
+/// Repairs `partition` to exactly `config.num_communities` communities, then splits
+/// any community above `config.max_community_size`; each step is a no-op when unset.
+/// The size cap is applied last and wins, so it can leave more than `num_communities`.
+pub fn constrain_communities(
+    partition: &mut Partition,
+    graph: &Graph,
+    config: &EvolutionConfig,
+    rng: &mut impl Rng,
+) {
+    if let Some(k) = config.num_communities {
+        constraint::repair_community_count(partition, graph, k, rng);
+    }
+    if let Some(max_size) = config.max_community_size {
+        constraint::repair_community_size(partition, graph, max_size, rng);
+    }
+    enforce_links(partition, config);
+}
+
+/// Repairs `partition` against the must-link and cannot-link pairs of `config`, see
+/// `constraint::repair_links`. Runs last in `constrain_communities`, so the links hold
+/// even where they cost an exact community count or size cap.
+pub fn enforce_links(partition: &mut Partition, config: &EvolutionConfig) {
+    if !config.must_link.is_empty() || !config.cannot_link.is_empty() {
+        constraint::repair_links(partition, &config.must_link, &config.cannot_link);
+    }
+}
+
+/// Cannot-link pair no partition can satisfy, see `constraint::link_conflict`
+pub fn link_conflict(
+    must_link: &[(NodeId, NodeId)],
+    cannot_link: &[(NodeId, NodeId)],
+) -> Option<(NodeId, NodeId)> {
+    constraint::link_conflict(must_link, cannot_link)
+}
+
+/// Rounds every objective to `decimals` decimal places, so differences below that
+/// precision (from summation order or platform maths) neither decide dominance nor
+/// move a solution between hypergrid boxes. None leaves full precision.
+pub fn round_objectives(objectives: &mut [f64], decimals: Option<u32>) {
+    let Some(decimals) = decimals else {
+        return;
+    };
+    let scale = 10f64.powi(decimals as i32);
+    for objective in objectives {
+        *objective = (*objective * scale).round() / scale;
+    }
+}
+
+/// Hypervolume of a bi-objective front, see `hypervolume::hypervolume_2d`
+pub fn hypervolume<'a>(
+    objectives: impl Iterator<Item = &'a Vec<f64>>,
+    reference: (f64, f64),
+) -> f64 {
+    let points: Vec<(f64, f64)> = objectives.map(|o| (o[0], o[1])).collect();
+    hypervolume::hypervolume_2d(&points, reference)
+}
+
+/// Index of the knee of a bi-objective front, see `knee::knee_point_2d`
+pub fn knee_point<'a>(objectives: impl Iterator<Item = &'a Vec<f64>>) -> Option<usize> {
+    let points: Vec<(f64, f64)> = objectives.map(|o| (o[0], o[1])).collect();
+    knee::knee_point_2d(&points)
+}
+
+/// Overlapping memberships derived from `partition`, see `overlap::soft_memberships`
+pub fn soft_memberships(
+    graph: &Graph,
+    partition: &Partition,
+    threshold: f64,
+) -> BTreeMap<NodeId, Vec<CommunityId>> {
+    overlap::soft_memberships(graph, partition, threshold)
+}
+
+pub fn ensemble_crossover(
+    parents: &[Partition],
+    crossover_rate: f64,
+    rng: &mut impl Rng,
+) -> Partition {
+    crossover::ensemble_crossover(parents, crossover_rate, rng)
 }
 
-pub fn mutation(partition: &mut Partition, graph: &Graph, mutation_rate: f64) {
-    mutation::optimized_mutate(partition, graph, mutation_rate);
+/// Change in Q from moving `node` into community `to`, see `objective::delta_modularity`
+pub fn delta_modularity(
+    graph: &Graph,
+    partition: &HashMap<NodeId, CommunityId, FxBuildHasher>,
+    strengths: &CommunityStrengths,
+    node: NodeId,
+    to: CommunityId,
+    resolution: f64,
+) -> f64 {
+    objective::delta_modularity(graph, partition, strengths, node, to, resolution)
 }
 
-pub fn ensemble_crossover(parents: &[Partition], crossover_rate: f64) -> Partition {
-    crossover::ensemble_crossover(parents, crossover_rate)
+/// Change in Q from merging each adjacent pair of communities, see
+/// `objective::merge_gains`
+pub fn merge_gains(
+    graph: &Graph,
+    partition: &Partition,
+    resolution: f64,
+) -> Vec<((CommunityId, CommunityId), f64)> {
+    objective::merge_gains(graph, partition, resolution)
+}
+
+/// Community of `partition` that a new `node` linked to `neighbors` (unit weights,
+/// pointing from `node` when directed) joins for the largest modularity gain over
+/// staying alone; ties go to the smaller id. When no move gains, returns the unused
+/// id one past the largest. Works on a copy of `graph`, leaving the partition as is.
+pub fn assign_node(
+    graph: &Graph,
+    partition: &Partition,
+    node: NodeId,
+    neighbors: &[NodeId],
+    resolution: f64,
+) -> CommunityId {
+    let alone = partition
+        .values()
+        .max()
+        .map_or(0, |&community| community + 1);
+    let mut extended = graph.clone();
+    extended.add_node(node);
+    for &neighbor in neighbors {
+        if neighbor != node && !extended.neighbors(&node).contains(&neighbor) {
+            extended.add_edge(node, neighbor);
+        }
+    }
+
+    let mut fast_partition: HashMap<NodeId, CommunityId, FxBuildHasher> = partition
+        .iter()
+        .map(|(&node, &community)| (node, community))
+        .collect();
+    fast_partition.insert(node, alone);
+    let strengths = CommunityStrengths::new(&extended, &fast_partition);
+
+    let candidates: BTreeSet<CommunityId> = neighbors
+        .iter()
+        .filter_map(|neighbor| partition.get(neighbor).copied())
+        .collect();
+    let mut best = (alone, 0.0);
+    for community in candidates {
+        let gain = delta_modularity(
+            &extended,
+            &fast_partition,
+            &strengths,
+            node,
+            community,
+            resolution,
+        );
+        if gain > best.1 {
+            best = (community, gain);
+        }
+    }
+    best.0
 }
 
 pub fn get_fitness(
@@ -79,19 +596,260 @@ pub fn get_fitness(
     partition: &Partition,
     degrees: &HashMap<i32, usize, FxBuildHasher>,
     parallel: bool,
+    resolution: f64,
+    variant: ModularityVariant,
 ) -> metrics::Metrics {
-    objective::calculate_objectives(graph, partition, degrees, parallel)
+    let newman =
+        || objective::calculate_objectives(graph, partition, degrees, parallel, resolution);
+    match variant {
+        ModularityVariant::Newman => newman(),
+        ModularityVariant::Dcsbm => objective::degree_corrected_objectives(newman(), resolution),
+        ModularityVariant::Bipartite => {
+            objective::bipartite_objectives(graph, partition, resolution)
+        }
+    }
+}
+
+pub fn generate_population(
+    graph: &Graph,
+    population_size: usize,
+    strategy: InitStrategy,
+    rng: &mut impl Rng,
+) -> Vec<Partition> {
+    match strategy {
+        InitStrategy::Random => {
+            population::generate_initial_population(graph, population_size, rng)
+        }
+        InitStrategy::LabelPropagation => {
+            population::generate_label_propagation_population(graph, population_size, rng)
+        }
+    }
+    // population::generate_optimized_population(graph, population_size, rng)
+}
+
+/// Both objectives and their combined Q for a partition, without precomputed degrees.
+/// `resolution` multiplies the expected-edges (inter) term, see `objective::calculate_objectives`,
+/// and `variant` picks its null model
+pub fn get_metrics_from_partition(
+    partition: &Partition,
+    graph: &Graph,
+    resolution: f64,
+    variant: ModularityVariant,
+) -> Metrics {
+    let degrees = graph.precompute_degrees();
+    get_fitness(graph, partition, &degrees, false, resolution, variant)
 }
 
-pub fn generate_population(graph: &Graph, population_size: usize) -> Vec<Partition> {
-    population::generate_initial_population(graph, population_size)
-    // population::generate_optimized_population(graph, population_size)
+/// Every node in its own community. The optimizers return it alone for graphs without
+/// edges, where all partitions tie at Q = 0 and there is nothing to evolve.
+pub fn singleton_partition(graph: &Graph) -> Partition {
+    graph
+        .sorted_nodes()
+        .into_iter()
+        .map(|node| (node, node))
+        .collect()
+}
+
+/// Overwrites the leading `WARM_START_SHARE` of `population` (at least one member)
+/// with copies of `initial_partition`; no-op when `None`
+pub fn warm_start(population: &mut [Partition], initial_partition: Option<&Partition>) {
+    let Some(initial_partition) = initial_partition else {
+        return;
+    };
+    let count = ((population.len() as f64 * WARM_START_SHARE).ceil() as usize).max(1);
+    for partition in population.iter_mut().take(count) {
+        partition.clone_from(initial_partition);
+    }
 }
 
 #[allow(dead_code)]
-pub fn get_modularity_from_partition(partition: &Partition, graph: &Graph) -> f64 {
-    let metrics: Metrics =
-        objective::calculate_objectives(graph, partition, &graph.precompute_degrees(), false);
+pub fn get_modularity_from_partition(partition: &Partition, graph: &Graph, resolution: f64) -> f64 {
+    get_metrics_from_partition(partition, graph, resolution, ModularityVariant::Newman)
+        .get_modularity()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
 
-    metrics.get_modularity()
+    #[test]
+    fn test_convergence_patience() {
+        let mut criteria = ConvergenceCriteria::new(Some(3), 1e-3);
+
+        assert!(!criteria.has_converged(0.5));
+        assert!(!criteria.has_converged(0.5005)); // within tolerance
+        assert!(!criteria.has_converged(0.4));
+        assert!(criteria.has_converged(0.5));
+        assert_eq!(criteria.get_best_fitness(), 0.5);
+    }
+
+    #[test]
+    fn test_convergence_without_patience() {
+        let mut criteria = ConvergenceCriteria::new(None, 1e-3);
+        assert!((0..1000).all(|_| !criteria.has_converged(0.5)));
+    }
+
+    #[test]
+    fn test_linear_schedule_reaches_min_at_last_generation() {
+        let config = EvolutionConfig {
+            num_gens: 50,
+            mut_rate: 0.4,
+            mut_rate_min: 0.05,
+            mut_rate_schedule: MutationSchedule::Linear,
+            ..Default::default()
+        };
+
+        assert_eq!(config.mut_rate_at(0), 0.4);
+        assert!(config.mut_rate_at(25) < config.mut_rate_at(24));
+        assert!((config.mut_rate_at(49) - 0.05).abs() < 1e-12);
+
+        let exponential = EvolutionConfig {
+            mut_rate_schedule: MutationSchedule::Exponential,
+            ..config.clone()
+        };
+        assert!((exponential.mut_rate_at(49) - 0.05).abs() < 1e-12);
+        assert!(exponential.mut_rate_at(25) < config.mut_rate_at(25));
+    }
+
+    #[test]
+    fn test_one_point_crossover_keeps_a_segment_of_each_parent() {
+        let parent1: Partition = (0..10).map(|node| (node, 0)).collect();
+        let parent2: Partition = (0..10).map(|node| (node, 1)).collect();
+
+        for index in 0..20 {
+            let mut rng = seeded_rng(3, 0, index);
+            let child = crossover(&parent1, &parent2, CrossoverType::OnePoint, 1.0, &mut rng);
+            let communities: Vec<CommunityId> = child.values().copied().collect();
+
+            // A non-empty prefix of parent1, then a non-empty suffix of parent2
+            let cut = communities.iter().position(|&c| c == 1).unwrap();
+            assert!(cut > 0);
+            assert!(communities[cut..].iter().all(|&c| c == 1));
+        }
+    }
+
+    #[test]
+    fn test_crossover_types_cover_every_node() {
+        let parent1: Partition = (0..10).map(|node| (node, node / 5)).collect();
+        let parent2: Partition = (0..10).map(|node| (node, node % 3)).collect();
+        let mut rng = seeded_rng(5, 0, 0);
+
+        for name in ["ensemble", "uniform", "one_point", "two_point"] {
+            let kind = CrossoverType::parse(name).unwrap();
+            let child = crossover(&parent1, &parent2, kind, 1.0, &mut rng);
+            assert_eq!(child.len(), 10);
+            assert!(
+                child
+                    .iter()
+                    .all(|(node, c)| parent1[node] == *c || parent2[node] == *c)
+            );
+        }
+        assert_eq!(CrossoverType::parse("three_point"), None);
+    }
+
+    fn two_triangles() -> Graph {
+        let mut graph = Graph::new();
+        for (u, v) in [(0, 1), (1, 2), (0, 2), (3, 4), (4, 5), (3, 5), (2, 3)] {
+            graph.add_edge(u, v);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_zero_rates_are_identity() {
+        let graph = two_triangles();
+        let parent1: Partition = (0..6).map(|node| (node, node / 3)).collect();
+        let parent2: Partition = (0..6).map(|node| (node, node % 2)).collect();
+        let mut rng = seeded_rng(9, 0, 0);
+
+        for _ in 0..20 {
+            let children = paired_crossover(&parent1, &parent2, 0.0, &mut rng);
+            assert_eq!(children, (parent1.clone(), parent2.clone()));
+
+            let mut mutated = parent2.clone();
+            mutation(&mut mutated, &graph, 0.0, 1.0, &mut rng);
+            assert_eq!(mutated, parent2);
+        }
+    }
+
+    #[test]
+    fn test_paired_crossover_children_are_complementary() {
+        let parent1: Partition = (0..10).map(|node| (node, 0)).collect();
+        let parent2: Partition = (0..10).map(|node| (node, 1)).collect();
+        let mut rng = seeded_rng(9, 0, 0);
+
+        let (child1, child2) = paired_crossover(&parent1, &parent2, 1.0, &mut rng);
+        assert!(child1.values().any(|&c| c == 1));
+        for node in 0..10 {
+            assert_eq!(child1[&node] + child2[&node], 1);
+        }
+    }
+
+    #[test]
+    fn test_components_recombine_into_modularity() {
+        let graph = two_triangles();
+        let partition: Partition = (0..6).map(|node| (node, node / 3)).collect();
+
+        let metrics =
+            get_metrics_from_partition(&partition, &graph, 1.0, ModularityVariant::Newman);
+        let q = get_modularity_from_partition(&partition, &graph, 1.0);
+        assert!((1.0 - metrics.intra - metrics.inter - q).abs() < 1e-12);
+        assert!(metrics.intra > 0.0 && metrics.inter > 0.0);
+    }
+
+    #[test]
+    fn test_unit_weights_reproduce_modularity() {
+        let graph = two_triangles();
+        let partition: Partition = (0..6).map(|node| (node, node / 3)).collect();
+
+        let metrics =
+            get_metrics_from_partition(&partition, &graph, 1.0, ModularityVariant::Newman);
+        assert_eq!(
+            metrics.weighted_modularity(1.0, 1.0),
+            get_modularity_from_partition(&partition, &graph, 1.0)
+        );
+        assert_eq!(metrics.weighted_modularity(0.0, 0.0), 1.0);
+        assert!(metrics.weighted_modularity(2.0, 1.0) < metrics.get_modularity());
+    }
+
+    /// Two triangles tied by three edges, hanging off a 5-clique by a single edge
+    fn borderline_triangles() -> Graph {
+        let mut graph = two_triangles();
+        graph.add_edge(0, 4);
+        graph.add_edge(1, 5);
+        for i in 6..11 {
+            for j in (i + 1)..11 {
+                graph.add_edge(i, j);
+            }
+        }
+        graph.add_edge(2, 6);
+        graph
+    }
+
+    #[test]
+    fn test_assign_node_bridging_two_communities() {
+        let graph = two_triangles();
+        let partition: Partition = (0..6).map(|node| (node, node / 3)).collect();
+
+        // Two links into the second triangle outweigh one into the first
+        assert_eq!(assign_node(&graph, &partition, 6, &[2, 3, 4], 1.0), 1);
+        assert_eq!(assign_node(&graph, &partition, 6, &[0, 1, 3], 1.0), 0);
+        // An even bridge goes to the smaller id; no neighbors starts a new community
+        assert_eq!(assign_node(&graph, &partition, 6, &[2, 3], 1.0), 0);
+        assert_eq!(assign_node(&graph, &partition, 6, &[], 1.0), 2);
+        assert_eq!(partition.len(), 6);
+    }
+
+    #[test]
+    fn test_higher_resolution_splits_borderline_community() {
+        let graph = borderline_triangles();
+        let merged: Partition = (0..11).map(|node| (node, (node >= 6) as i32)).collect();
+        let split: Partition = (0..11).map(|node| (node, (node / 3).min(2))).collect();
+
+        let q = |partition: &Partition, resolution| {
+            get_modularity_from_partition(partition, &graph, resolution)
+        };
+        assert!(q(&merged, 1.0) > q(&split, 1.0));
+        assert!(q(&merged, 2.0) < q(&split, 2.0));
+    }
 }
@@ -5,56 +5,103 @@
 //! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
 
 use crate::graph::{CommunityId, Graph, NodeId, Partition};
+use crate::operators::objective::{CommunityStrengths, delta_modularity};
 
 use rand::Rng;
-use rustc_hash::FxBuildHasher;
 use rustc_hash::FxHashMap as HashMap;
 
-pub fn optimized_mutate(partition: &mut Partition, graph: &Graph, mutation_rate: f64) {
-    let mut rng = rand::rng();
-
-    // Convert BTreeMap to a faster hash map for the duration of the mutation
-    let partition_size = partition.len();
-    let mut fast_partition: HashMap<NodeId, CommunityId> =
-        HashMap::with_capacity_and_hasher(partition_size, Default::default());
-    fast_partition.extend(partition.iter().map(|(&k, &v)| (k, v)));
-
+/// Moves each node, with probability `mutation_rate`, to the most common community among
+/// its neighbours, unless that lowers Q. Moves are scored incrementally with
+/// `delta_modularity`, so each check costs O(degree) instead of a full recompute.
+/// Q is taken at the given `resolution`.
+pub fn optimized_mutate(
+    partition: &mut Partition,
+    graph: &Graph,
+    mutation_rate: f64,
+    resolution: f64,
+    rng: &mut impl Rng,
+) {
     // Pre-calculate nodes to mutate
-    let nodes: Vec<NodeId> = fast_partition
+    let nodes: Vec<NodeId> = partition
         .keys()
         .copied()
         .filter(|_| rng.random_bool(mutation_rate))
         .collect();
+    if nodes.is_empty() {
+        return;
+    }
 
-    // Pre-allocate community cache with expected size
-    let mut community_cache: HashMap<NodeId, HashMap<CommunityId, usize>> =
-        HashMap::with_capacity_and_hasher(nodes.len(), FxBuildHasher);
-
-    // Process nodes in batches for better cache locality
-    const BATCH_SIZE: usize = 64;
-    for node_chunk in nodes.chunks(BATCH_SIZE) {
-        for &node in node_chunk {
-            let neighbor_communities = community_cache.entry(node).or_insert_with(|| {
-                let mut freq = HashMap::default();
-                if let Some(neighbors) = graph.adjacency_list.get(&node) {
-                    freq.reserve(neighbors.len());
-                    for &neighbor in neighbors {
-                        if let Some(&community) = fast_partition.get(&neighbor) {
-                            *freq.entry(community).or_insert(0) += 1;
-                        }
-                    }
-                }
-                freq
-            });
-
-            if let Some((&new_community, _)) =
-                neighbor_communities.iter().max_by_key(|&(_, count)| count)
-            {
-                fast_partition.insert(node, new_community);
+    // Convert BTreeMap to a faster hash map for the duration of the mutation
+    let mut fast_partition: HashMap<NodeId, CommunityId> =
+        HashMap::with_capacity_and_hasher(partition.len(), Default::default());
+    fast_partition.extend(partition.iter().map(|(&k, &v)| (k, v)));
+    let mut strengths = CommunityStrengths::new(graph, &fast_partition);
+
+    let mut neighbor_communities: HashMap<CommunityId, usize> = HashMap::default();
+    for node in nodes {
+        neighbor_communities.clear();
+        for neighbor in graph.neighbors(&node) {
+            if let Some(&community) = fast_partition.get(neighbor) {
+                *neighbor_communities.entry(community).or_insert(0) += 1;
             }
         }
+
+        let current = fast_partition[&node];
+        if let Some((&target, _)) = neighbor_communities.iter().max_by_key(|&(_, count)| count)
+            && target != current
+            && delta_modularity(graph, &fast_partition, &strengths, node, target, resolution) >= 0.0
+        {
+            strengths.move_node(graph, node, current, target);
+            fast_partition.insert(node, target);
+        }
     }
+
     // Update
     partition.clear();
     partition.extend(fast_partition);
 }
+
+/// Moves `kicks` distinct nodes (every node when `kicks` exceeds the count), drawn
+/// uniformly, each to a uniformly drawn community among the ones in use plus one fresh
+/// id. A node may draw its own community, so up to `kicks` nodes change.
+pub fn random_reassign(partition: &mut Partition, kicks: usize, rng: &mut impl Rng) {
+    let nodes: Vec<NodeId> = partition.keys().copied().collect();
+    let mut communities: Vec<CommunityId> = partition.values().copied().collect();
+    communities.sort_unstable();
+    communities.dedup();
+    let Some(&last) = communities.last() else {
+        return;
+    };
+    communities.push(last + 1);
+
+    for index in rand::seq::index::sample(rng, nodes.len(), kicks.min(nodes.len())) {
+        let community = communities[rng.random_range(0..communities.len())];
+        partition.insert(nodes[index], community);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_random_reassign_moves_at_most_kicks_nodes() {
+        let partition: Partition = (0..20).map(|node| (node, node / 5)).collect();
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+        for kicks in [0, 3, 50] {
+            let mut kicked = partition.clone();
+            random_reassign(&mut kicked, kicks, &mut rng);
+            let moved = (0..20)
+                .filter(|node| kicked[node] != partition[node])
+                .count();
+            assert!(moved <= kicks);
+            assert!(
+                kicked
+                    .values()
+                    .all(|&community| (0..=4).contains(&community))
+            );
+        }
+    }
+}
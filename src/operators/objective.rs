@@ -1,18 +1,33 @@
 //! operators/objective.rs
-use crate::graph::{Graph, NodeId, Partition};
+use crate::graph::{CommunityId, Graph, NodeId, Partition};
 use crate::operators::metrics::Metrics;
 use rayon::iter::*;
 use rustc_hash::FxHashMap as HashMap;
+use std::collections::BTreeMap;
 
+/// Splits Q into intra = 1 - L_in / m and inter = resolution * sum_c (D_c / 2m)^2, so
+/// that Q = 1 - intra - inter. A resolution above 1 weighs the expected edges more and
+/// favours more, smaller communities; below 1 favours fewer, larger ones. With node
+/// weights, each degree in D_c is scaled by its node's weight and 2m becomes the total
+/// weighted volume, so scaling every node weight alike leaves Q unchanged.
 pub fn calculate_objectives(
     graph: &Graph,
     partition: &Partition,
     degrees: &HashMap<NodeId, usize>,
     parallel: bool,
+    resolution: f64,
 ) -> Metrics {
-    let total_edges = graph.edges.len() as f64;
+    let total_edges = graph.total_weight();
     if total_edges == 0.0 {
-        return Metrics::default();
+        // No edge can fall inside a community, so every partition has Q = 0
+        return Metrics {
+            modularity: 0.0,
+            intra: 1.0,
+            inter: 0.0,
+        };
+    }
+    if graph.directed {
+        return oriented_objectives(graph, partition, &graph.edges, resolution);
     }
 
     // Build communities with HashSet for fast lookups
@@ -27,29 +42,42 @@ pub fn calculate_objectives(
         .map(|(&node, &comm)| (node, comm))
         .collect();
 
-    let total_edges_doubled = 2.0 * total_edges;
+    let total_edges_doubled = weighted_volume(graph, degrees, total_edges);
+    let weighted = graph.is_weighted();
 
     let folder = |(mut intra_acc, mut inter_acc), (_, nodes): (&i32, &Vec<NodeId>)| {
         let mut community_edges = 0.0;
         let mut community_degree = 0.0;
 
-        // Calculate community degree in one pass
-        for &node in nodes {
-            let degree = *degrees.get(&node).unwrap_or(&0) as f64;
-            community_degree += degree;
+        // Calculate community degree in one pass (strength when weighted)
+        if !weighted {
+            for &node in nodes {
+                let degree = *degrees.get(&node).unwrap_or(&0) as f64;
+                community_degree += degree * graph.node_weight(node);
+            }
         }
 
         // Count intra-community edges more efficiently
         for &node in nodes {
             if let Some(neighbors) = graph.adjacency_list.get(&node) {
                 for &neighbor in neighbors {
-                    // Only count edges once (when source < target)
-                    if node < neighbor {
-                        if let Some(neighbor_comm) = node_to_community.get(&neighbor) {
-                            if neighbor_comm == &node_to_community[&node] {
-                                community_edges += 1.0;
-                            }
-                        }
+                    let weight = if weighted {
+                        graph.weight(node, neighbor)
+                    } else {
+                        1.0
+                    };
+                    if weighted {
+                        community_degree += weight * graph.node_weight(node);
+                    }
+
+                    // Only count edges once (when source < target). A self-loop is listed
+                    // twice in the adjacency list, so each listing counts half
+                    if node == neighbor {
+                        community_edges += weight / 2.0;
+                    } else if node < neighbor
+                        && node_to_community.get(&neighbor) == Some(&node_to_community[&node])
+                    {
+                        community_edges += weight;
                     }
                 }
             }
@@ -61,15 +89,20 @@ pub fn calculate_objectives(
     };
 
     let (intra_sum, inter) = if parallel && communities.len() > 8 {
+        // Evaluate communities in parallel but sum them in a fixed order, so the
+        // result is bit-for-bit the same as the sequential path
         communities
             .par_iter()
-            .fold(|| (0.0, 0.0), folder)
-            .reduce(|| (0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1))
+            .map(|community| folder((0.0, 0.0), community))
+            .collect::<Vec<(f64, f64)>>()
+            .into_iter()
+            .fold((0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1))
     } else {
         communities.iter().fold((0.0, 0.0), folder)
     };
 
     let intra = 1.0 - (intra_sum / total_edges);
+    let inter = resolution * inter;
     let modularity = 1.0 - intra - inter;
 
     Metrics {
@@ -78,3 +111,572 @@ pub fn calculate_objectives(
         inter,
     }
 }
+
+/// 2m, or the sum of node-weighted degrees (strengths when weighted) once any node
+/// weight is set
+fn weighted_volume(graph: &Graph, degrees: &HashMap<NodeId, usize>, total_edges: f64) -> f64 {
+    if graph.node_weights.is_empty() {
+        return 2.0 * total_edges;
+    }
+    graph
+        .nodes
+        .iter()
+        .map(|&node| {
+            let strength = if graph.is_weighted() {
+                graph
+                    .neighbors(&node)
+                    .iter()
+                    .map(|&neighbor| graph.weight(node, neighbor))
+                    .sum()
+            } else {
+                *degrees.get(&node).unwrap_or(&0) as f64
+            };
+            strength * graph.node_weight(node)
+        })
+        .sum()
+}
+
+/// m^2 over the `oriented` edges, or the product of their total node-weighted out- and
+/// in-strengths once any node weight is set
+fn directed_volume(graph: &Graph, oriented: &[(NodeId, NodeId)], total: f64) -> f64 {
+    if graph.node_weights.is_empty() {
+        return total * total;
+    }
+    let (out_volume, in_volume) = oriented.iter().fold((0.0, 0.0), |(out, inn), &(u, v)| {
+        let weight = graph.weight(u, v);
+        (
+            out + weight * graph.node_weight(u),
+            inn + weight * graph.node_weight(v),
+        )
+    });
+    out_volume * in_volume
+}
+
+/// Leicht-Newman directed modularity of the `oriented` (source, target) edges, split
+/// the same way as the undirected objectives: intra = 1 - L_in / m and inter =
+/// sum_c Out_c * In_c / m^2, where L_in is the weight of edges inside communities and
+/// Out_c / In_c the summed out- and in-strengths of c. `resolution` scales inter as in
+/// the undirected case. Node weights scale the strengths, and m^2 becomes the product
+/// of the total weighted out- and in-strengths.
+fn oriented_objectives(
+    graph: &Graph,
+    partition: &Partition,
+    oriented: &[(NodeId, NodeId)],
+    resolution: f64,
+) -> Metrics {
+    let mut strengths: BTreeMap<CommunityId, (f64, f64)> = BTreeMap::new();
+    let mut intra_sum = 0.0;
+    let mut total = 0.0;
+
+    for &(from, to) in oriented {
+        let weight = graph.weight(from, to);
+        let (source, target) = (partition.get(&from), partition.get(&to));
+        if let Some(&source) = source {
+            strengths.entry(source).or_default().0 += weight * graph.node_weight(from);
+        }
+        if let Some(&target) = target {
+            strengths.entry(target).or_default().1 += weight * graph.node_weight(to);
+        }
+        if source.is_some() && source == target {
+            intra_sum += weight;
+        }
+        total += weight;
+    }
+
+    let inter: f64 = resolution
+        * strengths
+            .values()
+            .map(|&(out_strength, in_strength)| out_strength * in_strength)
+            .sum::<f64>()
+        / directed_volume(graph, oriented, total);
+    let intra = 1.0 - intra_sum / total;
+
+    Metrics {
+        modularity: 1.0 - intra - inter,
+        intra,
+        inter,
+    }
+}
+
+/// Barber's bipartite modularity over the two sides in `graph.node_types`: edges are
+/// oriented from side 0 to side 1 and scored as in `oriented_objectives`, so inter =
+/// sum_c R_c * B_c / m^2 with R_c and B_c the strengths of c's nodes on each side.
+/// Edges within a side, or touching a node without a side, are left out of every
+/// term; without cross-side edges every partition scores Q = 0.
+pub fn bipartite_objectives(graph: &Graph, partition: &Partition, resolution: f64) -> Metrics {
+    let oriented: Vec<(NodeId, NodeId)> = graph
+        .edges
+        .iter()
+        .filter_map(|&(u, v)| match (graph.node_type(u), graph.node_type(v)) {
+            (Some(0), Some(1)) => Some((u, v)),
+            (Some(1), Some(0)) => Some((v, u)),
+            _ => None,
+        })
+        .collect();
+    if oriented.is_empty() {
+        return Metrics {
+            modularity: 0.0,
+            intra: 1.0,
+            inter: 0.0,
+        };
+    }
+    oriented_objectives(graph, partition, &oriented, resolution)
+}
+
+/// Objectives under the planted-partition degree-corrected SBM (Newman, 2016), from
+/// the Newman `metrics` computed at `resolution`. Fitting the block model to the
+/// partition gives the edge rates w_in = f / p inside and w_out = (1 - f) / (1 - p)
+/// between communities, with f = L_in / m and p = sum_c (D_c / 2m)^2 the expected
+/// share; inter is then scaled by gamma = (w_in - w_out) / (ln w_in - ln w_out).
+/// Without edges or with a single community there is no between-community rate to
+/// fit, and the Newman objectives are returned unchanged.
+pub fn degree_corrected_objectives(metrics: Metrics, resolution: f64) -> Metrics {
+    let inside = 1.0 - metrics.intra;
+    let expected = metrics.inter / resolution;
+    if expected <= 0.0 || 1.0 - expected < 1e-12 {
+        return metrics;
+    }
+
+    let rate_in = inside / expected;
+    let rate_out = (1.0 - inside) / (1.0 - expected);
+    let gamma = if rate_in == 0.0 || rate_out == 0.0 {
+        // ln 0 = -inf: no edges on one side leaves nothing to correct for
+        0.0
+    } else if (rate_in - rate_out).abs() < 1e-12 {
+        rate_in
+    } else {
+        (rate_in - rate_out) / (rate_in.ln() - rate_out.ln())
+    };
+    let inter = gamma * metrics.inter;
+
+    Metrics {
+        modularity: 1.0 - metrics.intra - inter,
+        intra: metrics.intra,
+        inter,
+    }
+}
+
+/// Sum of node strengths (degrees when unweighted) per community, kept in step
+/// with the partition so `delta_modularity` does not need to rescan the graph.
+/// Entries are (out, in) strengths; both equal the plain strength when undirected.
+#[derive(Debug)]
+pub struct CommunityStrengths {
+    pub total_weight: f64,
+    strengths: HashMap<CommunityId, (f64, f64)>,
+}
+
+impl CommunityStrengths {
+    pub fn new(graph: &Graph, partition: &HashMap<NodeId, CommunityId>) -> Self {
+        let mut strengths: HashMap<CommunityId, (f64, f64)> = HashMap::default();
+        for (&node, &community) in partition {
+            let (out_strength, in_strength) = node_strengths(graph, node);
+            let entry = strengths.entry(community).or_default();
+            entry.0 += out_strength;
+            entry.1 += in_strength;
+        }
+        CommunityStrengths {
+            total_weight: graph.total_weight(),
+            strengths,
+        }
+    }
+
+    pub fn get(&self, community: CommunityId) -> (f64, f64) {
+        self.strengths.get(&community).copied().unwrap_or_default()
+    }
+
+    /// Records that `node` moved from `from` to `to`
+    pub fn move_node(&mut self, graph: &Graph, node: NodeId, from: CommunityId, to: CommunityId) {
+        let (out_strength, in_strength) = node_strengths(graph, node);
+        let source = self.strengths.entry(from).or_default();
+        source.0 -= out_strength;
+        source.1 -= in_strength;
+        let target = self.strengths.entry(to).or_default();
+        target.0 += out_strength;
+        target.1 += in_strength;
+    }
+}
+
+/// (out, in) weighted degree of a node; both are the plain strength on undirected
+/// graphs, where a self-loop counts twice
+fn node_strengths(graph: &Graph, node: NodeId) -> (f64, f64) {
+    if graph.directed {
+        let out_strength = graph
+            .successors(&node)
+            .iter()
+            .map(|&target| graph.weight(node, target))
+            .sum();
+        let in_strength = graph
+            .predecessors(&node)
+            .iter()
+            .map(|&source| graph.weight(source, node))
+            .sum();
+        return (out_strength, in_strength);
+    }
+
+    let strength = if graph.is_weighted() {
+        graph
+            .neighbors(&node)
+            .iter()
+            .map(|&neighbor| graph.weight(node, neighbor))
+            .sum()
+    } else {
+        graph.neighbors(&node).len() as f64
+    };
+    (strength, strength)
+}
+
+/// Change in Q from moving `node` out of its current community into `to`, in O(degree).
+///
+/// With k_i the node strength, k_i,C its link weight into community C (itself excluded)
+/// and D_C the community strengths before the move, undirected graphs use
+/// dQ = (k_i,C=to - k_i,C=from) / m - k_i * (D_to - D_from + k_i) / (2m^2).
+/// Directed graphs count links both ways into C and split strengths into out/in:
+/// dQ = (l_to - l_from) / m - (o_i (In_to - In_from) + i_i (Out_to - Out_from) + 2 o_i i_i) / m^2
+/// In both cases `resolution` multiplies the second (expected edges) term.
+pub fn delta_modularity(
+    graph: &Graph,
+    partition: &HashMap<NodeId, CommunityId>,
+    strengths: &CommunityStrengths,
+    node: NodeId,
+    to: CommunityId,
+    resolution: f64,
+) -> f64 {
+    let from = partition[&node];
+    let m = strengths.total_weight;
+    if from == to || m == 0.0 {
+        return 0.0;
+    }
+
+    let (mut links_from, mut links_to) = (0.0, 0.0);
+    let mut count_link = |neighbor: NodeId, weight: f64| {
+        if neighbor == node {
+            return;
+        }
+        match partition.get(&neighbor) {
+            Some(&community) if community == from => links_from += weight,
+            Some(&community) if community == to => links_to += weight,
+            _ => {}
+        }
+    };
+
+    if graph.directed {
+        for &target in graph.successors(&node) {
+            count_link(target, graph.weight(node, target));
+        }
+        for &source in graph.predecessors(&node) {
+            count_link(source, graph.weight(source, node));
+        }
+    } else {
+        for &neighbor in graph.neighbors(&node) {
+            count_link(neighbor, graph.weight(node, neighbor));
+        }
+    }
+
+    let (out_strength, in_strength) = node_strengths(graph, node);
+    let (out_from, in_from) = strengths.get(from);
+    let (out_to, in_to) = strengths.get(to);
+    if graph.directed {
+        (links_to - links_from) / m
+            - resolution
+                * (out_strength * (in_to - in_from)
+                    + in_strength * (out_to - out_from)
+                    + 2.0 * out_strength * in_strength)
+                / (m * m)
+    } else {
+        (links_to - links_from) / m
+            - resolution * out_strength * (out_to - out_from + out_strength) / (2.0 * m * m)
+    }
+}
+
+/// Change in Q from merging each pair of communities joined by at least one edge, as
+/// `((a, b), dQ)` with a < b, in ascending pair order. With w_ab the weight between
+/// the two and D the community strengths, undirected graphs use
+/// dQ = w_ab / m - D_a D_b / (2m^2); directed graphs count edges both ways in w_ab and
+/// use dQ = w_ab / m - (Out_a In_b + Out_b In_a) / m^2. `resolution` multiplies the
+/// second term, and node weights are ignored as in `delta_modularity`.
+pub fn merge_gains(
+    graph: &Graph,
+    partition: &Partition,
+    resolution: f64,
+) -> Vec<((CommunityId, CommunityId), f64)> {
+    let m = graph.total_weight();
+    let mut between: BTreeMap<(CommunityId, CommunityId), f64> = BTreeMap::new();
+    for &(from, to) in &graph.edges {
+        if let (Some(&a), Some(&b)) = (partition.get(&from), partition.get(&to))
+            && a != b
+        {
+            *between.entry((a.min(b), a.max(b))).or_insert(0.0) += graph.weight(from, to);
+        }
+    }
+
+    let fast_partition: HashMap<NodeId, CommunityId> = partition
+        .iter()
+        .map(|(&node, &community)| (node, community))
+        .collect();
+    let strengths = CommunityStrengths::new(graph, &fast_partition);
+    between
+        .into_iter()
+        .map(|((a, b), weight)| {
+            let (out_a, in_a) = strengths.get(a);
+            let (out_b, in_b) = strengths.get(b);
+            let expected = if graph.directed {
+                (out_a * in_b + out_b * in_a) / (m * m)
+            } else {
+                out_a * out_b / (2.0 * m * m)
+            };
+            ((a, b), weight / m - resolution * expected)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn triangle(weights: [f64; 3]) -> Graph {
+        let mut graph = Graph::new();
+        graph.add_weighted_edge(0, 1, weights[0]);
+        graph.add_weighted_edge(1, 2, weights[1]);
+        graph.add_weighted_edge(0, 2, weights[2]);
+        graph
+    }
+
+    #[test]
+    fn test_weighted_triangle() {
+        let graph = triangle([2.0, 1.0, 1.0]);
+        let partition: Partition = [(0, 0), (1, 0), (2, 1)].into_iter().collect();
+
+        // m = 4, intra weight = 2, strengths per community = 6 and 2
+        let metrics =
+            calculate_objectives(&graph, &partition, &graph.precompute_degrees(), false, 1.0);
+        assert!((metrics.intra - 0.5).abs() < 1e-12);
+        assert!((metrics.inter - 0.625).abs() < 1e-12);
+        assert!((metrics.modularity + 0.125).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_degree_corrected_objectives_on_hub_graph() {
+        // Hub 0 linked to the triangles 1-2-3 and 4-5-6. With the hub in the first
+        // block: m = 12, L_in = 9, block degrees 15 and 9, so f = 3/4, p = 17/32,
+        // w_in = 24/17, w_out = 8/15
+        let mut graph = Graph::new();
+        for node in 1..7 {
+            graph.add_edge(0, node);
+        }
+        for (u, v) in [(1, 2), (2, 3), (1, 3), (4, 5), (5, 6), (4, 6)] {
+            graph.add_edge(u, v);
+        }
+        let partition: Partition = [(0, 0), (1, 0), (2, 0), (3, 0), (4, 1), (5, 1), (6, 1)]
+            .into_iter()
+            .collect();
+
+        let newman =
+            calculate_objectives(&graph, &partition, &graph.precompute_degrees(), false, 1.0);
+        let (rate_in, rate_out) = (24.0_f64 / 17.0, 8.0_f64 / 15.0);
+        let gamma = (rate_in - rate_out) / (rate_in.ln() - rate_out.ln());
+        let dcsbm = degree_corrected_objectives(newman, 1.0);
+
+        assert!((newman.modularity - 7.0 / 32.0).abs() < 1e-12);
+        assert_eq!(dcsbm.intra, newman.intra);
+        assert!((dcsbm.inter - gamma * 17.0 / 32.0).abs() < 1e-12);
+        assert!(dcsbm.modularity > newman.modularity);
+
+        let whole: Partition = (0..7).map(|node| (node, 0)).collect();
+        let single = calculate_objectives(&graph, &whole, &graph.precompute_degrees(), false, 1.0);
+        assert_eq!(degree_corrected_objectives(single, 1.0), single);
+    }
+
+    #[test]
+    fn test_unit_weights_match_unweighted() {
+        let graph = triangle([1.0, 1.0, 1.0]);
+        let partition: Partition = [(0, 0), (1, 0), (2, 1)].into_iter().collect();
+
+        assert!(!graph.is_weighted());
+        let metrics =
+            calculate_objectives(&graph, &partition, &graph.precompute_degrees(), false, 1.0);
+        assert!((metrics.modularity + 2.0 / 9.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_node_weights_are_scale_invariant() {
+        let partition: Partition = [(0, 0), (1, 0), (2, 1)].into_iter().collect();
+        let q = |graph: &Graph| {
+            calculate_objectives(graph, &partition, &graph.precompute_degrees(), false, 1.0)
+        };
+
+        // Weighted degrees 4, 2, 2: volume 8, community volumes 6 and 2
+        let mut graph = triangle([1.0, 1.0, 1.0]);
+        graph.set_node_weight(0, 2.0);
+        let metrics = q(&graph);
+        assert!((metrics.inter - 0.625).abs() < 1e-12);
+        assert!((metrics.intra - 2.0 / 3.0).abs() < 1e-12);
+
+        for node in 0..3 {
+            graph.set_node_weight(node, 2.0 * graph.node_weight(node));
+        }
+        assert!((q(&graph).modularity - metrics.modularity).abs() < 1e-12);
+
+        let mut directed = Graph::new_directed();
+        for (u, v) in [(0, 1), (1, 2), (2, 0), (0, 2)] {
+            directed.add_edge(u, v);
+        }
+        directed.set_node_weight(1, 3.0);
+        let before = q(&directed).modularity;
+        for node in 0..3 {
+            directed.set_node_weight(node, 0.5 * directed.node_weight(node));
+        }
+        assert!((q(&directed).modularity - before).abs() < 1e-12);
+    }
+
+    /// Applies random single-node moves and checks the summed deltas against a recompute
+    fn assert_deltas_match_recompute(graph: &Graph, resolution: f64) {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(11);
+        let mut partition: HashMap<NodeId, CommunityId> =
+            (0..12).map(|node| (node, node % 4)).collect();
+        let full_q = |partition: &HashMap<NodeId, CommunityId>| {
+            let partition: Partition = partition.iter().map(|(&k, &v)| (k, v)).collect();
+            let degrees = graph.precompute_degrees();
+            calculate_objectives(graph, &partition, &degrees, false, resolution).modularity
+        };
+
+        let mut strengths = CommunityStrengths::new(graph, &partition);
+        let mut q = full_q(&partition);
+        for _ in 0..200 {
+            let node = rng.random_range(0..12);
+            let to = rng.random_range(0..5);
+            let from = partition[&node];
+
+            q += delta_modularity(graph, &partition, &strengths, node, to, resolution);
+            strengths.move_node(graph, node, from, to);
+            partition.insert(node, to);
+        }
+
+        assert!((q - full_q(&partition)).abs() < 1e-9);
+    }
+
+    fn weighted_ring(mut graph: Graph) -> Graph {
+        for i in 0..12 {
+            graph.add_weighted_edge(i, (i + 1) % 12, 1.0 + (i % 3) as f64);
+            graph.add_edge(i, (i + 5) % 12);
+        }
+        graph.add_edge(3, 3);
+        graph
+    }
+
+    #[test]
+    fn test_delta_modularity_matches_full_recompute() {
+        for resolution in [1.0, 2.5] {
+            assert_deltas_match_recompute(&weighted_ring(Graph::new()), resolution);
+            assert_deltas_match_recompute(&weighted_ring(Graph::new_directed()), resolution);
+        }
+    }
+
+    #[test]
+    fn test_merge_gains_of_two_cliques() {
+        // 5-cliques 0-4 and 5-9 joined by 4-5: m = 21, D = 21 per clique, so merging
+        // them costs 1/21 - 21^2 / (2 * 21^2) = 1/21 - 1/2
+        let mut graph = Graph::new();
+        for offset in [0, 5] {
+            for i in offset..offset + 5 {
+                for j in (i + 1)..offset + 5 {
+                    graph.add_edge(i, j);
+                }
+            }
+        }
+        graph.add_edge(4, 5);
+        let cliques: Partition = (0..10).map(|node| (node, node / 5)).collect();
+        let gains = merge_gains(&graph, &cliques, 1.0);
+        assert_eq!(gains.len(), 1);
+        assert_eq!(gains[0].0, (0, 1));
+        assert!((gains[0].1 - (1.0 / 21.0 - 0.5)).abs() < 1e-12);
+
+        // Every gain matches the change in Q, and halves of a clique want to merge
+        let halves: Partition = (0..10).map(|node| (node, node * 2 / 5)).collect();
+        let q = |partition: &Partition| {
+            calculate_objectives(&graph, partition, &graph.precompute_degrees(), false, 1.0)
+                .modularity
+        };
+        for ((a, b), gain) in merge_gains(&graph, &halves, 1.0) {
+            let merged: Partition = halves
+                .iter()
+                .map(|(&node, &c)| (node, if c == b { a } else { c }))
+                .collect();
+            assert!((q(&merged) - q(&halves) - gain).abs() < 1e-12);
+            if b == a + 1 && a % 2 == 0 {
+                assert!(gain > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_gains_match_full_recompute() {
+        for graph in [
+            weighted_ring(Graph::new()),
+            weighted_ring(Graph::new_directed()),
+        ] {
+            let partition: Partition = (0..12).map(|node| (node, node % 4)).collect();
+            let q = |partition: &Partition| {
+                calculate_objectives(&graph, partition, &graph.precompute_degrees(), false, 2.5)
+                    .modularity
+            };
+            for ((a, b), gain) in merge_gains(&graph, &partition, 2.5) {
+                let merged: Partition = partition
+                    .iter()
+                    .map(|(&node, &c)| (node, if c == b { a } else { c }))
+                    .collect();
+                assert!((q(&merged) - q(&partition) - gain).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_directed_modularity_two_cycles() {
+        // Directed triangles 0->1->2->0 and 3->4->5->3 joined by 2->3
+        let mut graph = Graph::new_directed();
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+            graph.add_edge(from, to);
+        }
+        let partition: Partition = (0..6).map(|node| (node, node / 3)).collect();
+
+        // m = 7, L_in = 6, (Out, In) = (4, 3) and (3, 4): Q = 6/7 - 24/49
+        let metrics =
+            calculate_objectives(&graph, &partition, &graph.precompute_degrees(), false, 1.0);
+        assert!((metrics.modularity - 18.0 / 49.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_bipartite_modularity_two_bicliques() {
+        // Users 0-3 and items 4-7: bicliques {0, 1} x {4, 5} and {2, 3} x {6, 7},
+        // joined by 1-6, plus a user-user edge 0-2 that Barber's modularity ignores
+        let mut graph = Graph::new();
+        for (u, v) in [
+            (0, 4),
+            (0, 5),
+            (1, 4),
+            (1, 5),
+            (2, 6),
+            (2, 7),
+            (3, 6),
+            (3, 7),
+            (1, 6),
+        ] {
+            graph.add_edge(u, v);
+        }
+        graph.add_edge(0, 2);
+        for node in 0..8 {
+            graph.node_types.insert(node, u8::from(node >= 4));
+        }
+        let partition: Partition = [0, 1, 4, 5]
+            .iter()
+            .map(|&node| (node, 0))
+            .chain([2, 3, 6, 7].iter().map(|&node| (node, 1)))
+            .collect();
+
+        // m = 9, L_in = 8, (R, B) = (5, 4) and (4, 5): Q = 8/9 - 40/81
+        let metrics = bipartite_objectives(&graph, &partition, 1.0);
+        assert!((metrics.modularity - 32.0 / 81.0).abs() < 1e-12);
+        assert!((metrics.intra - 1.0 / 9.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,94 @@
+//! operators/overlap.rs
+//! Derives overlapping (soft) community memberships from a hard partition
+//! This Source Code Form is subject to the terms of The GNU General Public License v3.0
+//! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
+//! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
+
+use crate::graph::{CommunityId, Graph, NodeId, Partition};
+
+use std::collections::BTreeMap;
+
+/// Communities of each node: its own community from `partition` first, followed, in
+/// ascending id order, by every other community holding strictly more than `threshold`
+/// of the node's incident edges. A fraction exactly equal to the threshold does not
+/// count, and a node never loses its own community, so nodes without neighbours keep
+/// just that one. On directed graphs both in- and out-edges are incident.
+pub fn soft_memberships(
+    graph: &Graph,
+    partition: &Partition,
+    threshold: f64,
+) -> BTreeMap<NodeId, Vec<CommunityId>> {
+    let mut memberships = BTreeMap::new();
+    let mut counts: BTreeMap<CommunityId, usize> = BTreeMap::new();
+
+    for (&node, &own) in partition {
+        counts.clear();
+        let neighbors = graph.neighbors(&node);
+        for neighbor in neighbors {
+            if let Some(&community) = partition.get(neighbor) {
+                *counts.entry(community).or_insert(0) += 1;
+            }
+        }
+
+        let degree = neighbors.len() as f64;
+        let mut communities = vec![own];
+        communities.extend(
+            counts
+                .iter()
+                .filter(|&(&community, &count)| {
+                    community != own && count as f64 / degree > threshold
+                })
+                .map(|(&community, _)| community),
+        );
+        memberships.insert(node, communities);
+    }
+    memberships
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Two 4-cliques (0-3 and 5-8) both fully joined to bridge node 4
+    fn bridged_cliques() -> (Graph, Partition) {
+        let mut graph = Graph::new();
+        for clique in [[0, 1, 2, 3], [5, 6, 7, 8]] {
+            for (i, &u) in clique.iter().enumerate() {
+                for &v in &clique[i + 1..] {
+                    graph.add_edge(u, v);
+                }
+                graph.add_edge(u, 4);
+            }
+        }
+        let partition = (0..9)
+            .map(|node| (node, (node > 4) as CommunityId))
+            .collect();
+        (graph, partition)
+    }
+
+    #[test]
+    fn test_bridge_node_joins_both_cliques() {
+        let (graph, partition) = bridged_cliques();
+
+        // The bridge has half its edges in each clique
+        let memberships = soft_memberships(&graph, &partition, 0.4);
+        assert_eq!(memberships[&4], vec![0, 1]);
+        assert_eq!(memberships[&0], vec![0]);
+        assert_eq!(memberships[&8], vec![1]);
+
+        // Exactly at the threshold is not enough
+        let memberships = soft_memberships(&graph, &partition, 0.5);
+        assert_eq!(memberships[&4], vec![0]);
+    }
+
+    #[test]
+    fn test_own_community_is_listed_first() {
+        let (graph, mut partition) = bridged_cliques();
+        partition.insert(4, 1);
+
+        let memberships = soft_memberships(&graph, &partition, 0.4);
+        assert_eq!(memberships[&4], vec![1, 0]);
+        // Clique members see a quarter of their edges going to the bridge
+        assert_eq!(soft_memberships(&graph, &partition, 0.2)[&0], vec![0, 1]);
+    }
+}
@@ -6,14 +6,19 @@
 
 use crate::graph::{CommunityId, Graph, NodeId, Partition};
 
-use rand::Rng;
+use rand::{Rng, seq::SliceRandom};
 use rustc_hash::FxHashSet as HashSet;
 use std::collections::BTreeMap;
 
 #[allow(dead_code)]
-pub fn generate_optimized_population(graph: &Graph, population_size: usize) -> Vec<Partition> {
-    let mut rng = rand::rng();
-    let nodes: Vec<NodeId> = graph.nodes.iter().copied().collect();
+pub fn generate_optimized_population(
+    graph: &Graph,
+    population_size: usize,
+    rng: &mut impl Rng,
+) -> Vec<Partition> {
+    // Sorted so the draws depend on the graph's contents, not on hash-set layout
+    let mut nodes: Vec<NodeId> = graph.nodes.iter().copied().collect();
+    nodes.sort_unstable();
     let num_nodes = nodes.len();
     let mut population = Vec::with_capacity(population_size);
 
@@ -75,9 +80,14 @@ pub fn generate_optimized_population(graph: &Graph, population_size: usize) -> V
 }
 
 #[allow(dead_code)]
-pub fn generate_initial_population(graph: &Graph, population_size: usize) -> Vec<Partition> {
-    let mut rng = rand::rng();
-    let nodes: Vec<NodeId> = graph.nodes.iter().copied().collect();
+pub fn generate_initial_population(
+    graph: &Graph,
+    population_size: usize,
+    rng: &mut impl Rng,
+) -> Vec<Partition> {
+    // Sorted so the draws depend on the graph's contents, not on hash-set layout
+    let mut nodes: Vec<NodeId> = graph.nodes.iter().copied().collect();
+    nodes.sort_unstable();
     let num_nodes = nodes.len();
 
     (0..population_size)
@@ -89,3 +99,50 @@ pub fn generate_initial_population(graph: &Graph, population_size: usize) -> Vec
         })
         .collect()
 }
+
+/// Label propagation sweeps per individual: enough for labels to settle inside dense
+/// groups, few enough to leave the population diverse
+const LP_SWEEPS: usize = 3;
+
+/// Individuals grown by `LP_SWEEPS` label propagation sweeps over `adjacency_list`,
+/// starting from singletons. Each sweep visits the nodes in a fresh random order and
+/// gives each the label most common among its neighbours, breaking ties at random, so
+/// individuals differ while all following the graph's structure.
+pub fn generate_label_propagation_population(
+    graph: &Graph,
+    population_size: usize,
+    rng: &mut impl Rng,
+) -> Vec<Partition> {
+    // Sorted so the draws depend on the graph's contents, not on hash-set layout
+    let mut nodes: Vec<NodeId> = graph.nodes.iter().copied().collect();
+    nodes.sort_unstable();
+
+    (0..population_size)
+        .map(|_| {
+            let mut labels: BTreeMap<NodeId, CommunityId> = nodes
+                .iter()
+                .map(|&node| (node, node as CommunityId))
+                .collect();
+            let mut order = nodes.clone();
+            for _ in 0..LP_SWEEPS {
+                order.shuffle(rng);
+                for &node in &order {
+                    let mut counts: BTreeMap<CommunityId, usize> = BTreeMap::new();
+                    for neighbor in graph.adjacency_list.get(&node).into_iter().flatten() {
+                        *counts.entry(labels[neighbor]).or_insert(0) += 1;
+                    }
+                    let Some(&most) = counts.values().max() else {
+                        continue;
+                    };
+                    let tied: Vec<CommunityId> = counts
+                        .into_iter()
+                        .filter(|&(_, count)| count == most)
+                        .map(|(label, _)| label)
+                        .collect();
+                    labels.insert(node, tied[rng.random_range(0..tied.len())]);
+                }
+            }
+            labels
+        })
+        .collect()
+}
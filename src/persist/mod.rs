@@ -0,0 +1,499 @@
+//! persist/mod.rs
+//! Compact binary snapshots of fitted estimators (graph, settings and archive)
+//! This Source Code Form is subject to the terms of The GNU General Public License v3.0
+//! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
+//! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
+
+use crate::graph::{Graph, NodeId};
+use crate::operators::{
+    CrossoverType, EvolutionConfig, InitStrategy, ModularityVariant, MutationSchedule,
+};
+
+use crate::utils::EdgeRules;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+
+const MAGIC: &[u8; 6] = b"PYMOCD";
+const VERSION: u8 = 1;
+
+/// Little-endian encoding of a value
+pub trait Encode {
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+/// Inverse of `Encode`; consumes the bytes it reads
+pub trait Decode: Sized {
+    fn decode(input: &mut &[u8]) -> Result<Self>;
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (**self).encode(out);
+    }
+}
+
+/// Writes `value` to `path`, tagged with `kind` so it only loads back into the same estimator
+pub fn save<T: Encode>(path: &str, kind: &str, value: &T) -> Result<()> {
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    kind.to_string().encode(&mut out);
+    value.encode(&mut out);
+    fs::write(path, out)
+}
+
+/// Reads a value written by `save` with the same `kind`
+pub fn load<T: Decode>(path: &str, kind: &str) -> Result<T> {
+    let bytes = fs::read(path)?;
+    let mut input = bytes.as_slice();
+
+    if take(&mut input, MAGIC.len())? != MAGIC {
+        return Err(invalid(format!("{} is not a pymocd snapshot", path)));
+    }
+    let version = u8::decode(&mut input)?;
+    if version != VERSION {
+        return Err(invalid(format!("unsupported snapshot version {}", version)));
+    }
+    let found = String::decode(&mut input)?;
+    if found != kind {
+        return Err(invalid(format!(
+            "snapshot holds a {}, not a {}",
+            found, kind
+        )));
+    }
+
+    let value = T::decode(&mut input)?;
+    if !input.is_empty() {
+        return Err(invalid("trailing bytes after snapshot".to_string()));
+    }
+    Ok(value)
+}
+
+fn invalid(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if input.len() < len {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "truncated snapshot"));
+    }
+    let (head, tail) = input.split_at(len);
+    *input = tail;
+    Ok(head)
+}
+
+macro_rules! persist_number {
+    ($($ty:ty),*) => {$(
+        impl Encode for $ty {
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(input: &mut &[u8]) -> Result<Self> {
+                let bytes = take(input, std::mem::size_of::<$ty>())?;
+                Ok(<$ty>::from_le_bytes(bytes.try_into().unwrap()))
+            }
+        }
+    )*};
+}
+
+persist_number!(u8, i8, i32, u32, u64, f64);
+
+impl Encode for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+}
+
+impl Decode for usize {
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        usize::try_from(u64::decode(input)?).map_err(|err| invalid(err.to_string()))
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u8).encode(out);
+    }
+}
+
+impl Decode for bool {
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        Ok(u8::decode(input)? != 0)
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl Decode for String {
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        let len = usize::decode(input)?;
+        String::from_utf8(take(input, len)?.to_vec()).map_err(|err| invalid(err.to_string()))
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.is_some().encode(out);
+        if let Some(value) = self {
+            value.encode(out);
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        Ok(if bool::decode(input)? {
+            Some(T::decode(input)?)
+        } else {
+            None
+        })
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        for value in self {
+            value.encode(out);
+        }
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        let len = usize::decode(input)?;
+        // Every element takes at least one byte, so a corrupt length fails fast
+        if len > input.len() {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "truncated snapshot"));
+        }
+        (0..len).map(|_| T::decode(input)).collect()
+    }
+}
+
+impl<K: Encode + Ord, V: Encode> Encode for BTreeMap<K, V> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        for (key, value) in self {
+            key.encode(out);
+            value.encode(out);
+        }
+    }
+}
+
+impl<K: Decode + Ord, V: Decode> Decode for BTreeMap<K, V> {
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        let entries: Vec<(K, V)> = Vec::decode(input)?;
+        Ok(entries.into_iter().collect())
+    }
+}
+
+macro_rules! persist_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Encode),+> Encode for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn encode(&self, out: &mut Vec<u8>) {
+                let ($($name,)+) = self;
+                $($name.encode(out);)+
+            }
+        }
+
+        impl<$($name: Decode),+> Decode for ($($name,)+) {
+            fn decode(input: &mut &[u8]) -> Result<Self> {
+                Ok(($($name::decode(input)?,)+))
+            }
+        }
+    };
+}
+
+persist_tuple!(A, B);
+persist_tuple!(A, B, C);
+persist_tuple!(A, B, C, D);
+persist_tuple!(A, B, C, D, E);
+persist_tuple!(A, B, C, D, E, F);
+persist_tuple!(A, B, C, D, E, F, G);
+persist_tuple!(A, B, C, D, E, F, G, H);
+
+/// Stored as its node set and ordered edge list; replaying the edges rebuilds the
+/// adjacency lists in their original order
+impl Encode for Graph {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.directed.encode(out);
+        self.sorted_nodes().encode(out);
+        self.edges.len().encode(out);
+        for &(from, to) in &self.edges {
+            (from, to, self.weight(from, to)).encode(out);
+        }
+        let node_weights: BTreeMap<NodeId, f64> = self
+            .node_weights
+            .iter()
+            .map(|(&node, &w)| (node, w))
+            .collect();
+        node_weights.encode(out);
+        let node_types: BTreeMap<NodeId, u8> = self
+            .node_types
+            .iter()
+            .map(|(&node, &t)| (node, t))
+            .collect();
+        node_types.encode(out);
+    }
+}
+
+impl Decode for Graph {
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        let mut graph = if bool::decode(input)? {
+            Graph::new_directed()
+        } else {
+            Graph::new()
+        };
+        for node in Vec::<NodeId>::decode(input)? {
+            graph.add_node(node);
+        }
+        for (from, to, weight) in Vec::<(NodeId, NodeId, f64)>::decode(input)? {
+            graph.add_weighted_edge(from, to, weight);
+        }
+        for (node, weight) in BTreeMap::<NodeId, f64>::decode(input)? {
+            graph.set_node_weight(node, weight);
+        }
+        graph.node_types = BTreeMap::<NodeId, u8>::decode(input)?.into_iter().collect();
+        Ok(graph)
+    }
+}
+
+impl Encode for ModularityVariant {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let tag: u8 = match self {
+            ModularityVariant::Newman => 0,
+            ModularityVariant::Dcsbm => 1,
+            ModularityVariant::Bipartite => 2,
+        };
+        tag.encode(out);
+    }
+}
+
+impl Decode for ModularityVariant {
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        match u8::decode(input)? {
+            0 => Ok(ModularityVariant::Newman),
+            1 => Ok(ModularityVariant::Dcsbm),
+            2 => Ok(ModularityVariant::Bipartite),
+            tag => Err(invalid(format!("unknown modularity variant {}", tag))),
+        }
+    }
+}
+
+impl Encode for CrossoverType {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let tag: u8 = match self {
+            CrossoverType::Ensemble => 0,
+            CrossoverType::Uniform => 1,
+            CrossoverType::OnePoint => 2,
+            CrossoverType::TwoPoint => 3,
+        };
+        tag.encode(out);
+    }
+}
+
+impl Decode for CrossoverType {
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        match u8::decode(input)? {
+            0 => Ok(CrossoverType::Ensemble),
+            1 => Ok(CrossoverType::Uniform),
+            2 => Ok(CrossoverType::OnePoint),
+            3 => Ok(CrossoverType::TwoPoint),
+            tag => Err(invalid(format!("unknown crossover type {}", tag))),
+        }
+    }
+}
+
+impl Encode for MutationSchedule {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let tag: u8 = match self {
+            MutationSchedule::Constant => 0,
+            MutationSchedule::Linear => 1,
+            MutationSchedule::Exponential => 2,
+        };
+        tag.encode(out);
+    }
+}
+
+impl Decode for MutationSchedule {
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        match u8::decode(input)? {
+            0 => Ok(MutationSchedule::Constant),
+            1 => Ok(MutationSchedule::Linear),
+            2 => Ok(MutationSchedule::Exponential),
+            tag => Err(invalid(format!("unknown mutation schedule {}", tag))),
+        }
+    }
+}
+
+impl Encode for InitStrategy {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let tag: u8 = match self {
+            InitStrategy::Random => 0,
+            InitStrategy::LabelPropagation => 1,
+        };
+        tag.encode(out);
+    }
+}
+
+impl Decode for InitStrategy {
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        match u8::decode(input)? {
+            0 => Ok(InitStrategy::Random),
+            1 => Ok(InitStrategy::LabelPropagation),
+            tag => Err(invalid(format!("unknown init strategy {}", tag))),
+        }
+    }
+}
+
+impl Encode for EdgeRules {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.drop_self_loops.encode(out);
+        self.parallel_as_weight.encode(out);
+    }
+}
+
+impl Decode for EdgeRules {
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        Ok(EdgeRules {
+            drop_self_loops: Decode::decode(input)?,
+            parallel_as_weight: Decode::decode(input)?,
+        })
+    }
+}
+
+impl Encode for EvolutionConfig {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.num_gens.encode(out);
+        self.pop_size.encode(out);
+        self.cross_rate.encode(out);
+        self.mut_rate.encode(out);
+        self.seed.encode(out);
+        self.patience.encode(out);
+        self.tol.encode(out);
+        self.num_threads.encode(out);
+        self.num_communities.encode(out);
+        self.time_limit_secs.encode(out);
+        self.tournament_size.encode(out);
+        self.grid_divisions.encode(out);
+        self.archive_size.encode(out);
+        self.archive_patience.encode(out);
+        self.resolution.encode(out);
+        self.macro_mutation_rate.encode(out);
+        self.elitism.encode(out);
+        self.refine.encode(out);
+        self.max_community_size.encode(out);
+        self.modularity_variant.encode(out);
+        self.crossover_type.encode(out);
+        self.mut_rate_schedule.encode(out);
+        self.mut_rate_min.encode(out);
+        self.obj_min.encode(out);
+        self.obj_max.encode(out);
+        self.init_strategy.encode(out);
+        self.initial_partition.encode(out);
+        self.must_link.encode(out);
+        self.cannot_link.encode(out);
+        self.round_objectives.encode(out);
+    }
+}
+
+impl Decode for EvolutionConfig {
+    fn decode(input: &mut &[u8]) -> Result<Self> {
+        Ok(EvolutionConfig {
+            num_gens: Decode::decode(input)?,
+            pop_size: Decode::decode(input)?,
+            cross_rate: Decode::decode(input)?,
+            mut_rate: Decode::decode(input)?,
+            seed: Decode::decode(input)?,
+            patience: Decode::decode(input)?,
+            tol: Decode::decode(input)?,
+            num_threads: Decode::decode(input)?,
+            num_communities: Decode::decode(input)?,
+            time_limit_secs: Decode::decode(input)?,
+            tournament_size: Decode::decode(input)?,
+            grid_divisions: Decode::decode(input)?,
+            archive_size: Decode::decode(input)?,
+            archive_patience: Decode::decode(input)?,
+            resolution: Decode::decode(input)?,
+            macro_mutation_rate: Decode::decode(input)?,
+            elitism: Decode::decode(input)?,
+            refine: Decode::decode(input)?,
+            max_community_size: Decode::decode(input)?,
+            modularity_variant: Decode::decode(input)?,
+            crossover_type: Decode::decode(input)?,
+            mut_rate_schedule: Decode::decode(input)?,
+            mut_rate_min: Decode::decode(input)?,
+            obj_min: Decode::decode(input)?,
+            obj_max: Decode::decode(input)?,
+            init_strategy: Decode::decode(input)?,
+            initial_partition: Decode::decode(input)?,
+            must_link: Decode::decode(input)?,
+            cannot_link: Decode::decode(input)?,
+            round_objectives: Decode::decode(input)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::Partition;
+
+    fn round_trip<T: Encode + Decode>(value: &T) -> T {
+        let mut out = Vec::new();
+        value.encode(&mut out);
+        let mut input = out.as_slice();
+        let decoded = T::decode(&mut input).unwrap();
+        assert!(input.is_empty());
+        decoded
+    }
+
+    #[test]
+    fn test_round_trip_graph_and_partition() {
+        let mut graph = Graph::new_directed();
+        graph.add_weighted_edge(2, 0, 0.5);
+        graph.add_edge(0, 1);
+        graph.add_node(7);
+        graph.set_node_weight(7, 2.5);
+        graph.node_types.insert(1, 1);
+        let partition: Partition = [(0, 1), (1, 1), (2, 0), (7, 2)].into_iter().collect();
+
+        let decoded = round_trip(&graph);
+        assert!(decoded.directed);
+        assert_eq!(decoded.edges, graph.edges);
+        assert_eq!(decoded.adjacency_list, graph.adjacency_list);
+        assert_eq!(decoded.weight(2, 0), 0.5);
+        assert_eq!(decoded.num_nodes(), 4);
+        assert_eq!(decoded.node_weights, graph.node_weights);
+        assert_eq!(decoded.node_types, graph.node_types);
+        assert_eq!(round_trip(&partition), partition);
+    }
+
+    #[test]
+    fn test_load_rejects_other_kinds_and_truncation() {
+        let path = std::env::temp_dir().join(format!("pymocd-persist-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        save(path, "MOCD", &(1.5_f64, Some(3_usize))).unwrap();
+        assert_eq!(
+            load::<(f64, Option<usize>)>(path, "MOCD").unwrap(),
+            (1.5, Some(3))
+        );
+        assert!(load::<(f64, Option<usize>)>(path, "HpMocd").is_err());
+
+        let bytes = fs::read(path).unwrap();
+        fs::write(path, &bytes[..bytes.len() - 1]).unwrap();
+        assert!(load::<(f64, Option<usize>)>(path, "MOCD").is_err());
+        fs::remove_file(path).unwrap();
+    }
+}
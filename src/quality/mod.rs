@@ -0,0 +1,136 @@
+//! quality/mod.rs
+//! Structural quality measures of a single partition over a graph
+//! This Source Code Form is subject to the terms of The GNU General Public License v3.0
+//! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
+//! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
+
+use crate::graph::{CommunityId, Graph, Partition};
+use std::collections::BTreeMap;
+
+/// Average conductance over the communities of `partition`, each being
+/// cut / min(volume inside, volume outside). Volumes are summed edge weights
+/// (degrees when unweighted); a community holding the whole volume scores 0.
+pub fn conductance(graph: &Graph, partition: &Partition) -> f64 {
+    // Communities only exist through their nodes, so none of these is empty
+    let mut cut: BTreeMap<CommunityId, f64> = partition.values().map(|&c| (c, 0.0)).collect();
+    let mut volume = cut.clone();
+
+    for &(from, to) in &graph.edges {
+        let weight = graph.weight(from, to);
+        let (a, b) = (partition.get(&from), partition.get(&to));
+        for (community, other) in [(a, b), (b, a)] {
+            if let Some(community) = community {
+                *volume.get_mut(community).unwrap() += weight;
+                if other != Some(community) {
+                    *cut.get_mut(community).unwrap() += weight;
+                }
+            }
+        }
+    }
+
+    if cut.is_empty() {
+        return 0.0;
+    }
+    let total_volume = 2.0 * graph.total_weight();
+    let sum: f64 = cut
+        .iter()
+        .map(|(community, &cut)| {
+            let inside = volume[community];
+            let denominator = inside.min(total_volume - inside);
+            if denominator > 0.0 {
+                cut / denominator
+            } else {
+                0.0
+            }
+        })
+        .sum();
+    sum / cut.len() as f64
+}
+
+/// Fraction of edges that fall inside a community; self-loops count as inside
+pub fn coverage(graph: &Graph, partition: &Partition) -> f64 {
+    if graph.num_edges() == 0 {
+        return 0.0;
+    }
+    let intra = graph
+        .edges
+        .iter()
+        .filter(|(from, to)| {
+            let community = partition.get(from);
+            community.is_some() && community == partition.get(to)
+        })
+        .count();
+    intra as f64 / graph.num_edges() as f64
+}
+
+/// Fraction of node pairs classified correctly: intra-community edges plus
+/// inter-community non-edges, over n * (n - 1) / 2. Self-loops are ignored.
+pub fn performance(graph: &Graph, partition: &Partition) -> f64 {
+    let n = graph.num_nodes() as u64;
+    if n < 2 {
+        return 0.0;
+    }
+    let total_pairs = n * (n - 1) / 2;
+
+    let mut sizes: BTreeMap<CommunityId, u64> = BTreeMap::new();
+    for &community in partition.values() {
+        *sizes.entry(community).or_insert(0) += 1;
+    }
+    let intra_pairs: u64 = sizes
+        .values()
+        .map(|&size| size * size.saturating_sub(1) / 2)
+        .sum();
+
+    let (mut intra, mut inter) = (0, 0);
+    for (from, to) in graph.edges.iter().filter(|(from, to)| from != to) {
+        let community = partition.get(from);
+        if community.is_some() && community == partition.get(to) {
+            intra += 1;
+        } else {
+            inter += 1;
+        }
+    }
+    let inter_non_edges = (total_pairs - intra_pairs).saturating_sub(inter);
+
+    (intra + inter_non_edges) as f64 / total_pairs as f64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn two_triangles() -> Graph {
+        let mut graph = Graph::new();
+        for (from, to) in [(0, 1), (1, 2), (0, 2), (3, 4), (4, 5), (3, 5), (2, 3)] {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_conductance_two_triangles() {
+        let graph = two_triangles();
+        let partition: Partition = (0..6).map(|node| (node, node / 3)).collect();
+
+        // Each side: cut 1, volume 7 out of 14
+        assert!((conductance(&graph, &partition) - 1.0 / 7.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_conductance_single_community() {
+        let graph = two_triangles();
+        let partition: Partition = (0..6).map(|node| (node, 0)).collect();
+
+        assert_eq!(conductance(&graph, &partition), 0.0);
+    }
+
+    #[test]
+    fn test_coverage_and_performance_two_triangles() {
+        let graph = two_triangles();
+        let partition: Partition = (0..6).map(|node| (node, node / 3)).collect();
+
+        // 6 of 7 edges inside; 6 intra edges + 8 inter non-edges out of 15 pairs
+        assert!((coverage(&graph, &partition) - 6.0 / 7.0).abs() < 1e-12);
+        assert!((performance(&graph, &partition) - 14.0 / 15.0).abs() < 1e-12);
+    }
+}
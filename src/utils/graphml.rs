@@ -0,0 +1,304 @@
+//! utils/graphml.rs
+//! Minimal GraphML reader behind `from_graphml`
+//! This Source Code Form is subject to the terms of The GNU General Public License v3.0
+//! Copyright 2024 - Guilherme Santos. If a copy of the MPL was not distributed with this
+//! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
+
+use super::WeightedEdges;
+use crate::graph::NodeId;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::io::{self, ErrorKind};
+
+/// Nodes and edges of a GraphML document, with its string node ids remapped to dense
+/// `NodeId`s in order of first appearance
+pub struct GraphMl {
+    pub names: Vec<String>, // NodeId -> GraphML node id
+    pub edges: WeightedEdges,
+}
+
+impl GraphMl {
+    fn intern(&mut self, ids: &mut FxHashMap<String, NodeId>, name: String) -> io::Result<NodeId> {
+        if let Some(&id) = ids.get(&name) {
+            return Ok(id);
+        }
+        let id = NodeId::try_from(self.names.len())
+            .map_err(|_| invalid("too many distinct node ids".to_string()))?;
+        ids.insert(name.clone(), id);
+        self.names.push(name);
+        Ok(id)
+    }
+}
+
+/// An edge whose closing tag has not been read yet
+struct OpenEdge {
+    index: usize,
+    from: NodeId,
+    to: NodeId,
+    weight: f64,
+}
+
+/// Parses the `<node id>` and `<edge source target>` elements of `text`. An edge's
+/// weight comes from its `<data>` under a numeric (`int`, `long`, `float`, `double`)
+/// `<key attr.name="weight">` declared for edges, and defaults to 1.0; other keys, data
+/// and elements are skipped. Failures are `InvalidData` naming the element and its
+/// 1-based position among elements of the same name.
+pub fn parse_graphml(text: &str) -> io::Result<GraphMl> {
+    let mut graph = GraphMl {
+        names: Vec::new(),
+        edges: Vec::new(),
+    };
+    let mut ids = FxHashMap::default();
+    let mut weight_keys = FxHashSet::default();
+    let mut counts: FxHashMap<String, usize> = FxHashMap::default();
+    let mut edge: Option<OpenEdge> = None;
+    let mut data: Option<(usize, String, String)> = None; // (index, key, text)
+    let mut pos = 0;
+
+    while let Some(offset) = text[pos..].find('<') {
+        let start = pos + offset;
+        if let Some((_, _, value)) = data.as_mut() {
+            value.push_str(&unescape(&text[pos..start]));
+        }
+        let rest = &text[start..];
+
+        if let Some(body) = rest.strip_prefix("<![CDATA[") {
+            let end = body
+                .find("]]>")
+                .ok_or_else(|| invalid("unterminated CDATA".into()))?;
+            if let Some((_, _, value)) = data.as_mut() {
+                value.push_str(&body[..end]);
+            }
+            pos = start + "<![CDATA[".len() + end + "]]>".len();
+            continue;
+        }
+        let skip_to = |close: &str| {
+            rest.find(close)
+                .map(|end| start + end + close.len())
+                .ok_or_else(|| invalid(format!("unterminated '{}'", &rest[..2])))
+        };
+        if rest.starts_with("<!--") {
+            pos = skip_to("-->")?;
+            continue;
+        }
+        if rest.starts_with("<?") {
+            pos = skip_to("?>")?;
+            continue;
+        }
+        if rest.starts_with("<!") {
+            pos = skip_to(">")?;
+            continue;
+        }
+
+        let end = tag_end(rest).ok_or_else(|| invalid("unterminated tag".into()))?;
+        pos = start + end + 1;
+        let tag = &rest[1..end];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            match local_name(name.trim()) {
+                "edge" => {
+                    if let Some(open) = edge.take() {
+                        graph.edges.push((open.from, open.to, open.weight));
+                    }
+                }
+                "data" => {
+                    let Some((index, key, value)) = data.take() else {
+                        continue;
+                    };
+                    if let (Some(open), true) = (edge.as_mut(), weight_keys.contains(&key)) {
+                        open.weight = value.trim().parse().map_err(|_| {
+                            invalid(format!(
+                                "data {} in edge {}: '{}' is not a weight",
+                                index,
+                                open.index,
+                                value.trim()
+                            ))
+                        })?;
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.strip_suffix('/').unwrap_or(tag);
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let name = local_name(&tag[..name_end]);
+        let count = counts.entry(name.to_string()).or_insert(0);
+        *count += 1;
+        let index = *count;
+        let attrs = attributes(&tag[name_end..])
+            .ok_or_else(|| invalid(format!("{} {}: malformed attributes", name, index)))?;
+        let attr = |key: &str| {
+            attrs
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.clone())
+        };
+        let required = |key: &str| {
+            attr(key)
+                .ok_or_else(|| invalid(format!("{} {}: missing '{}' attribute", name, index, key)))
+        };
+
+        match name {
+            "key" => {
+                let for_edges = matches!(attr("for").as_deref(), Some("edge" | "all") | None);
+                let numeric = matches!(
+                    attr("attr.type").as_deref(),
+                    Some("int" | "long" | "float" | "double")
+                );
+                if for_edges && numeric && attr("attr.name").as_deref() == Some("weight") {
+                    weight_keys.insert(required("id")?);
+                }
+            }
+            "node" => {
+                graph.intern(&mut ids, required("id")?)?;
+            }
+            "edge" => {
+                let open = OpenEdge {
+                    index,
+                    from: graph.intern(&mut ids, required("source")?)?,
+                    to: graph.intern(&mut ids, required("target")?)?,
+                    weight: 1.0,
+                };
+                if self_closing {
+                    graph.edges.push((open.from, open.to, open.weight));
+                } else {
+                    edge = Some(open);
+                }
+            }
+            "data" if !self_closing => data = Some((index, required("key")?, String::new())),
+            _ => {}
+        }
+    }
+
+    Ok(graph)
+}
+
+fn invalid(reason: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, reason)
+}
+
+/// Element name without its namespace prefix
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Byte offset of the `>` closing the tag that starts `rest`, skipping quoted values
+fn tag_end(rest: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in rest.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `name="value"` pairs of a tag, values unescaped; None when a value is unquoted
+fn attributes(mut rest: &str) -> Option<Vec<(&str, String)>> {
+    let mut attrs = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return Some(attrs);
+        }
+        let (name, value) = rest.split_once('=')?;
+        let value = value.trim_start();
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let end = value[1..].find(quote)? + 1;
+        attrs.push((name.trim(), unescape(&value[1..end])));
+        rest = &value[end + 1..];
+    }
+}
+
+/// Replaces the predefined XML entities and numeric character references
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DOCUMENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="w" for="edge" attr.name="weight" attr.type="double"/>
+  <key id="c" for="edge" attr.name="color" attr.type="string"/>
+  <graph id="G" edgedefault="undirected">
+    <!-- a triangle plus a pendant node -->
+    <node id="a"/>
+    <node id="b &amp; c"/>
+    <node id="d"/>
+    <edge source="a" target="b &amp; c"><data key="w">2.5</data></edge>
+    <edge source="b &amp; c" target="d"><data key="c">red</data></edge>
+    <edge source="d" target="a"/>
+    <edge source="d" target="e"/>
+  </graph>
+</graphml>"#;
+
+    #[test]
+    fn test_parse_graphml_reads_nodes_edges_and_weights() {
+        let graph = parse_graphml(DOCUMENT).unwrap();
+        assert_eq!(graph.names, ["a", "b & c", "d", "e"]);
+        assert_eq!(
+            graph.edges,
+            [(0, 1, 2.5), (1, 2, 1.0), (2, 0, 1.0), (2, 3, 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_parse_graphml_names_the_failing_element() {
+        let missing = parse_graphml(r#"<graph><node id="a"/><edge source="a"/></graph>"#);
+        let err = missing.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "edge 1: missing 'target' attribute");
+
+        let weight = parse_graphml(
+            r#"<key id="w" for="edge" attr.name="weight" attr.type="int"/>
+            <edge source="a" target="b"/>
+            <edge source="b" target="c"><data key="w">heavy</data></edge>"#,
+        );
+        assert_eq!(
+            weight.err().unwrap().to_string(),
+            "data 1 in edge 2: 'heavy' is not a weight"
+        );
+    }
+}
@@ -4,18 +4,37 @@
 //! file, You can obtain one at https://www.gnu.org/licenses/gpl-3.0.html
 
 use crate::graph::*;
+use crate::operators::{
+    CrossoverType, GenerationCallback, InitStrategy, ModularityVariant, MutationSchedule, install,
+    link_conflict, seeded_rng,
+};
 
+use rand::Rng;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, HashMap};
+use std::ffi::CString;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, ErrorKind};
+use std::sync::Mutex;
 
+use pyo3::exceptions::{PyRuntimeError, PyUserWarning, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::{PyAny, PyDict};
+use pyo3::types::{PyAny, PyBytes, PyDict, PyList, PyString};
 
+mod graphml;
+
+/// Relabels communities as 0, 1, 2, ... in order of first appearance while
+/// walking nodes in ascending `NodeId` order. The result depends only on the
+/// grouping, so two partitions with the same communities normalize to equal maps.
+/// For graphs whose labels were remapped (see `NodeLabels`), ascending id order is
+/// the order in which the labels first appeared in the input.
 pub fn normalize_community_ids(partition: Partition) -> Partition {
     let mut new_partition = Partition::new();
     let mut id_mapping = HashMap::new();
     let mut next_id = 0;
 
-    // Create a new mapping for community IDs
+    // `Partition` is a BTreeMap, so this walks nodes in ascending order
     for (node_id, &community_id) in partition.iter() {
         if let std::collections::hash_map::Entry::Vacant(e) = id_mapping.entry(community_id) {
             e.insert(next_id);
@@ -27,35 +46,1487 @@ pub fn normalize_community_ids(partition: Partition) -> Partition {
     new_partition
 }
 
-/// Convert Python dict to Rust partition
-pub fn to_partition(py_dict: &Bound<'_, PyDict>) -> PyResult<Partition> {
+/// Number of distinct communities in `partition`, the ids left after
+/// `normalize_community_ids`
+pub fn community_count(partition: &Partition) -> usize {
+    normalize_community_ids(partition.clone())
+        .values()
+        .max()
+        .map_or(0, |&max_id| max_id as usize + 1)
+}
+
+/// Nodes of each community, with communities ordered by their id after
+/// `normalize_community_ids` and nodes ascending within each
+pub fn community_sets(partition: Partition) -> Vec<Vec<NodeId>> {
+    let mut sets: Vec<Vec<NodeId>> = Vec::new();
+    for (node, community) in normalize_community_ids(partition) {
+        // Normalized ids first appear in increasing order
+        if community as usize == sets.len() {
+            sets.push(Vec::new());
+        }
+        sets[community as usize].push(node);
+    }
+    sets
+}
+
+/// `(node, community)` pairs in ascending node order, with communities relabelled
+/// by `normalize_community_ids`
+pub fn partition_records(partition: Partition) -> Vec<(NodeId, CommunityId)> {
+    normalize_community_ids(partition).into_iter().collect()
+}
+
+/// `(from, to, weight)` edges over `NodeId`s
+pub type WeightedEdges = Vec<(NodeId, NodeId, f64)>;
+
+/// `(from, to, weight)` edges over Python node labels
+pub type LabeledEdges<'py> = Vec<(Bound<'py, PyAny>, Bound<'py, PyAny>, f64)>;
+
+/// Edges read by `get_edges`, with every node the input lists on its own (NetworkX
+/// nodes, matrix rows, GraphML `<node>`s) so that nodes without edges are kept
+#[derive(Debug, Default)]
+pub struct GraphInput {
+    pub edges: WeightedEdges,
+    pub nodes: Vec<NodeId>,
+}
+
+impl From<WeightedEdges> for GraphInput {
+    fn from(edges: WeightedEdges) -> Self {
+        GraphInput {
+            edges,
+            nodes: Vec::new(),
+        }
+    }
+}
+
+/// Dense `NodeId`s for arbitrary hashable Python node labels, and the labels back.
+/// Ids are handed out in order of first appearance; lookups go through a Python
+/// dict, so labels compare the way they do in NetworkX (`1 == 1.0 == True`).
+pub struct NodeLabels {
+    index: Py<PyDict>,     // label -> NodeId
+    labels: Vec<PyObject>, // NodeId -> label
+}
+
+impl NodeLabels {
+    pub fn new(py: Python<'_>) -> Self {
+        NodeLabels {
+            index: PyDict::new(py).unbind(),
+            labels: Vec::new(),
+        }
+    }
+
+    /// Id of `label`, assigning the next free one when it is new
+    pub fn intern(&mut self, label: &Bound<'_, PyAny>) -> PyResult<NodeId> {
+        let index = self.index.bind(label.py());
+        if let Some(id) = index.get_item(label)? {
+            return id.extract();
+        }
+        let id = NodeId::try_from(self.labels.len())
+            .map_err(|_| PyValueError::new_err("too many distinct node labels"))?;
+        index.set_item(label, id)?;
+        self.labels.push(label.clone().unbind());
+        Ok(id)
+    }
+
+    /// Id of a known `label`; raises ValueError for labels not seen when building
+    pub fn id(&self, label: &Bound<'_, PyAny>) -> PyResult<NodeId> {
+        match self.find(label)? {
+            Some(id) => Ok(id),
+            None => Err(PyValueError::new_err(format!(
+                "node {} is not in the graph",
+                label.repr()?
+            ))),
+        }
+    }
+
+    /// Id of `label`, or None for labels not seen when building
+    pub fn find(&self, label: &Bound<'_, PyAny>) -> PyResult<Option<NodeId>> {
+        self.index
+            .bind(label.py())
+            .get_item(label)?
+            .map(|id| id.extract())
+            .transpose()
+    }
+
+    pub fn label<'py>(&self, py: Python<'py>, id: NodeId) -> Bound<'py, PyAny> {
+        self.labels[id as usize].bind(py).clone()
+    }
+
+    /// Reads a partition dict, interning its node labels
+    pub fn intern_partition(&mut self, py_dict: &Bound<'_, PyDict>) -> PyResult<Partition> {
+        let mut part = BTreeMap::new();
+        for (node, comm) in py_dict.iter() {
+            part.insert(self.intern(&node)?, comm.extract::<CommunityId>()?);
+        }
+        Ok(part)
+    }
+
+    /// Independent copy of the labels, so later interning does not show up in it
+    pub fn copy(&self, py: Python<'_>) -> PyResult<Self> {
+        Ok(NodeLabels {
+            index: self.index.bind(py).copy()?.unbind(),
+            labels: self
+                .labels
+                .iter()
+                .map(|label| label.clone_ref(py))
+                .collect(),
+        })
+    }
+
+    /// Pickles the labels in id order, for snapshots
+    pub fn to_bytes(&self, py: Python<'_>) -> PyResult<Vec<u8>> {
+        let labels = PyList::new(py, self.labels.iter().map(|label| label.bind(py)))?;
+        py.import("pickle")?
+            .call_method1("dumps", (labels,))?
+            .extract()
+    }
+
+    /// Inverse of `to_bytes`
+    pub fn from_bytes(py: Python<'_>, bytes: &[u8]) -> PyResult<Self> {
+        let labels = py
+            .import("pickle")?
+            .call_method1("loads", (PyBytes::new(py, bytes),))?;
+        let mut node_labels = NodeLabels::new(py);
+        for label in labels.try_iter()? {
+            node_labels.intern(&label?)?;
+        }
+        Ok(node_labels)
+    }
+}
+
+/// Convert Python dict to Rust partition. With `labels`, keys are node labels of the
+/// graph the labels were built from; otherwise they must be integer node ids.
+pub fn to_partition(
+    py_dict: &Bound<'_, PyDict>,
+    labels: Option<&NodeLabels>,
+) -> PyResult<Partition> {
     let mut part = BTreeMap::new();
     for (node, comm) in py_dict.iter() {
-        part.insert(node.extract::<NodeId>()?, comm.extract::<CommunityId>()?);
+        let node = match labels {
+            Some(labels) => labels.id(&node)?,
+            None => node.extract::<NodeId>()?,
+        };
+        part.insert(node, comm.extract::<CommunityId>()?);
     }
     Ok(part)
 }
 
-/// Get edges from NetworkX graph
-pub fn get_edges(graph: &Bound<'_, PyAny>) -> PyResult<Vec<(NodeId, NodeId)>> {
+/// Convert a Rust partition (or any per-node map, such as soft memberships) to a
+/// Python dict keyed by the original node labels
+pub fn from_partition<'py, V>(
+    py: Python<'py>,
+    partition: &BTreeMap<NodeId, V>,
+    labels: Option<&NodeLabels>,
+) -> PyResult<Bound<'py, PyDict>>
+where
+    V: IntoPyObject<'py> + Clone,
+{
+    let dict = PyDict::new(py);
+    for (&node, community) in partition {
+        match labels {
+            Some(labels) => dict.set_item(labels.label(py, node), community.clone())?,
+            None => dict.set_item(node, community.clone())?,
+        }
+    }
+    Ok(dict)
+}
+
+/// Lazy iterator over a fitted front, returned by the estimators' `iter_front`. Yields
+/// `(partition, objectives)` like `front()`, building each partition dict only when it
+/// is reached. It owns a copy of the front, so refitting the estimator does not
+/// change it.
+#[pyclass]
+pub struct ParetoIterator {
+    front: std::vec::IntoIter<(Partition, Vec<f64>)>,
+    labels: Option<NodeLabels>,
+}
+
+impl ParetoIterator {
+    pub fn new(front: Vec<(Partition, Vec<f64>)>, labels: Option<NodeLabels>) -> Self {
+        ParetoIterator {
+            front: front.into_iter(),
+            labels,
+        }
+    }
+}
+
+#[pymethods]
+impl ParetoIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(
+        &mut self,
+        py: Python<'py>,
+    ) -> PyResult<Option<(Bound<'py, PyDict>, Vec<f64>)>> {
+        let Some((partition, objectives)) = self.front.next() else {
+            return Ok(None);
+        };
+        let partition = from_partition(
+            py,
+            &normalize_community_ids(partition),
+            self.labels.as_ref(),
+        )?;
+        Ok(Some((partition, objectives)))
+    }
+
+    /// Solutions not yielded yet
+    fn __len__(&self) -> usize {
+        self.front.len()
+    }
+}
+
+/// The Python labels of `nodes`: the original labels, or the ids themselves
+pub fn node_labels<'py>(
+    py: Python<'py>,
+    nodes: &[NodeId],
+    labels: Option<&NodeLabels>,
+) -> PyResult<Vec<Bound<'py, PyAny>>> {
+    nodes
+        .iter()
+        .map(|&node| match labels {
+            Some(labels) => Ok(labels.label(py, node)),
+            None => Ok(node.into_pyobject(py)?.into_any()),
+        })
+        .collect()
+}
+
+/// `partition_records` over the Python labels of the nodes
+pub fn records_with_labels<'py>(
+    py: Python<'py>,
+    records: Vec<(NodeId, CommunityId)>,
+    labels: Option<&NodeLabels>,
+) -> PyResult<Vec<(Bound<'py, PyAny>, CommunityId)>> {
+    let nodes: Vec<NodeId> = records.iter().map(|&(node, _)| node).collect();
+    let nodes = node_labels(py, &nodes, labels)?;
+    Ok(nodes
+        .into_iter()
+        .zip(records)
+        .map(|(node, (_, community))| (node, community))
+        .collect())
+}
+
+/// The edges of `graph` as `(u, v, weight)` tuples over the original node labels,
+/// accepted back by every estimator
+pub fn edge_list<'py>(
+    py: Python<'py>,
+    graph: &Graph,
+    labels: Option<&NodeLabels>,
+) -> PyResult<LabeledEdges<'py>> {
+    graph
+        .edges
+        .iter()
+        .map(|&(from, to)| {
+            let mut ends = node_labels(py, &[from, to], labels)?.into_iter();
+            Ok((
+                ends.next().unwrap(),
+                ends.next().unwrap(),
+                graph.weight(from, to),
+            ))
+        })
+        .collect()
+}
+
+/// Runs `run` with an optional Python callable adapted as the per-generation
+/// hook, on `num_threads` workers (see `operators::install`). The GIL is
+/// released for the run and re-acquired for every call; if the callable
+/// raises, the run is stopped and the exception is returned instead of the result.
+pub fn with_generation_callback<T: Send>(
+    callback: Option<&Py<PyAny>>,
+    num_threads: Option<usize>,
+    run: impl FnOnce(Option<&GenerationCallback<'_>>) -> T + Send,
+) -> PyResult<T> {
+    let error: Mutex<Option<PyErr>> = Mutex::new(None);
+    let hook = |generation: usize, best_q: f64| {
+        let Some(callback) = callback else {
+            return true;
+        };
+        match Python::with_gil(|py| callback.call1(py, (generation, best_q))) {
+            Ok(_) => true,
+            Err(err) => {
+                *error.lock().unwrap() = Some(err);
+                false
+            }
+        }
+    };
+    let on_generation = callback.map(|_| &hook as &GenerationCallback<'_>);
+
+    let result =
+        Python::with_gil(|py| py.allow_threads(|| install(num_threads, || run(on_generation))))
+            .map_err(|err| PyRuntimeError::new_err(format!("cannot build thread pool: {}", err)))?;
+    match error.into_inner().unwrap() {
+        Some(err) => Err(err),
+        None => Ok(result),
+    }
+}
+
+/// Adapts Python objective callables for `ObjectiveFn`: each is called with the
+/// normalized partition as a dict keyed by the original labels and must return a
+/// float. The first exception lands in `error`; from then on every partition scores
+/// +inf without calling back into Python.
+pub fn python_objectives<'a>(
+    callables: &'a [Py<PyAny>],
+    labels: Option<&'a NodeLabels>,
+    error: &'a Mutex<Option<PyErr>>,
+) -> impl Fn(&Partition) -> Vec<f64> + Sync + 'a {
+    move |partition| {
+        let failed = vec![f64::INFINITY; callables.len()];
+        if error.lock().unwrap().is_some() {
+            return failed;
+        }
+        let scores = Python::with_gil(|py| {
+            let partition =
+                from_partition(py, &normalize_community_ids(partition.clone()), labels)?;
+            callables
+                .iter()
+                .map(|objective| objective.call1(py, (&partition,))?.extract::<f64>(py))
+                .collect::<PyResult<Vec<f64>>>()
+        });
+        scores.unwrap_or_else(|err| {
+            error.lock().unwrap().get_or_insert(err);
+            failed
+        })
+    }
+}
+
+/// `[intra, inter]` objectives as `{"intra", "inter", "modularity"}`, with modularity
+/// read as 1 - intra - inter
+pub fn objective_dict<'py>(py: Python<'py>, objectives: &[f64]) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("intra", objectives[0])?;
+    dict.set_item("inter", objectives[1])?;
+    dict.set_item("modularity", 1.0 - objectives[0] - objectives[1])?;
+    Ok(dict)
+}
+
+/// Front solutions as CSV text: an `intra,inter,modularity,num_communities` header and
+/// one row per solution, modularity read as 1 - intra - inter like `objective_dict`.
+/// Floats use Rust's shortest round-trip formatting.
+pub fn front_csv<'a>(solutions: impl Iterator<Item = (&'a Partition, &'a [f64])>) -> String {
+    let mut csv = String::from("intra,inter,modularity,num_communities\n");
+    for (partition, objectives) in solutions {
+        let (intra, inter) = (objectives[0], objectives[1]);
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            intra,
+            inter,
+            1.0 - intra - inter,
+            community_count(partition)
+        ));
+    }
+    csv
+}
+
+/// Objective rows as an (N, 2) `numpy.ndarray` of [intra, inter]. NumPy is imported
+/// at call time, so it is only needed by callers of this helper.
+pub fn objectives_array<'a>(
+    py: Python<'_>,
+    objectives: impl Iterator<Item = &'a Vec<f64>>,
+) -> PyResult<PyObject> {
+    let rows: Vec<Vec<f64>> = objectives.cloned().collect();
+    numpy_array(py, rows, "float64", 2)
+}
+
+/// The graph's `adjacency_matrix` as an (N, N) int32 `numpy.ndarray`.
+pub fn adjacency_array(py: Python<'_>, graph: &Graph) -> PyResult<PyObject> {
+    numpy_array(py, graph.adjacency_matrix(), "int32", graph.num_nodes())
+}
+
+/// A copy of the graph's `adjacency_list` as a dict of neighbour lists keyed by the
+/// original labels, nodes in `sorted_nodes` order and neighbours in insertion order
+pub fn adjacency_dict<'py>(
+    py: Python<'py>,
+    graph: &Graph,
+    labels: Option<&NodeLabels>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for node in graph.sorted_nodes() {
+        let neighbors = node_labels(py, graph.neighbors(&node), labels)?;
+        dict.set_item(&node_labels(py, &[node], labels)?[0], neighbors)?;
+    }
+    Ok(dict)
+}
+
+fn numpy_array<T>(py: Python<'_>, rows: Vec<Vec<T>>, dtype: &str, cols: usize) -> PyResult<PyObject>
+where
+    for<'py> T: IntoPyObject<'py>,
+{
+    let numpy = py.import("numpy")?;
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("dtype", numpy.getattr(dtype)?)?;
+    // The explicit shape keeps the column count when there are no rows
+    let shape = (rows.len(), cols);
+    Ok(numpy
+        .call_method("array", (rows,), Some(&kwargs))?
+        .call_method1("reshape", (shape,))?
+        .unbind())
+}
+
+/// Ensure two partitions are defined over the same node set
+pub fn check_same_nodes(a: &Partition, b: &Partition) -> PyResult<()> {
+    let missing = a.keys().filter(|node| !b.contains_key(node)).count();
+    let extra = b.keys().filter(|node| !a.contains_key(node)).count();
+    if missing > 0 || extra > 0 {
+        return Err(PyValueError::new_err(format!(
+            "partitions cover different nodes ({} only in the first, {} only in the second)",
+            missing, extra
+        )));
+    }
+    Ok(())
+}
+
+/// Ensure a partition assigns exactly the nodes of `graph`
+pub fn check_partition_nodes(graph: &Graph, partition: &Partition) -> PyResult<()> {
+    let missing = graph
+        .nodes
+        .iter()
+        .filter(|node| !partition.contains_key(node))
+        .count();
+    let extra = partition
+        .keys()
+        .filter(|node| !graph.nodes.contains(node))
+        .count();
+    if missing > 0 || extra > 0 {
+        return Err(PyValueError::new_err(format!(
+            "partition does not match the graph ({} graph nodes unassigned, {} unknown nodes)",
+            missing, extra
+        )));
+    }
+    Ok(())
+}
+
+/// Nodes of `graph` missing from `assigned`, in ascending order
+pub fn missing_nodes(graph: &Graph, assigned: &FxHashSet<NodeId>) -> Vec<NodeId> {
+    graph
+        .sorted_nodes()
+        .into_iter()
+        .filter(|node| !assigned.contains(node))
+        .collect()
+}
+
+/// Id of a `node` of `graph`, given by its label; raises ValueError if it is not there
+pub fn node_id(
+    graph: &Graph,
+    labels: Option<&NodeLabels>,
+    node: &Bound<'_, PyAny>,
+) -> PyResult<NodeId> {
+    let id = match labels {
+        Some(labels) => labels.id(node)?,
+        None => node.extract()?,
+    };
+    if !graph.nodes.contains(&id) {
+        return Err(PyValueError::new_err(format!(
+            "node {} is not in the graph",
+            node.repr()?
+        )));
+    }
+    Ok(id)
+}
+
+/// Stores `node_weights` (keyed like a partition) on `graph` for the modularity null
+/// model; nodes left out weigh 1.0. Raises ValueError for nodes outside the graph and
+/// for weights that are not positive and finite.
+pub fn set_node_weights(
+    graph: &mut Graph,
+    node_weights: &Bound<'_, PyDict>,
+    labels: Option<&NodeLabels>,
+) -> PyResult<()> {
+    for (node, weight) in node_weights.iter() {
+        let id = node_id(graph, labels, &node)?;
+        let weight: f64 = weight.extract()?;
+        if !(weight > 0.0 && weight.is_finite()) {
+            return Err(PyValueError::new_err(format!(
+                "node weights must be positive and finite, got {} for node {}",
+                weight,
+                node.repr()?
+            )));
+        }
+        graph.set_node_weight(id, weight);
+    }
+    Ok(())
+}
+
+/// A list of node pairs, given by their labels, as passed to `must_link`/`cannot_link`
+pub type LinkPairs<'py> = Vec<(Bound<'py, PyAny>, Bound<'py, PyAny>)>;
+type LinkIds = Vec<(NodeId, NodeId)>;
+
+/// Ids of the `must_link` and `cannot_link` node pairs. Raises ValueError for nodes
+/// outside the graph and for a cannot-link pair no partition can satisfy: a node
+/// paired with itself, or two nodes joined by a chain of must-links.
+pub fn link_constraints(
+    py: Python<'_>,
+    graph: &Graph,
+    labels: Option<&NodeLabels>,
+    must_link: Option<LinkPairs<'_>>,
+    cannot_link: Option<LinkPairs<'_>>,
+) -> PyResult<(LinkIds, LinkIds)> {
+    let ids = |pairs: Option<LinkPairs<'_>>| -> PyResult<LinkIds> {
+        pairs
+            .unwrap_or_default()
+            .iter()
+            .map(|(a, b)| Ok((node_id(graph, labels, a)?, node_id(graph, labels, b)?)))
+            .collect()
+    };
+    let (must_link, cannot_link) = (ids(must_link)?, ids(cannot_link)?);
+    if let Some((a, b)) = link_conflict(&must_link, &cannot_link) {
+        let pair = node_labels(py, &[a, b], labels)?;
+        return Err(PyValueError::new_err(format!(
+            "cannot_link pair ({}, {}) can never be separated, its nodes are the same or \
+             joined by must_link",
+            pair[0].repr()?,
+            pair[1].repr()?
+        )));
+    }
+    Ok((must_link, cannot_link))
+}
+
+/// Switches to Barber's bipartite modularity when `bipartite` is set, storing
+/// `node_types` (keyed like a partition, 0 or 1 per node) on `graph` and returning the
+/// `Bipartite` variant; otherwise returns `variant` unchanged. Raises ValueError when
+/// the flag and the types do not come together, when the graph is directed or another
+/// `modularity_variant` was asked for, and for nodes without a 0/1 type.
+pub fn set_bipartite(
+    graph: &mut Graph,
+    bipartite: bool,
+    node_types: Option<&Bound<'_, PyDict>>,
+    labels: Option<&NodeLabels>,
+    variant: ModularityVariant,
+) -> PyResult<ModularityVariant> {
+    let Some(node_types) = node_types.filter(|_| bipartite) else {
+        return match (bipartite, node_types) {
+            (false, None) => Ok(variant),
+            (false, Some(_)) => Err(PyValueError::new_err("node_types needs bipartite=True")),
+            (true, _) => Err(PyValueError::new_err("bipartite=True needs node_types")),
+        };
+    };
+    if graph.directed {
+        return Err(PyValueError::new_err(
+            "bipartite modularity is undirected, drop directed=True",
+        ));
+    }
+    if variant != ModularityVariant::Newman {
+        return Err(PyValueError::new_err(
+            "bipartite=True replaces modularity_variant, leave it at 'newman'",
+        ));
+    }
+
+    for (node, node_type) in node_types.iter() {
+        let id = node_id(graph, labels, &node)?;
+        let node_type: u8 = match node_type.extract() {
+            Ok(node_type @ (0 | 1)) => node_type,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "node types must be 0 or 1, got {} for node {}",
+                    node_type.repr()?,
+                    node.repr()?
+                )));
+            }
+        };
+        graph.node_types.insert(id, node_type);
+    }
+    if let Some(&untyped) = graph
+        .sorted_nodes()
+        .iter()
+        .find(|node| !graph.node_types.contains_key(node))
+    {
+        let py = node_types.py();
+        return Err(PyValueError::new_err(format!(
+            "node {} has no entry in node_types",
+            node_labels(py, &[untyped], labels)?[0].repr()?
+        )));
+    }
+    Ok(ModularityVariant::Bipartite)
+}
+
+/// Ids for a `node` that is not in `graph` yet and for its `neighbors`, which must be.
+/// Integer nodes keep their value; a new labelled node takes the id after the largest
+/// in use. Raises ValueError otherwise.
+pub fn new_node_ids(
+    graph: &Graph,
+    labels: Option<&NodeLabels>,
+    node: &Bound<'_, PyAny>,
+    neighbors: &[Bound<'_, PyAny>],
+) -> PyResult<(NodeId, Vec<NodeId>)> {
+    let known = match labels {
+        Some(labels) => labels.id(node).is_ok(),
+        None => graph.nodes.contains(&node.extract::<NodeId>()?),
+    };
+    if known {
+        return Err(PyValueError::new_err(format!(
+            "node {} is already in the graph",
+            node.repr()?
+        )));
+    }
+    let id = match labels {
+        Some(_) => graph.nodes.iter().max().map_or(0, |&max| max + 1),
+        None => node.extract()?,
+    };
+
+    let neighbors = neighbors
+        .iter()
+        .map(|neighbor| {
+            let neighbor_id = match labels {
+                Some(labels) => labels.id(neighbor)?,
+                None => neighbor.extract()?,
+            };
+            if !graph.nodes.contains(&neighbor_id) {
+                return Err(PyValueError::new_err(format!(
+                    "node {} is not in the graph",
+                    neighbor.repr()?
+                )));
+            }
+            Ok(neighbor_id)
+        })
+        .collect::<PyResult<_>>()?;
+    Ok((id, neighbors))
+}
+
+/// Ids of the ends of an edge given by node labels. With `labels`, unknown labels get
+/// the next free id when `intern` is set and raise ValueError otherwise; integer nodes
+/// keep their value.
+pub fn edge_node_ids(
+    mut labels: Option<&mut NodeLabels>,
+    u: &Bound<'_, PyAny>,
+    v: &Bound<'_, PyAny>,
+    intern: bool,
+) -> PyResult<(NodeId, NodeId)> {
+    let mut id = |node: &Bound<'_, PyAny>| match labels.as_deref_mut() {
+        Some(labels) if intern => labels.intern(node),
+        Some(labels) => labels.id(node),
+        None => node.extract::<NodeId>(),
+    };
+    Ok((id(u)?, id(v)?))
+}
+
+/// Ensure a soft-membership threshold is a fraction of incident edges
+pub fn check_overlap_threshold(threshold: Option<f64>) -> PyResult<()> {
+    match threshold {
+        Some(threshold) if !(0.0..=1.0).contains(&threshold) => Err(PyValueError::new_err(
+            format!("overlap_threshold must be in [0, 1], got {}", threshold),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Emits a Python `UserWarning` when `graph` has more than one connected component,
+/// since modularity then also rewards keeping the components apart
+pub fn warn_if_disconnected(py: Python<'_>, graph: &Graph, estimator: &str) -> PyResult<()> {
+    let num_components = graph.connected_components().len();
+    if num_components <= 1 {
+        return Ok(());
+    }
+    let message = CString::new(format!(
+        "[{}::new]: graph has {} connected components",
+        estimator, num_components
+    ))?;
+    PyErr::warn(py, &py.get_type::<PyUserWarning>(), &message, 1)
+}
+
+/// Installs `env_logger` on stdout the first time it is called, then sets the global
+/// `log` level. The logger itself lets every record through so later calls can raise
+/// or lower the level; a logger installed by someone else is kept as is.
+pub fn init_logging(level: log::LevelFilter) {
+    let _ = env_logger::Builder::new()
+        .filter_level(log::LevelFilter::Trace)
+        .target(env_logger::Target::Stdout)
+        .try_init();
+    log::set_max_level(level);
+}
+
+/// Turns on the logging `debug_level` asks for: info at 1, debug from 2. Never lowers
+/// a level already set through `init_logging`.
+pub fn enable_debug_logging(debug_level: i8) {
+    let level = match debug_level {
+        i8::MIN..=0 => return,
+        1 => log::LevelFilter::Info,
+        _ => log::LevelFilter::Debug,
+    };
+    if log::max_level() < level {
+        init_logging(level);
+    }
+}
+
+/// Stream of `operators::seeded_rng` reserved for edge sampling, apart from the ones
+/// the optimizers draw from
+const EDGE_SAMPLE_STREAM: usize = usize::MAX;
+
+/// `graph` reduced to a random share `rate` of its edges (all nodes kept) when a rate
+/// is given, reproducibly under `seed`. Raises ValueError unless 0 < rate <= 1.
+pub fn sample_graph(graph: Graph, rate: Option<f64>, seed: Option<u64>) -> PyResult<Graph> {
+    let Some(rate) = rate else {
+        return Ok(graph);
+    };
+    if !(rate > 0.0 && rate <= 1.0) {
+        return Err(PyValueError::new_err(format!(
+            "edge_sample_rate must be in (0, 1], got {}",
+            rate
+        )));
+    }
+    let seed = seed.unwrap_or_else(|| rand::rng().random());
+    let mut rng = seeded_rng(seed, 0, EDGE_SAMPLE_STREAM);
+    Ok(graph.sample_edges(rate, &mut rng))
+}
+
+/// Most decimals `round_objectives` accepts; f64 holds about 15 significant digits
+const MAX_OBJECTIVE_DECIMALS: u32 = 15;
+
+/// Ensure `round_objectives`, when set, asks for no more decimals than f64 can hold
+pub fn check_round_objectives(decimals: Option<u32>) -> PyResult<()> {
+    match decimals {
+        Some(decimals) if decimals > MAX_OBJECTIVE_DECIMALS => Err(PyValueError::new_err(format!(
+            "round_objectives must be at most {}, got {}",
+            MAX_OBJECTIVE_DECIMALS, decimals
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Ensure a crossover or mutation rate is a probability
+pub fn check_rate(name: &str, rate: f64) -> PyResult<()> {
+    if (0.0..=1.0).contains(&rate) {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "{} must be in [0, 1], got {}",
+            name, rate
+        )))
+    }
+}
+
+/// Ensure fixed hypergrid bounds come together, finite, with `obj_min` below
+/// `obj_max` in each objective
+pub fn check_objective_bounds(
+    obj_min: Option<(f64, f64)>,
+    obj_max: Option<(f64, f64)>,
+) -> PyResult<()> {
+    let (min, max) = match (obj_min, obj_max) {
+        (None, None) => return Ok(()),
+        (Some(min), Some(max)) => (min, max),
+        _ => {
+            return Err(PyValueError::new_err(
+                "obj_min and obj_max must be given together",
+            ));
+        }
+    };
+    let finite = [min.0, min.1, max.0, max.1].iter().all(|v| v.is_finite());
+    if !finite || min.0 >= max.0 || min.1 >= max.1 {
+        return Err(PyValueError::new_err(format!(
+            "obj_min {:?} must be finite and below obj_max {:?} in both objectives",
+            min, max
+        )));
+    }
+    Ok(())
+}
+
+/// Reads a `modularity_variant` name, raising ValueError for unknown ones
+pub fn parse_modularity_variant(name: &str) -> PyResult<ModularityVariant> {
+    ModularityVariant::parse(name).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "modularity_variant must be 'newman' or 'dcsbm', got '{}'",
+            name
+        ))
+    })
+}
+
+/// Reads an optional `crossover_type` name, raising ValueError for unknown ones
+pub fn parse_crossover_type(name: Option<&str>) -> PyResult<Option<CrossoverType>> {
+    name.map(|name| {
+        CrossoverType::parse(name).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "crossover_type must be 'ensemble', 'uniform', 'one_point' or 'two_point', \
+                 got '{}'",
+                name
+            ))
+        })
+    })
+    .transpose()
+}
+
+/// Reads an `init_strategy` name, raising ValueError for unknown ones
+pub fn parse_init_strategy(name: &str) -> PyResult<InitStrategy> {
+    InitStrategy::parse(name).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "init_strategy must be 'random' or 'label_propagation', got '{}'",
+            name
+        ))
+    })
+}
+
+/// Reads a `mut_rate_schedule` name, raising ValueError for unknown ones or for a
+/// decaying schedule whose `mut_rate_min` is not a rate at most `mut_rate`
+pub fn parse_mutation_schedule(
+    name: &str,
+    mut_rate: f64,
+    mut_rate_min: f64,
+) -> PyResult<MutationSchedule> {
+    let schedule = MutationSchedule::parse(name).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "mut_rate_schedule must be 'constant', 'linear' or 'exponential', got '{}'",
+            name
+        ))
+    })?;
+    check_rate("mut_rate_min", mut_rate_min)?;
+    if schedule != MutationSchedule::Constant && mut_rate_min > mut_rate {
+        return Err(PyValueError::new_err(format!(
+            "mut_rate_min ({}) must not exceed mut_rate ({})",
+            mut_rate_min, mut_rate
+        )));
+    }
+    Ok(schedule)
+}
+
+/// Ensure a modularity resolution is a positive number
+pub fn check_resolution(resolution: f64) -> PyResult<()> {
+    if resolution > 0.0 && resolution.is_finite() {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "resolution must be positive, got {}",
+            resolution
+        )))
+    }
+}
+
+/// Edges read from a file by `from_file` or `from_graphml`, passed to an estimator's
+/// constructor in place of a Python graph so they never become Python objects
+#[pyclass]
+pub struct EdgeFile {
+    edges: WeightedEdges,
+    names: Option<Vec<String>>, // NodeId -> string label, for GraphML node ids
+}
+
+impl EdgeFile {
+    /// Reads the edge list at `path`, see `read_edge_list`. Malformed lines raise
+    /// ValueError, I/O failures OSError.
+    pub fn read(path: &str, delimiter: Option<&str>) -> PyResult<Self> {
+        let edges = file_result(
+            path,
+            File::open(path).and_then(|file| read_edge_list(BufReader::new(file), delimiter)),
+        )?;
+        Ok(EdgeFile { edges, names: None })
+    }
+
+    /// Reads the GraphML document at `path`, see `graphml::parse_graphml`. Its node ids
+    /// become string labels. Malformed elements raise ValueError, I/O failures OSError.
+    pub fn read_graphml(path: &str) -> PyResult<Self> {
+        let graph = file_result(
+            path,
+            std::fs::read_to_string(path).and_then(|text| graphml::parse_graphml(&text)),
+        )?;
+        Ok(EdgeFile {
+            edges: graph.edges,
+            names: Some(graph.names),
+        })
+    }
+}
+
+/// Raises `InvalidData` as ValueError prefixed with `path`, other errors as OSError
+fn file_result<T>(path: &str, result: io::Result<T>) -> PyResult<T> {
+    result.map_err(|err| match err.kind() {
+        ErrorKind::InvalidData => PyValueError::new_err(format!("{}: {}", path, err)),
+        _ => err.into(),
+    })
+}
+
+/// Reads `u v` or `u v weight` lines of integer node ids, split on `delimiter` or on
+/// any whitespace when `None`. Blank lines and lines starting with `#` are skipped; a
+/// malformed line fails with `InvalidData` and its 1-based line number.
+fn read_edge_list(reader: impl BufRead, delimiter: Option<&str>) -> io::Result<WeightedEdges> {
+    let mut edges = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = match delimiter {
+            Some(delimiter) => line.split(delimiter).map(str::trim).collect(),
+            None => line.split_whitespace().collect(),
+        };
+        let malformed = |reason: String| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("line {}: {}", index + 1, reason),
+            )
+        };
+        let node = |field: &str| {
+            field
+                .parse::<NodeId>()
+                .map_err(|_| malformed(format!("'{}' is not an integer node id", field)))
+        };
+
+        let weight = match fields.len() {
+            2 => 1.0,
+            3 => fields[2]
+                .parse::<f64>()
+                .map_err(|_| malformed(format!("'{}' is not a weight", fields[2])))?,
+            len => return Err(malformed(format!("expected 2 or 3 columns, got {}", len))),
+        };
+        edges.push((node(fields[0])?, node(fields[1])?, weight));
+    }
+
+    Ok(edges)
+}
+
+/// Get edges from a NetworkX graph, a SciPy sparse adjacency matrix or an edge list.
+///
+/// NetworkX graphs have their `weight` attribute read when present (defaults
+/// to 1.0). CSR (`indptr`/`indices`/`data`) and COO (`row`/`col`/`data`) matrices
+/// contribute their nonzero upper triangle, with `data` as edge weights. Any other
+/// iterable is treated as an edge list of `(u, v)` or `(u, v, weight)` tuples.
+/// Nodes without edges are kept for NetworkX graphs (every node of `graph.nodes()`),
+/// matrices (every row) and GraphML files (every `<node>`); edge lists cannot hold them.
+///
+/// Integer node labels that fit in a signed 32-bit integer are used as ids directly
+/// and no `NodeLabels` are returned. Any other hashable labels (strings, floats,
+/// tuples, large integers) are remapped to dense ids and returned with the map back.
+pub fn get_edges(graph: &Bound<'_, PyAny>) -> PyResult<(GraphInput, Option<NodeLabels>)> {
+    if let Ok(file) = graph.downcast::<EdgeFile>() {
+        let mut file = file.borrow_mut();
+        let mut nodes = Vec::new();
+        let labels = match file.names.take() {
+            Some(names) => {
+                let mut labels = NodeLabels::new(graph.py());
+                for name in names {
+                    nodes.push(labels.intern(&PyString::new(graph.py(), &name))?);
+                }
+                Some(labels)
+            }
+            None => None,
+        };
+        let edges = std::mem::take(&mut file.edges);
+        return Ok((GraphInput { edges, nodes }, labels));
+    }
+    if graph.hasattr("indptr")? || graph.hasattr("row")? {
+        return Ok((get_sparse_edges(graph)?, None));
+    }
+    if !graph.hasattr("edges")? {
+        return label_edges(graph.py(), get_edge_list(graph)?, Vec::new());
+    }
+
     let mut edges = Vec::new();
-    let edges_iter = graph.call_method0("edges")?.call_method0("__iter__")?;
+    let kwargs = PyDict::new(graph.py());
+    kwargs.set_item("data", "weight")?;
+    kwargs.set_item("default", 1.0)?;
+    let edges_iter = graph
+        .call_method("edges", (), Some(&kwargs))?
+        .call_method0("__iter__")?;
 
     for edge in edges_iter.try_iter()? {
         let edge = edge?;
-        let from = edge.get_item(0)?.extract()?;
-        let to = edge.get_item(1)?.extract()?;
-        edges.push((from, to));
+        let weight = edge.get_item(2)?.extract()?;
+        edges.push((edge.get_item(0)?, edge.get_item(1)?, weight));
+    }
+    let nodes = graph
+        .call_method0("nodes")?
+        .try_iter()?
+        .collect::<PyResult<Vec<_>>>()?;
+
+    label_edges(graph.py(), edges, nodes)
+}
+
+/// Keeps integer labels as ids when all of them fit, otherwise remaps every label.
+/// Edge ends are interned before `nodes`, so ids follow the edges as without them.
+fn label_edges<'py>(
+    py: Python<'py>,
+    edges: LabeledEdges<'py>,
+    nodes: Vec<Bound<'py, PyAny>>,
+) -> PyResult<(GraphInput, Option<NodeLabels>)> {
+    let ids: Option<WeightedEdges> = edges
+        .iter()
+        .map(|(from, to, weight)| Some((from.extract().ok()?, to.extract().ok()?, *weight)))
+        .collect();
+    let node_ids: Option<Vec<NodeId>> = nodes.iter().map(|node| node.extract().ok()).collect();
+    if let (Some(edges), Some(nodes)) = (ids, node_ids) {
+        return Ok((GraphInput { edges, nodes }, None));
     }
 
+    let mut labels = NodeLabels::new(py);
+    let edges = edges
+        .iter()
+        .map(|(from, to, weight)| Ok((labels.intern(from)?, labels.intern(to)?, *weight)))
+        .collect::<PyResult<_>>()?;
+    let nodes = nodes
+        .iter()
+        .map(|node| labels.intern(node))
+        .collect::<PyResult<_>>()?;
+    Ok((GraphInput { edges, nodes }, Some(labels)))
+}
+
+/// Read a CSR or COO sparse matrix into its upper-triangle edges, one node per row
+fn get_sparse_edges(matrix: &Bound<'_, PyAny>) -> PyResult<GraphInput> {
+    let (num_rows, num_cols): (usize, usize) = matrix.getattr("shape")?.extract()?;
+    if num_rows != num_cols {
+        return Err(PyValueError::new_err(format!(
+            "adjacency matrix must be square, got shape ({}, {})",
+            num_rows, num_cols
+        )));
+    }
+
+    let data: Vec<f64> = extract_array(matrix, "data")?;
+    let (rows, cols): (Vec<usize>, Vec<usize>) = if matrix.hasattr("indptr")? {
+        let indptr: Vec<usize> = extract_array(matrix, "indptr")?;
+        let rows = indptr
+            .windows(2)
+            .enumerate()
+            .flat_map(|(row, bounds)| std::iter::repeat_n(row, bounds[1] - bounds[0]))
+            .collect();
+        (rows, extract_array(matrix, "indices")?)
+    } else {
+        (extract_array(matrix, "row")?, extract_array(matrix, "col")?)
+    };
+
+    if rows.len() != data.len() || cols.len() != data.len() {
+        return Err(PyValueError::new_err(
+            "sparse matrix index and data arrays differ in length",
+        ));
+    }
+    let edges = upper_triangle_edges(&rows, &cols, &data).map_err(|index| {
+        PyValueError::new_err(format!("node index {} does not fit in i32", index))
+    })?;
+    let nodes = (0..num_rows)
+        .map(NodeId::try_from)
+        .collect::<Result<_, _>>()
+        .map_err(|_| PyValueError::new_err(format!("{} rows do not fit in i32", num_rows)))?;
+    Ok(GraphInput { edges, nodes })
+}
+
+/// Read a NumPy array attribute (or any sequence) into a Vec
+fn extract_array<'py, T: FromPyObject<'py>>(
+    obj: &Bound<'py, PyAny>,
+    name: &str,
+) -> PyResult<Vec<T>> {
+    let array = obj.getattr(name)?;
+    if array.hasattr("tolist")? {
+        array.call_method0("tolist")?.extract()
+    } else {
+        array.extract()
+    }
+}
+
+/// Nonzero entries with `row <= col` as weighted edges; fails with the first index
+/// that is not a valid NodeId
+fn upper_triangle_edges(
+    rows: &[usize],
+    cols: &[usize],
+    data: &[f64],
+) -> Result<Vec<(NodeId, NodeId, f64)>, usize> {
+    let to_node = |index: usize| NodeId::try_from(index).map_err(|_| index);
+
+    let mut edges = Vec::new();
+    for ((&row, &col), &weight) in rows.iter().zip(cols).zip(data) {
+        if row <= col && weight != 0.0 {
+            edges.push((to_node(row)?, to_node(col)?, weight));
+        }
+    }
     Ok(edges)
 }
 
-/// Build Graph from edges
-pub fn build_graph(edges: Vec<(NodeId, NodeId)>) -> Graph {
-    let mut graph = Graph::new();
-    for (from, to) in edges {
-        graph.add_edge(from, to);
+/// Read a plain `list[tuple[u, v]]` (optionally with a third weight item)
+fn get_edge_list<'py>(edge_list: &Bound<'py, PyAny>) -> PyResult<LabeledEdges<'py>> {
+    let mut edges = Vec::new();
+
+    for edge in edge_list.try_iter()? {
+        let edge = edge?;
+        let from = edge.get_item(0)?;
+        let to = edge.get_item(1)?;
+        let weight = match edge.len()? {
+            2 => 1.0,
+            3 => edge.get_item(2)?.extract()?,
+            len => {
+                return Err(PyValueError::new_err(format!(
+                    "edge list items must be (u, v) or (u, v, weight), got {} values",
+                    len
+                )));
+            }
+        };
+        edges.push((from, to, weight));
+    }
+
+    Ok(edges)
+}
+
+/// Build Graph from (possibly weighted) edges. When `drop_self_loops` is set, `(n, n)` edges
+/// are skipped; returns the graph and the number of self-loops removed. Unless
+/// `allow_parallel` is set, repeated edges are kept once (first weight wins), or with
+/// `parallel_as_weight` merged into one edge carrying the sum of their weights, i.e.
+/// the multiplicity for unit weights. On a `directed` graph `(a, b)` and `(b, a)` are
+/// distinct edges. Nodes listed in `input.nodes` are added even without an edge.
+pub fn build_graph(
+    input: impl Into<GraphInput>,
+    drop_self_loops: bool,
+    allow_parallel: bool,
+    parallel_as_weight: bool,
+    directed: bool,
+) -> (Graph, usize) {
+    let mut graph = if directed {
+        Graph::new_directed()
+    } else {
+        Graph::new()
+    };
+    let GraphInput { edges, nodes } = input.into();
+    for node in nodes {
+        graph.add_node(node);
+    }
+    let fold = parallel_as_weight && !allow_parallel;
+    // Position of each edge in `folded`, kept in order of first appearance
+    let mut folded_index: FxHashMap<(NodeId, NodeId), usize> = FxHashMap::default();
+    let mut folded: Vec<(NodeId, NodeId, f64)> = Vec::new();
+    let mut seen: FxHashSet<(NodeId, NodeId)> = FxHashSet::default();
+    let mut removed = 0;
+    for (from, to, weight) in edges {
+        if drop_self_loops && from == to {
+            graph.add_node(from);
+            removed += 1;
+            continue;
+        }
+        if fold {
+            match folded_index.entry(graph.key(from, to)) {
+                Entry::Occupied(entry) => folded[*entry.get()].2 += weight,
+                Entry::Vacant(entry) => {
+                    entry.insert(folded.len());
+                    folded.push((from, to, weight));
+                }
+            }
+            continue;
+        }
+        if !allow_parallel && !seen.insert(graph.key(from, to)) {
+            continue;
+        }
+        graph.add_weighted_edge(from, to, weight);
+    }
+    for (from, to, weight) in folded {
+        graph.add_weighted_edge(from, to, weight);
+    }
+    (graph, removed)
+}
+
+/// How `build_graph` treated self-loops and repeated edges, kept by the estimators so
+/// that edges added later follow the same rules
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EdgeRules {
+    pub drop_self_loops: bool,
+    pub parallel_as_weight: bool,
+}
+
+/// Adds `from`-`to` to `graph` the way `build_graph` treats each edge under `rules`: a
+/// dropped self-loop only adds its node, and an edge already in the graph gains `weight`
+/// when parallel edges fold into weights and is skipped otherwise. Returns whether the
+/// graph changed.
+pub fn add_edge_with_rules(
+    graph: &mut Graph,
+    from: NodeId,
+    to: NodeId,
+    weight: f64,
+    rules: EdgeRules,
+) -> bool {
+    if rules.drop_self_loops && from == to {
+        return graph.nodes.insert(from);
+    }
+    if !graph.has_edge(from, to) {
+        graph.add_weighted_edge(from, to, weight);
+        return true;
+    }
+    if !rules.parallel_as_weight {
+        return false;
+    }
+    let key = graph.key(from, to);
+    let folded = graph.weight(from, to) + weight;
+    if folded == 1.0 {
+        graph.weights.remove(&key);
+    } else {
+        graph.weights.insert(key, folded);
+    }
+    true
+}
+
+/// Raises ValueError when `graph` is bipartite and `u` or `v` has no entry in its
+/// `node_types`, since a node added by an edge would belong to neither side
+pub fn check_typed_ends(
+    graph: &Graph,
+    labels: Option<&NodeLabels>,
+    u: &Bound<'_, PyAny>,
+    v: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    if graph.node_types.is_empty() {
+        return Ok(());
+    }
+    for node in [u, v] {
+        let id = match labels {
+            Some(labels) => labels.id(node).ok(),
+            None => node.extract::<NodeId>().ok(),
+        };
+        if !id.is_some_and(|id| graph.node_types.contains_key(&id)) {
+            return Err(PyValueError::new_err(format!(
+                "node {} has no entry in node_types",
+                node.repr()?
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::operators::get_modularity_from_partition;
+
+    fn triangle_with_loop() -> Vec<(NodeId, NodeId, f64)> {
+        vec![(0, 1, 1.0), (1, 2, 1.0), (0, 2, 1.0), (0, 0, 1.0)]
+    }
+
+    #[test]
+    fn test_normalize_community_ids_depends_only_on_grouping() {
+        let a: Partition = [(3, 9), (1, 4), (2, 9), (0, 4), (4, 7)]
+            .into_iter()
+            .collect();
+        let b: Partition = [(0, 1), (1, 1), (2, 0), (3, 0), (4, 5)]
+            .into_iter()
+            .collect();
+
+        let expected: Partition = [(0, 0), (1, 0), (2, 1), (3, 1), (4, 2)]
+            .into_iter()
+            .collect();
+        assert_eq!(normalize_community_ids(a), expected);
+        assert_eq!(normalize_community_ids(b), expected);
+    }
+
+    #[test]
+    fn test_front_csv_has_a_row_per_solution() {
+        let partitions: Vec<Partition> = vec![
+            (0..4).map(|node| (node, 0)).collect(),
+            (0..4).map(|node| (node, node / 2)).collect(),
+        ];
+        let objectives = [vec![0.25, 0.5], vec![0.5, 0.125]];
+        let csv = front_csv(partitions.iter().zip(objectives.iter().map(Vec::as_slice)));
+
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("intra,inter,modularity,num_communities"));
+        let rows: Vec<Vec<f64>> = lines
+            .map(|line| {
+                line.split(',')
+                    .map(|field| field.parse().unwrap())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(rows, [[0.25, 0.5, 0.25, 1.0], [0.5, 0.125, 0.375, 2.0]]);
+    }
+
+    #[test]
+    fn test_build_graph_drops_self_loops() {
+        let (graph, removed) = build_graph(triangle_with_loop(), true, false, false, false);
+
+        assert_eq!(removed, 1);
+        assert_eq!(graph.num_edges(), 3);
+        assert_eq!(graph.degree_sequence(), vec![2, 2, 2]);
+    }
+
+    #[test]
+    fn test_build_graph_keeps_self_loops() {
+        let (graph, removed) = build_graph(triangle_with_loop(), false, false, false, false);
+        let partition: Partition = [(0, 0), (1, 0), (2, 1)].into_iter().collect();
+
+        // A self-loop adds 2 to the degree and counts once as an intra-community edge:
+        // m = 4, intra = 1 - 2/4, inter = (6/8)^2 + (2/8)^2
+        assert_eq!(removed, 0);
+        assert_eq!(graph.degree_sequence(), vec![4, 2, 2]);
+        let q = get_modularity_from_partition(&partition, &graph, 1.0);
+        assert!((q + 0.125).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_parallel_weighted_self_loops_in_modularity() {
+        // Triangle 0-1-2, pendant 2-3 and two unit self-loops on 3. Folded, the loops
+        // weigh 2 and add 4 to the strength of 3: m = 6, D = (7, 5), L_in = (3, 2),
+        // Q = 5/6 - (7^2 + 5^2) / 12^2
+        let edges = vec![
+            (0, 1, 1.0),
+            (1, 2, 1.0),
+            (0, 2, 1.0),
+            (2, 3, 1.0),
+            (3, 3, 1.0),
+            (3, 3, 1.0),
+        ];
+        let partition: Partition = [(0, 0), (1, 0), (2, 0), (3, 1)].into_iter().collect();
+
+        let (folded, _) = build_graph(edges.clone(), false, false, true, false);
+        assert_eq!(folded.weight(3, 3), 2.0);
+        let q = get_modularity_from_partition(&partition, &folded, 1.0);
+        assert!((q - 23.0 / 72.0).abs() < 1e-12);
+
+        // Deduplicated, one unit loop is left: m = 5, D = (7, 3), Q = 4/5 - 58/100
+        let (deduplicated, _) = build_graph(edges, false, false, false, false);
+        let q = get_modularity_from_partition(&partition, &deduplicated, 1.0);
+        assert!((q - 0.22).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_upper_triangle_edges_from_coo() {
+        // Symmetric 3x3 COO matrix: weighted 0-1, 1-2 plus an explicit zero and a loop
+        let rows = [0, 1, 1, 2, 0, 2];
+        let cols = [1, 0, 2, 1, 2, 2];
+        let data = [2.0, 2.0, 1.0, 1.0, 0.0, 3.0];
+
+        let edges = upper_triangle_edges(&rows, &cols, &data).unwrap();
+        assert_eq!(edges, vec![(0, 1, 2.0), (1, 2, 1.0), (2, 2, 3.0)]);
+
+        let (graph, removed) = build_graph(edges, true, false, false, false);
+        assert_eq!(removed, 1);
+        assert_eq!(graph.num_edges(), 2);
+        assert_eq!(graph.weight(1, 0), 2.0);
+    }
+
+    #[test]
+    fn test_build_graph_keeps_isolated_nodes() {
+        // An edgeless graph, and one isolated node next to the edge 0-1
+        let empty = GraphInput {
+            edges: Vec::new(),
+            nodes: vec![0, 1, 2],
+        };
+        let (graph, _) = build_graph(empty, true, false, false, false);
+        assert_eq!(graph.num_nodes(), 3);
+        assert_eq!(graph.num_edges(), 0);
+
+        let input = GraphInput {
+            edges: vec![(0, 1, 1.0)],
+            nodes: vec![0, 1, 2],
+        };
+        let (graph, _) = build_graph(input, true, false, false, false);
+        assert_eq!(graph.num_nodes(), 3);
+        assert_eq!(graph.degree_sequence(), vec![1, 1, 0]);
+    }
+
+    #[test]
+    fn test_add_edge_with_rules_matches_build_graph() {
+        let edges = vec![
+            (0, 1, 1.0),
+            (1, 2, 1.0),
+            (2, 2, 1.0),
+            (1, 0, 1.0),
+            (3, 3, 1.0),
+        ];
+        for (drop_self_loops, parallel_as_weight) in
+            [(true, false), (false, false), (true, true), (false, true)]
+        {
+            let rules = EdgeRules {
+                drop_self_loops,
+                parallel_as_weight,
+            };
+            let (built, _) = build_graph(
+                edges.clone(),
+                drop_self_loops,
+                false,
+                parallel_as_weight,
+                false,
+            );
+            let mut grown = Graph::new();
+            for &(from, to, weight) in &edges {
+                add_edge_with_rules(&mut grown, from, to, weight, rules);
+            }
+            assert_eq!(grown.sorted_nodes(), built.sorted_nodes());
+            assert_eq!(grown.num_edges(), built.num_edges());
+            assert_eq!(grown.weights, built.weights);
+        }
+
+        let mut graph = Graph::new();
+        let rules = EdgeRules {
+            drop_self_loops: true,
+            parallel_as_weight: false,
+        };
+        assert!(add_edge_with_rules(&mut graph, 0, 1, 1.0, rules));
+        assert!(!add_edge_with_rules(&mut graph, 1, 0, 1.0, rules));
+        assert!(!add_edge_with_rules(&mut graph, 1, 1, 1.0, rules));
+        assert!(add_edge_with_rules(&mut graph, 2, 2, 1.0, rules));
+        assert_eq!(graph.degree_sequence(), vec![1, 1, 0]);
+    }
+
+    #[test]
+    fn test_build_graph_deduplicates_parallel_edges() {
+        let edges = vec![(0, 1, 1.0), (1, 0, 1.0), (0, 1, 1.0)];
+
+        let (graph, _) = build_graph(edges.clone(), true, false, false, false);
+        assert_eq!(graph.num_edges(), 1);
+        assert_eq!(graph.neighbors(&0), &[1]);
+        assert_eq!(graph.degree_sequence(), vec![1, 1]);
+
+        let (multigraph, _) = build_graph(edges, true, true, false, false);
+        assert_eq!(multigraph.num_edges(), 3);
+    }
+
+    #[test]
+    fn test_build_graph_folds_parallel_edges_into_weights() {
+        let edges = vec![(0, 1, 1.0), (1, 2, 1.0), (1, 0, 1.0), (0, 1, 1.0)];
+
+        let (graph, _) = build_graph(edges.clone(), true, false, true, false);
+        assert_eq!(graph.num_edges(), 2);
+        assert_eq!(graph.weight(0, 1), 3.0);
+        assert_eq!(graph.weight(1, 2), 1.0);
+        assert_eq!(graph.degree_sequence(), vec![1, 2, 1]);
+
+        let (directed, _) = build_graph(edges, true, false, true, true);
+        assert_eq!(directed.weight(0, 1), 2.0);
+        assert_eq!(directed.weight(1, 0), 1.0);
+    }
+
+    #[test]
+    fn test_read_edge_file() {
+        let path = std::env::temp_dir().join(format!("pymocd-edges-{}", std::process::id()));
+        let read = |contents: &str, delimiter| {
+            std::fs::write(&path, contents).unwrap();
+            read_edge_list(BufReader::new(File::open(&path).unwrap()), delimiter)
+        };
+
+        let edges = read(
+            "# source,target\n0, 1\n\n1,2,0.5\n  # indented comment\n2,0\n",
+            Some(","),
+        );
+        assert_eq!(edges.unwrap(), vec![(0, 1, 1.0), (1, 2, 0.5), (2, 0, 1.0)]);
+        assert_eq!(
+            read("0 1\n1\t2\n", None).unwrap(),
+            vec![(0, 1, 1.0), (1, 2, 1.0)]
+        );
+
+        let err = read("0 1\n# comment\n1 two\n", None).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "line 3: 'two' is not an integer node id");
+        let err = read("0,1,2,3\n", Some(",")).unwrap_err();
+        assert_eq!(err.to_string(), "line 1: expected 2 or 3 columns, got 4");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_community_sets_cover_nodes_disjointly() {
+        let partition: Partition = [(4, 7), (1, 3), (9, 7), (2, 5), (6, 3)]
+            .into_iter()
+            .collect();
+        let sets = community_sets(partition.clone());
+        assert_eq!(sets, vec![vec![1, 6], vec![2], vec![4, 9]]);
+
+        let mut union: Vec<NodeId> = sets.concat();
+        union.sort_unstable();
+        let nodes: Vec<NodeId> = partition.keys().copied().collect();
+        assert_eq!(union, nodes, "every node appears in exactly one set");
+    }
+
+    #[test]
+    fn test_missing_nodes_are_sorted() {
+        let (graph, _) = build_graph(triangle_with_loop(), true, false, false, false);
+        let assigned: FxHashSet<NodeId> = [2, 0, 7].into_iter().collect();
+
+        assert_eq!(missing_nodes(&graph, &assigned), vec![1]);
+        assert!(missing_nodes(&graph, &graph.nodes.iter().copied().collect()).is_empty());
+    }
+
+    #[test]
+    fn test_partition_records_are_sorted_by_node() {
+        let partition: Partition = [(4, 7), (1, 3), (9, 7), (2, 5), (6, 3)]
+            .into_iter()
+            .collect();
+        let records = partition_records(partition.clone());
+
+        assert_eq!(records.len(), partition.len());
+        assert!(records.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(records, vec![(1, 0), (2, 1), (4, 2), (6, 0), (9, 2)]);
     }
-    graph
 }